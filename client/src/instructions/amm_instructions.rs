@@ -9,8 +9,8 @@ use solana_sdk::{
 use raydium_amm_v3::accounts as raydium_accounts;
 use raydium_amm_v3::instruction as raydium_instruction;
 use raydium_amm_v3::states::{
-    AMM_CONFIG_SEED, OBSERVATION_SEED, OPERATION_SEED, POOL_SEED, POOL_VAULT_SEED, POSITION_SEED,
-    TICK_ARRAY_SEED,
+    AMM_CONFIG_SEED, LIMIT_ORDER_SEED, OBSERVATION_SEED, OPERATION_SEED, POOL_SEED,
+    POOL_VAULT_SEED, POSITION_SEED, TICK_ARRAY_SEED,
 };
 use std::rc::Rc;
 
@@ -23,6 +23,7 @@ pub fn create_amm_config_instr(
     trade_fee_rate: u32,
     protocol_fee_rate: u32,
     fund_fee_rate: u32,
+    creator_fee_rate: Option<u32>,
 ) -> Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.admin_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
@@ -33,21 +34,34 @@ pub fn create_amm_config_instr(
         &[AMM_CONFIG_SEED.as_bytes(), &config_index.to_be_bytes()],
         &program.id(),
     );
-    let instructions = program
+    let request = program
         .request()
         .accounts(raydium_accounts::CreateAmmConfig {
             owner: program.payer(),
             amm_config: amm_config_key,
             system_program: system_program::id(),
-        })
-        .args(raydium_instruction::CreateAmmConfig {
-            index: config_index,
-            tick_spacing,
-            trade_fee_rate,
-            protocol_fee_rate,
-            fund_fee_rate,
-        })
-        .instructions()?;
+        });
+    let instructions = match creator_fee_rate {
+        Some(creator_fee_rate) => request
+            .args(raydium_instruction::CreateAmmConfigWithCreatorFee {
+                index: config_index,
+                tick_spacing,
+                trade_fee_rate,
+                protocol_fee_rate,
+                fund_fee_rate,
+                creator_fee_rate,
+            })
+            .instructions()?,
+        None => request
+            .args(raydium_instruction::CreateAmmConfig {
+                index: config_index,
+                tick_spacing,
+                trade_fee_rate,
+                protocol_fee_rate,
+                fund_fee_rate,
+            })
+            .instructions()?,
+    };
     Ok(instructions)
 }
 
@@ -401,6 +415,110 @@ pub fn open_position_with_token22_nft_instr(
     Ok(instructions)
 }
 
+pub fn open_position_single_sided_instr(
+    config: &ClientConfig,
+    amm_config: Pubkey,
+    pool_account_key: Pubkey,
+    observation_state: Pubkey,
+    token_vault_0: Pubkey,
+    token_vault_1: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    nft_mint_key: Pubkey,
+    nft_to_owner: Pubkey,
+    user_token_account_0: Pubkey,
+    user_token_account_1: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+    amount: u64,
+    base_flag: bool,
+    liquidity_min: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    with_metadata: bool,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program)?;
+    let nft_ata_token_account =
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            &program.payer(),
+            &nft_mint_key,
+            &spl_token_2022::id(),
+        );
+    let (protocol_position_key, __bump) = Pubkey::find_program_address(
+        &[
+            POSITION_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_lower, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (tick_array_upper, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let (personal_position_key, __bump) = Pubkey::find_program_address(
+        &[POSITION_SEED.as_bytes(), nft_mint_key.to_bytes().as_ref()],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::OpenPositionSingleSided {
+            payer: program.payer(),
+            position_nft_owner: nft_to_owner,
+            position_nft_mint: nft_mint_key,
+            position_nft_account: nft_ata_token_account,
+            amm_config,
+            pool_state: pool_account_key,
+            protocol_position: protocol_position_key,
+            tick_array_lower,
+            tick_array_upper,
+            personal_position: personal_position_key,
+            observation_state,
+            token_account_0: user_token_account_0,
+            token_account_1: user_token_account_1,
+            token_vault_0,
+            token_vault_1,
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            token_program_2022: spl_token_2022::id(),
+            vault_0_mint: token_mint_0,
+            vault_1_mint: token_mint_1,
+        })
+        .accounts(remaining_accounts)
+        .args(raydium_instruction::OpenPositionSingleSided {
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            amount,
+            base_flag,
+            liquidity_min,
+            with_metadata,
+        })
+        .instructions()?;
+    Ok(instructions)
+}
+
 pub fn increase_liquidity_instr(
     config: &ClientConfig,
     pool_account_key: Pubkey,
@@ -615,13 +733,14 @@ pub fn swap_instr(
     other_amount_threshold: u64,
     sqrt_price_limit_x64: Option<u128>,
     is_base_input: bool,
+    deadline: Option<i64>,
 ) -> Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
     // Client.
     let client = Client::new(url, Rc::new(payer));
     let program = client.program(config.raydium_v3_program)?;
-    let instructions = program
+    let request = program
         .request()
         .accounts(raydium_accounts::SwapSingle {
             payer: program.payer(),
@@ -635,14 +754,27 @@ pub fn swap_instr(
             observation_state,
             token_program: spl_token::id(),
         })
-        .accounts(remaining_accounts)
-        .args(raydium_instruction::Swap {
-            amount,
-            other_amount_threshold,
-            sqrt_price_limit_x64: sqrt_price_limit_x64.unwrap_or(0u128),
-            is_base_input,
-        })
-        .instructions()?;
+        .accounts(remaining_accounts);
+    // the deadline is checked by a separate instruction, the original one keeps its arguments
+    let instructions = match deadline {
+        Some(deadline) => request
+            .args(raydium_instruction::SwapWithDeadline {
+                amount,
+                other_amount_threshold,
+                sqrt_price_limit_x64: sqrt_price_limit_x64.unwrap_or(0u128),
+                is_base_input,
+                deadline,
+            })
+            .instructions()?,
+        None => request
+            .args(raydium_instruction::Swap {
+                amount,
+                other_amount_threshold,
+                sqrt_price_limit_x64: sqrt_price_limit_x64.unwrap_or(0u128),
+                is_base_input,
+            })
+            .instructions()?,
+    };
     Ok(instructions)
 }
 
@@ -662,13 +794,14 @@ pub fn swap_v2_instr(
     other_amount_threshold: u64,
     sqrt_price_limit_x64: Option<u128>,
     is_base_input: bool,
+    deadline: Option<i64>,
 ) -> Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
     // Client.
     let client = Client::new(url, Rc::new(payer));
     let program = client.program(config.raydium_v3_program)?;
-    let instructions = program
+    let request = program
         .request()
         .accounts(raydium_accounts::SwapSingleV2 {
             payer: program.payer(),
@@ -685,17 +818,212 @@ pub fn swap_v2_instr(
             input_vault_mint,
             output_vault_mint,
         })
+        .accounts(remaining_accounts);
+    let instructions = match deadline {
+        Some(deadline) => request
+            .args(raydium_instruction::SwapV2WithDeadline {
+                amount,
+                other_amount_threshold,
+                sqrt_price_limit_x64: sqrt_price_limit_x64.unwrap_or(0u128),
+                is_base_input,
+                deadline,
+            })
+            .instructions()?,
+        None => request
+            .args(raydium_instruction::SwapV2 {
+                amount,
+                other_amount_threshold,
+                sqrt_price_limit_x64: sqrt_price_limit_x64.unwrap_or(0u128),
+                is_base_input,
+            })
+            .instructions()?,
+    };
+    Ok(instructions)
+}
+
+fn limit_order_key(
+    program_id: &Pubkey,
+    pool_account_key: Pubkey,
+    owner: Pubkey,
+    tick_index: i32,
+    zero_for_one: bool,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            LIMIT_ORDER_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            owner.to_bytes().as_ref(),
+            &tick_index.to_be_bytes(),
+            &[zero_for_one as u8],
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn open_limit_order_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    input_vault: Pubkey,
+    input_vault_mint: Pubkey,
+    user_input_token: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+    tick_index: i32,
+    tick_array_start_index: i32,
+    zero_for_one: bool,
+    amount: u64,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program)?;
+    let limit_order = limit_order_key(
+        &program.id(),
+        pool_account_key,
+        program.payer(),
+        tick_index,
+        zero_for_one,
+    );
+    let (tick_array, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::OpenLimitOrder {
+            owner: program.payer(),
+            pool_state: pool_account_key,
+            limit_order,
+            tick_array,
+            input_token_account: user_input_token,
+            input_vault,
+            input_vault_mint,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            system_program: system_program::id(),
+        })
         .accounts(remaining_accounts)
-        .args(raydium_instruction::SwapV2 {
+        .args(raydium_instruction::OpenLimitOrder {
+            tick_index,
+            tick_array_start_index,
+            zero_for_one,
             amount,
-            other_amount_threshold,
-            sqrt_price_limit_x64: sqrt_price_limit_x64.unwrap_or(0u128),
-            is_base_input,
         })
         .instructions()?;
     Ok(instructions)
 }
 
+pub fn cancel_limit_order_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    token_vault_0: Pubkey,
+    token_vault_1: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    user_token_account_0: Pubkey,
+    user_token_account_1: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+    tick_index: i32,
+    tick_array_start_index: i32,
+    zero_for_one: bool,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program)?;
+    let limit_order = limit_order_key(
+        &program.id(),
+        pool_account_key,
+        program.payer(),
+        tick_index,
+        zero_for_one,
+    );
+    let (tick_array, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::CancelLimitOrder {
+            owner: program.payer(),
+            pool_state: pool_account_key,
+            limit_order,
+            tick_array,
+            recipient_token_account_0: user_token_account_0,
+            recipient_token_account_1: user_token_account_1,
+            token_vault_0,
+            token_vault_1,
+            vault_0_mint: token_mint_0,
+            vault_1_mint: token_mint_1,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+        })
+        .accounts(remaining_accounts)
+        .args(raydium_instruction::CancelLimitOrder)
+        .instructions()?;
+    Ok(instructions)
+}
+
+pub fn claim_limit_order_instr(
+    config: &ClientConfig,
+    pool_account_key: Pubkey,
+    output_vault: Pubkey,
+    output_vault_mint: Pubkey,
+    user_output_token: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+    tick_index: i32,
+    tick_array_start_index: i32,
+    zero_for_one: bool,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_v3_program)?;
+    let limit_order = limit_order_key(
+        &program.id(),
+        pool_account_key,
+        program.payer(),
+        tick_index,
+        zero_for_one,
+    );
+    let (tick_array, __bump) = Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_account_key.to_bytes().as_ref(),
+            &tick_array_start_index.to_be_bytes(),
+        ],
+        &program.id(),
+    );
+    let instructions = program
+        .request()
+        .accounts(raydium_accounts::ClaimLimitOrder {
+            owner: program.payer(),
+            pool_state: pool_account_key,
+            limit_order,
+            tick_array,
+            recipient_token_account: user_output_token,
+            output_vault,
+            output_vault_mint,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+        })
+        .accounts(remaining_accounts)
+        .args(raydium_instruction::ClaimLimitOrder)
+        .instructions()?;
+    Ok(instructions)
+}
+
 pub fn initialize_reward_instr(
     config: &ClientConfig,
     pool_account_key: Pubkey,
@@ -155,6 +155,51 @@ pub fn handle_program_log(
                     decode_event::<CollectProtocolFeeEvent>(&mut slice)?
                 );
             }
+            ObservationDurationChangeEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<ObservationDurationChangeEvent>(&mut slice)?
+                );
+            }
+            RewardRangeChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<RewardRangeChangeEvent>(&mut slice)?);
+            }
+            PoolOpenTimeChangeEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<PoolOpenTimeChangeEvent>(&mut slice)?
+                );
+            }
+            PoolSwapHookChangeEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<PoolSwapHookChangeEvent>(&mut slice)?
+                );
+            }
+            PoolTradeFeeRateChangeEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<PoolTradeFeeRateChangeEvent>(&mut slice)?
+                );
+            }
+            PoolTickDeviationChangeEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<PoolTickDeviationChangeEvent>(&mut slice)?
+                );
+            }
+            CollectCreatorFeeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<CollectCreatorFeeEvent>(&mut slice)?);
+            }
+            CreatorFeeRateChangeEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<CreatorFeeRateChangeEvent>(&mut slice)?
+                );
+            }
+            DynamicFeeChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<DynamicFeeChangeEvent>(&mut slice)?);
+            }
             CreatePersonalPositionEvent::DISCRIMINATOR => {
                 println!(
                     "{:#?}",
@@ -167,6 +212,24 @@ pub fn handle_program_log(
             IncreaseLiquidityEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<IncreaseLiquidityEvent>(&mut slice)?);
             }
+            CompoundPositionEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<CompoundPositionEvent>(&mut slice)?);
+            }
+            RebalancePositionEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<RebalancePositionEvent>(&mut slice)?);
+            }
+            GrantPositionDelegateEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<GrantPositionDelegateEvent>(&mut slice)?
+                );
+            }
+            RevokePositionDelegateEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<RevokePositionDelegateEvent>(&mut slice)?
+                );
+            }
             LiquidityCalculateEvent::DISCRIMINATOR => {
                 println!(
                     "{:#?}",
@@ -182,9 +245,42 @@ pub fn handle_program_log(
             SwapEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<SwapEvent>(&mut slice)?);
             }
+            FlashEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<FlashEvent>(&mut slice)?);
+            }
+            FundRewardEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<FundRewardEvent>(&mut slice)?);
+            }
+            RefundRewardFunderEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<RefundRewardFunderEvent>(&mut slice)?
+                );
+            }
+            CollectExtensionRewardEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<CollectExtensionRewardEvent>(&mut slice)?
+                );
+            }
             PoolCreatedEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<PoolCreatedEvent>(&mut slice)?);
             }
+            PoolAmmConfigChangeEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<PoolAmmConfigChangeEvent>(&mut slice)?
+                );
+            }
+            OpenLimitOrderEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<OpenLimitOrderEvent>(&mut slice)?);
+            }
+            CancelLimitOrderEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<CancelLimitOrderEvent>(&mut slice)?);
+            }
+            ClaimLimitOrderEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<ClaimLimitOrderEvent>(&mut slice)?);
+            }
             _ => {
                 println!("unknow event: {}", l);
             }
@@ -375,6 +471,34 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", CreateAmmConfig::from(ix));
         }
+        instruction::CreateAmmConfigWithCreatorFee::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CreateAmmConfigWithCreatorFee>(&mut ix_data)
+                .unwrap();
+            #[derive(Debug)]
+            pub struct CreateAmmConfigWithCreatorFee {
+                pub index: u16,
+                pub tick_spacing: u16,
+                pub trade_fee_rate: u32,
+                pub protocol_fee_rate: u32,
+                pub fund_fee_rate: u32,
+                pub creator_fee_rate: u32,
+            }
+            impl From<instruction::CreateAmmConfigWithCreatorFee> for CreateAmmConfigWithCreatorFee {
+                fn from(
+                    instr: instruction::CreateAmmConfigWithCreatorFee,
+                ) -> CreateAmmConfigWithCreatorFee {
+                    CreateAmmConfigWithCreatorFee {
+                        index: instr.index,
+                        tick_spacing: instr.tick_spacing,
+                        trade_fee_rate: instr.trade_fee_rate,
+                        protocol_fee_rate: instr.protocol_fee_rate,
+                        fund_fee_rate: instr.fund_fee_rate,
+                        creator_fee_rate: instr.creator_fee_rate,
+                    }
+                }
+            }
+            println!("{:#?}", CreateAmmConfigWithCreatorFee::from(ix));
+        }
         instruction::UpdateAmmConfig::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::UpdateAmmConfig>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -409,6 +533,21 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", CreatePool::from(ix));
         }
+        instruction::SetPoolOpenTime::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SetPoolOpenTime>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SetPoolOpenTime {
+                pub open_time: u64,
+            }
+            impl From<instruction::SetPoolOpenTime> for SetPoolOpenTime {
+                fn from(instr: instruction::SetPoolOpenTime) -> SetPoolOpenTime {
+                    SetPoolOpenTime {
+                        open_time: instr.open_time,
+                    }
+                }
+            }
+            println!("{:#?}", SetPoolOpenTime::from(ix));
+        }
         instruction::UpdatePoolStatus::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::UpdatePoolStatus>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -424,6 +563,86 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", UpdatePoolStatus::from(ix));
         }
+        instruction::UpdateObservationDuration::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::UpdateObservationDuration>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdateObservationDuration {
+                pub update_duration: u32,
+            }
+            impl From<instruction::UpdateObservationDuration> for UpdateObservationDuration {
+                fn from(
+                    instr: instruction::UpdateObservationDuration,
+                ) -> UpdateObservationDuration {
+                    UpdateObservationDuration {
+                        update_duration: instr.update_duration,
+                    }
+                }
+            }
+            println!("{:#?}", UpdateObservationDuration::from(ix));
+        }
+        instruction::UpdatePoolTickDeviation::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::UpdatePoolTickDeviation>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolTickDeviation {
+                pub max_tick_deviation: u32,
+                pub tick_deviation_period: u32,
+            }
+            impl From<instruction::UpdatePoolTickDeviation> for UpdatePoolTickDeviation {
+                fn from(instr: instruction::UpdatePoolTickDeviation) -> UpdatePoolTickDeviation {
+                    UpdatePoolTickDeviation {
+                        max_tick_deviation: instr.max_tick_deviation,
+                        tick_deviation_period: instr.tick_deviation_period,
+                    }
+                }
+            }
+            println!("{:#?}", UpdatePoolTickDeviation::from(ix));
+        }
+        instruction::UpdatePoolTradeFeeRate::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::UpdatePoolTradeFeeRate>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolTradeFeeRate {
+                pub trade_fee_rate: Option<u32>,
+            }
+            impl From<instruction::UpdatePoolTradeFeeRate> for UpdatePoolTradeFeeRate {
+                fn from(instr: instruction::UpdatePoolTradeFeeRate) -> UpdatePoolTradeFeeRate {
+                    UpdatePoolTradeFeeRate {
+                        trade_fee_rate: instr.trade_fee_rate,
+                    }
+                }
+            }
+            println!("{:#?}", UpdatePoolTradeFeeRate::from(ix));
+        }
+        instruction::UpdatePoolAmmConfig::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::UpdatePoolAmmConfig>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolAmmConfig;
+            impl From<instruction::UpdatePoolAmmConfig> for UpdatePoolAmmConfig {
+                fn from(_instr: instruction::UpdatePoolAmmConfig) -> UpdatePoolAmmConfig {
+                    UpdatePoolAmmConfig
+                }
+            }
+            println!("{:#?}", UpdatePoolAmmConfig::from(ix));
+        }
+        instruction::UpdatePoolSwapHook::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::UpdatePoolSwapHook>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolSwapHook {
+                pub swap_hook_program: Option<Pubkey>,
+                pub swap_hook_account_count: u8,
+            }
+            impl From<instruction::UpdatePoolSwapHook> for UpdatePoolSwapHook {
+                fn from(instr: instruction::UpdatePoolSwapHook) -> UpdatePoolSwapHook {
+                    UpdatePoolSwapHook {
+                        swap_hook_program: instr.swap_hook_program,
+                        swap_hook_account_count: instr.swap_hook_account_count,
+                    }
+                }
+            }
+            println!("{:#?}", UpdatePoolSwapHook::from(ix));
+        }
         instruction::CreateOperationAccount::DISCRIMINATOR => {
             let ix =
                 decode_instruction::<instruction::CreateOperationAccount>(&mut ix_data).unwrap();
@@ -482,6 +701,113 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", InitializeReward::from(ix));
         }
+        instruction::InitializeRewardExtension::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::InitializeRewardExtension>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct InitializeRewardExtension;
+            impl From<instruction::InitializeRewardExtension> for InitializeRewardExtension {
+                fn from(
+                    _instr: instruction::InitializeRewardExtension,
+                ) -> InitializeRewardExtension {
+                    InitializeRewardExtension
+                }
+            }
+            println!("{:#?}", InitializeRewardExtension::from(ix));
+        }
+        instruction::InitializeTickArrayRewardExtension::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::InitializeTickArrayRewardExtension>(&mut ix_data)
+                    .unwrap();
+            #[derive(Debug)]
+            pub struct InitializeTickArrayRewardExtension {
+                pub tick_array_start_index: i32,
+            }
+            impl From<instruction::InitializeTickArrayRewardExtension> for InitializeTickArrayRewardExtension {
+                fn from(
+                    instr: instruction::InitializeTickArrayRewardExtension,
+                ) -> InitializeTickArrayRewardExtension {
+                    InitializeTickArrayRewardExtension {
+                        tick_array_start_index: instr.tick_array_start_index,
+                    }
+                }
+            }
+            println!("{:#?}", InitializeTickArrayRewardExtension::from(ix));
+        }
+        instruction::InitializePositionRewardExtension::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::InitializePositionRewardExtension>(&mut ix_data)
+                    .unwrap();
+            #[derive(Debug)]
+            pub struct InitializePositionRewardExtension;
+            impl From<instruction::InitializePositionRewardExtension> for InitializePositionRewardExtension {
+                fn from(
+                    _instr: instruction::InitializePositionRewardExtension,
+                ) -> InitializePositionRewardExtension {
+                    InitializePositionRewardExtension
+                }
+            }
+            println!("{:#?}", InitializePositionRewardExtension::from(ix));
+        }
+        instruction::FundReward::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::FundReward>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct FundReward {
+                pub reward_index: u8,
+                pub amount: u64,
+                pub extend_end_time: bool,
+            }
+            impl From<instruction::FundReward> for FundReward {
+                fn from(instr: instruction::FundReward) -> FundReward {
+                    FundReward {
+                        reward_index: instr.reward_index,
+                        amount: instr.amount,
+                        extend_end_time: instr.extend_end_time,
+                    }
+                }
+            }
+            println!("{:#?}", FundReward::from(ix));
+        }
+        instruction::SetRewardRange::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SetRewardRange>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SetRewardRange {
+                pub reward_index: u8,
+                pub enabled: bool,
+                pub tick_lower: i32,
+                pub tick_upper: i32,
+            }
+            impl From<instruction::SetRewardRange> for SetRewardRange {
+                fn from(instr: instruction::SetRewardRange) -> SetRewardRange {
+                    SetRewardRange {
+                        reward_index: instr.reward_index,
+                        enabled: instr.enabled,
+                        tick_lower: instr.tick_lower,
+                        tick_upper: instr.tick_upper,
+                    }
+                }
+            }
+            println!("{:#?}", SetRewardRange::from(ix));
+        }
+        instruction::SetRewardSchedule::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SetRewardSchedule>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SetRewardSchedule {
+                pub reward_index: u8,
+                pub points: Vec<RewardSchedulePoint>,
+                pub end_time: u64,
+            }
+            impl From<instruction::SetRewardSchedule> for SetRewardSchedule {
+                fn from(instr: instruction::SetRewardSchedule) -> SetRewardSchedule {
+                    SetRewardSchedule {
+                        reward_index: instr.reward_index,
+                        points: instr.points,
+                        end_time: instr.end_time,
+                    }
+                }
+            }
+            println!("{:#?}", SetRewardSchedule::from(ix));
+        }
         instruction::CollectRemainingRewards::DISCRIMINATOR => {
             let ix =
                 decode_instruction::<instruction::CollectRemainingRewards>(&mut ix_data).unwrap();
@@ -498,6 +824,23 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", CollectRemainingRewards::from(ix));
         }
+        instruction::RefundRewardFunders::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::RefundRewardFunders>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct RefundRewardFunders {
+                pub reward_index: u8,
+                pub funder_count: u8,
+            }
+            impl From<instruction::RefundRewardFunders> for RefundRewardFunders {
+                fn from(instr: instruction::RefundRewardFunders) -> RefundRewardFunders {
+                    RefundRewardFunders {
+                        reward_index: instr.reward_index,
+                        funder_count: instr.funder_count,
+                    }
+                }
+            }
+            println!("{:#?}", RefundRewardFunders::from(ix));
+        }
         instruction::UpdateRewardInfos::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::UpdateRewardInfos>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -564,6 +907,23 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", CollectFundFee::from(ix));
         }
+        instruction::CollectCreatorFee::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CollectCreatorFee>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CollectCreatorFee {
+                pub amount_0_requested: u64,
+                pub amount_1_requested: u64,
+            }
+            impl From<instruction::CollectCreatorFee> for CollectCreatorFee {
+                fn from(instr: instruction::CollectCreatorFee) -> CollectCreatorFee {
+                    CollectCreatorFee {
+                        amount_0_requested: instr.amount_0_requested,
+                        amount_1_requested: instr.amount_1_requested,
+                    }
+                }
+            }
+            println!("{:#?}", CollectCreatorFee::from(ix));
+        }
         instruction::OpenPosition::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::OpenPosition>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -622,6 +982,110 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", OpenPositionV2::from(ix));
         }
+        instruction::OpenPositionV2WithDeadline::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::OpenPositionV2WithDeadline>(&mut ix_data)
+                .unwrap();
+            #[derive(Debug)]
+            pub struct OpenPositionV2WithDeadline {
+                pub tick_lower_index: i32,
+                pub tick_upper_index: i32,
+                pub tick_array_lower_start_index: i32,
+                pub tick_array_upper_start_index: i32,
+                pub liquidity: u128,
+                pub amount_0_max: u64,
+                pub amount_1_max: u64,
+                pub base_flag: Option<bool>,
+                pub with_metadata: bool,
+                pub deadline: i64,
+            }
+            impl From<instruction::OpenPositionV2WithDeadline> for OpenPositionV2WithDeadline {
+                fn from(
+                    instr: instruction::OpenPositionV2WithDeadline,
+                ) -> OpenPositionV2WithDeadline {
+                    OpenPositionV2WithDeadline {
+                        tick_lower_index: instr.tick_lower_index,
+                        tick_upper_index: instr.tick_upper_index,
+                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
+                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
+                        liquidity: instr.liquidity,
+                        amount_0_max: instr.amount_0_max,
+                        amount_1_max: instr.amount_1_max,
+                        base_flag: instr.base_flag,
+                        with_metadata: instr.with_metadata,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", OpenPositionV2WithDeadline::from(ix));
+        }
+        instruction::OpenPositionSingleSided::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::OpenPositionSingleSided>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct OpenPositionSingleSided {
+                pub tick_lower_index: i32,
+                pub tick_upper_index: i32,
+                pub tick_array_lower_start_index: i32,
+                pub tick_array_upper_start_index: i32,
+                pub amount: u64,
+                pub base_flag: bool,
+                pub liquidity_min: u128,
+                pub with_metadata: bool,
+            }
+            impl From<instruction::OpenPositionSingleSided> for OpenPositionSingleSided {
+                fn from(instr: instruction::OpenPositionSingleSided) -> OpenPositionSingleSided {
+                    OpenPositionSingleSided {
+                        tick_lower_index: instr.tick_lower_index,
+                        tick_upper_index: instr.tick_upper_index,
+                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
+                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
+                        amount: instr.amount,
+                        base_flag: instr.base_flag,
+                        liquidity_min: instr.liquidity_min,
+                        with_metadata: instr.with_metadata,
+                    }
+                }
+            }
+            println!("{:#?}", OpenPositionSingleSided::from(ix));
+        }
+        instruction::OpenPositionSingleSidedWithDeadline::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::OpenPositionSingleSidedWithDeadline>(
+                &mut ix_data,
+            )
+            .unwrap();
+            #[derive(Debug)]
+            pub struct OpenPositionSingleSidedWithDeadline {
+                pub tick_lower_index: i32,
+                pub tick_upper_index: i32,
+                pub tick_array_lower_start_index: i32,
+                pub tick_array_upper_start_index: i32,
+                pub amount: u64,
+                pub base_flag: bool,
+                pub liquidity_min: u128,
+                pub with_metadata: bool,
+                pub deadline: i64,
+            }
+            impl From<instruction::OpenPositionSingleSidedWithDeadline>
+                for OpenPositionSingleSidedWithDeadline
+            {
+                fn from(
+                    instr: instruction::OpenPositionSingleSidedWithDeadline,
+                ) -> OpenPositionSingleSidedWithDeadline {
+                    OpenPositionSingleSidedWithDeadline {
+                        tick_lower_index: instr.tick_lower_index,
+                        tick_upper_index: instr.tick_upper_index,
+                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
+                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
+                        amount: instr.amount,
+                        base_flag: instr.base_flag,
+                        liquidity_min: instr.liquidity_min,
+                        with_metadata: instr.with_metadata,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", OpenPositionSingleSidedWithDeadline::from(ix));
+        }
         instruction::ClosePosition::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::ClosePosition>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -673,6 +1137,33 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", IncreaseLiquidityV2::from(ix));
         }
+        instruction::IncreaseLiquidityV2WithDeadline::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::IncreaseLiquidityV2WithDeadline>(&mut ix_data)
+                    .unwrap();
+            #[derive(Debug)]
+            pub struct IncreaseLiquidityV2WithDeadline {
+                pub liquidity: u128,
+                pub amount_0_max: u64,
+                pub amount_1_max: u64,
+                pub base_flag: Option<bool>,
+                pub deadline: i64,
+            }
+            impl From<instruction::IncreaseLiquidityV2WithDeadline> for IncreaseLiquidityV2WithDeadline {
+                fn from(
+                    instr: instruction::IncreaseLiquidityV2WithDeadline,
+                ) -> IncreaseLiquidityV2WithDeadline {
+                    IncreaseLiquidityV2WithDeadline {
+                        liquidity: instr.liquidity,
+                        amount_0_max: instr.amount_0_max,
+                        amount_1_max: instr.amount_1_max,
+                        base_flag: instr.base_flag,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", IncreaseLiquidityV2WithDeadline::from(ix));
+        }
         instruction::DecreaseLiquidity::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::DecreaseLiquidity>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -711,6 +1202,146 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", DecreaseLiquidityV2::from(ix));
         }
+        instruction::DecreaseLiquidityV2WithDeadline::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::DecreaseLiquidityV2WithDeadline>(&mut ix_data)
+                    .unwrap();
+            #[derive(Debug)]
+            pub struct DecreaseLiquidityV2WithDeadline {
+                pub liquidity: u128,
+                pub amount_0_min: u64,
+                pub amount_1_min: u64,
+                pub deadline: i64,
+            }
+            impl From<instruction::DecreaseLiquidityV2WithDeadline> for DecreaseLiquidityV2WithDeadline {
+                fn from(
+                    instr: instruction::DecreaseLiquidityV2WithDeadline,
+                ) -> DecreaseLiquidityV2WithDeadline {
+                    DecreaseLiquidityV2WithDeadline {
+                        liquidity: instr.liquidity,
+                        amount_0_min: instr.amount_0_min,
+                        amount_1_min: instr.amount_1_min,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", DecreaseLiquidityV2WithDeadline::from(ix));
+        }
+        instruction::CollectAll::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CollectAll>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CollectAll {
+                pub position_count: u8,
+            }
+            impl From<instruction::CollectAll> for CollectAll {
+                fn from(instr: instruction::CollectAll) -> CollectAll {
+                    CollectAll {
+                        position_count: instr.position_count,
+                    }
+                }
+            }
+            println!("{:#?}", CollectAll::from(ix));
+        }
+        instruction::CompoundPosition::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CompoundPosition>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CompoundPosition;
+            impl From<instruction::CompoundPosition> for CompoundPosition {
+                fn from(_instr: instruction::CompoundPosition) -> CompoundPosition {
+                    CompoundPosition
+                }
+            }
+            println!("{:#?}", CompoundPosition::from(ix));
+        }
+        instruction::RebalancePosition::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::RebalancePosition>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct RebalancePosition {
+                pub tick_lower_index: i32,
+                pub tick_upper_index: i32,
+                pub tick_array_lower_start_index: i32,
+                pub tick_array_upper_start_index: i32,
+                pub swap_amount: u64,
+                pub zero_for_one: bool,
+                pub liquidity_min: u128,
+            }
+            impl From<instruction::RebalancePosition> for RebalancePosition {
+                fn from(instr: instruction::RebalancePosition) -> RebalancePosition {
+                    RebalancePosition {
+                        tick_lower_index: instr.tick_lower_index,
+                        tick_upper_index: instr.tick_upper_index,
+                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
+                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
+                        swap_amount: instr.swap_amount,
+                        zero_for_one: instr.zero_for_one,
+                        liquidity_min: instr.liquidity_min,
+                    }
+                }
+            }
+            println!("{:#?}", RebalancePosition::from(ix));
+        }
+        instruction::RebalancePositionWithDeadline::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::RebalancePositionWithDeadline>(&mut ix_data)
+                .unwrap();
+            #[derive(Debug)]
+            pub struct RebalancePositionWithDeadline {
+                pub tick_lower_index: i32,
+                pub tick_upper_index: i32,
+                pub tick_array_lower_start_index: i32,
+                pub tick_array_upper_start_index: i32,
+                pub swap_amount: u64,
+                pub zero_for_one: bool,
+                pub liquidity_min: u128,
+                pub deadline: i64,
+            }
+            impl From<instruction::RebalancePositionWithDeadline> for RebalancePositionWithDeadline {
+                fn from(
+                    instr: instruction::RebalancePositionWithDeadline,
+                ) -> RebalancePositionWithDeadline {
+                    RebalancePositionWithDeadline {
+                        tick_lower_index: instr.tick_lower_index,
+                        tick_upper_index: instr.tick_upper_index,
+                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
+                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
+                        swap_amount: instr.swap_amount,
+                        zero_for_one: instr.zero_for_one,
+                        liquidity_min: instr.liquidity_min,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", RebalancePositionWithDeadline::from(ix));
+        }
+        instruction::GrantPositionDelegate::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::GrantPositionDelegate>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct GrantPositionDelegate {
+                pub permissions: u8,
+                pub expiry: u64,
+            }
+            impl From<instruction::GrantPositionDelegate> for GrantPositionDelegate {
+                fn from(instr: instruction::GrantPositionDelegate) -> GrantPositionDelegate {
+                    GrantPositionDelegate {
+                        permissions: instr.permissions,
+                        expiry: instr.expiry,
+                    }
+                }
+            }
+            println!("{:#?}", GrantPositionDelegate::from(ix));
+        }
+        instruction::RevokePositionDelegate::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::RevokePositionDelegate>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct RevokePositionDelegate;
+            impl From<instruction::RevokePositionDelegate> for RevokePositionDelegate {
+                fn from(_instr: instruction::RevokePositionDelegate) -> RevokePositionDelegate {
+                    RevokePositionDelegate
+                }
+            }
+            println!("{:#?}", RevokePositionDelegate::from(ix));
+        }
         instruction::Swap::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::Swap>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -732,6 +1363,29 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", Swap::from(ix));
         }
+        instruction::SwapWithDeadline::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SwapWithDeadline>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SwapWithDeadline {
+                pub amount: u64,
+                pub other_amount_threshold: u64,
+                pub sqrt_price_limit_x64: u128,
+                pub is_base_input: bool,
+                pub deadline: i64,
+            }
+            impl From<instruction::SwapWithDeadline> for SwapWithDeadline {
+                fn from(instr: instruction::SwapWithDeadline) -> SwapWithDeadline {
+                    SwapWithDeadline {
+                        amount: instr.amount,
+                        other_amount_threshold: instr.other_amount_threshold,
+                        sqrt_price_limit_x64: instr.sqrt_price_limit_x64,
+                        is_base_input: instr.is_base_input,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", SwapWithDeadline::from(ix));
+        }
         instruction::SwapV2::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::SwapV2>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -753,6 +1407,29 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", SwapV2::from(ix));
         }
+        instruction::SwapV2WithDeadline::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SwapV2WithDeadline>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SwapV2WithDeadline {
+                pub amount: u64,
+                pub other_amount_threshold: u64,
+                pub sqrt_price_limit_x64: u128,
+                pub is_base_input: bool,
+                pub deadline: i64,
+            }
+            impl From<instruction::SwapV2WithDeadline> for SwapV2WithDeadline {
+                fn from(instr: instruction::SwapV2WithDeadline) -> SwapV2WithDeadline {
+                    SwapV2WithDeadline {
+                        amount: instr.amount,
+                        other_amount_threshold: instr.other_amount_threshold,
+                        sqrt_price_limit_x64: instr.sqrt_price_limit_x64,
+                        is_base_input: instr.is_base_input,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", SwapV2WithDeadline::from(ix));
+        }
         instruction::SwapRouterBaseIn::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::SwapRouterBaseIn>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -770,6 +1447,182 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", SwapRouterBaseIn::from(ix));
         }
+        instruction::SwapRouterBaseInWithDeadline::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SwapRouterBaseInWithDeadline>(&mut ix_data)
+                .unwrap();
+            #[derive(Debug)]
+            pub struct SwapRouterBaseInWithDeadline {
+                pub amount_in: u64,
+                pub amount_out_minimum: u64,
+                pub deadline: i64,
+            }
+            impl From<instruction::SwapRouterBaseInWithDeadline> for SwapRouterBaseInWithDeadline {
+                fn from(
+                    instr: instruction::SwapRouterBaseInWithDeadline,
+                ) -> SwapRouterBaseInWithDeadline {
+                    SwapRouterBaseInWithDeadline {
+                        amount_in: instr.amount_in,
+                        amount_out_minimum: instr.amount_out_minimum,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", SwapRouterBaseInWithDeadline::from(ix));
+        }
+        instruction::SwapRouterBaseOut::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SwapRouterBaseOut>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SwapRouterBaseOut {
+                pub amount_out: u64,
+                pub amount_in_maximum: u64,
+            }
+            impl From<instruction::SwapRouterBaseOut> for SwapRouterBaseOut {
+                fn from(instr: instruction::SwapRouterBaseOut) -> SwapRouterBaseOut {
+                    SwapRouterBaseOut {
+                        amount_out: instr.amount_out,
+                        amount_in_maximum: instr.amount_in_maximum,
+                    }
+                }
+            }
+            println!("{:#?}", SwapRouterBaseOut::from(ix));
+        }
+        instruction::SwapRouterBaseOutWithDeadline::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SwapRouterBaseOutWithDeadline>(&mut ix_data)
+                .unwrap();
+            #[derive(Debug)]
+            pub struct SwapRouterBaseOutWithDeadline {
+                pub amount_out: u64,
+                pub amount_in_maximum: u64,
+                pub deadline: i64,
+            }
+            impl From<instruction::SwapRouterBaseOutWithDeadline> for SwapRouterBaseOutWithDeadline {
+                fn from(
+                    instr: instruction::SwapRouterBaseOutWithDeadline,
+                ) -> SwapRouterBaseOutWithDeadline {
+                    SwapRouterBaseOutWithDeadline {
+                        amount_out: instr.amount_out,
+                        amount_in_maximum: instr.amount_in_maximum,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", SwapRouterBaseOutWithDeadline::from(ix));
+        }
+        instruction::QuoteSwap::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::QuoteSwap>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct QuoteSwap {
+                pub amount: u64,
+                pub sqrt_price_limit_x64: u128,
+                pub is_base_input: bool,
+            }
+            impl From<instruction::QuoteSwap> for QuoteSwap {
+                fn from(instr: instruction::QuoteSwap) -> QuoteSwap {
+                    QuoteSwap {
+                        amount: instr.amount,
+                        sqrt_price_limit_x64: instr.sqrt_price_limit_x64,
+                        is_base_input: instr.is_base_input,
+                    }
+                }
+            }
+            println!("{:#?}", QuoteSwap::from(ix));
+        }
+        instruction::Flash::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::Flash>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct Flash {
+                pub amount_0: u64,
+                pub amount_1: u64,
+                pub data: Vec<u8>,
+            }
+            impl From<instruction::Flash> for Flash {
+                fn from(instr: instruction::Flash) -> Flash {
+                    Flash {
+                        amount_0: instr.amount_0,
+                        amount_1: instr.amount_1,
+                        data: instr.data,
+                    }
+                }
+            }
+            println!("{:#?}", Flash::from(ix));
+        }
+        instruction::OpenLimitOrder::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::OpenLimitOrder>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct OpenLimitOrder {
+                pub tick_index: i32,
+                pub tick_array_start_index: i32,
+                pub zero_for_one: bool,
+                pub amount: u64,
+            }
+            impl From<instruction::OpenLimitOrder> for OpenLimitOrder {
+                fn from(instr: instruction::OpenLimitOrder) -> OpenLimitOrder {
+                    OpenLimitOrder {
+                        tick_index: instr.tick_index,
+                        tick_array_start_index: instr.tick_array_start_index,
+                        zero_for_one: instr.zero_for_one,
+                        amount: instr.amount,
+                    }
+                }
+            }
+            println!("{:#?}", OpenLimitOrder::from(ix));
+        }
+        instruction::CancelLimitOrder::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CancelLimitOrder>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CancelLimitOrder;
+            impl From<instruction::CancelLimitOrder> for CancelLimitOrder {
+                fn from(_instr: instruction::CancelLimitOrder) -> CancelLimitOrder {
+                    CancelLimitOrder
+                }
+            }
+            println!("{:#?}", CancelLimitOrder::from(ix));
+        }
+        instruction::ClaimLimitOrder::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::ClaimLimitOrder>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct ClaimLimitOrder;
+            impl From<instruction::ClaimLimitOrder> for ClaimLimitOrder {
+                fn from(_instr: instruction::ClaimLimitOrder) -> ClaimLimitOrder {
+                    ClaimLimitOrder
+                }
+            }
+            println!("{:#?}", ClaimLimitOrder::from(ix));
+        }
+        instruction::Observe::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::Observe>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct Observe {
+                pub seconds_agos: Vec<u32>,
+            }
+            impl From<instruction::Observe> for Observe {
+                fn from(instr: instruction::Observe) -> Observe {
+                    Observe {
+                        seconds_agos: instr.seconds_agos,
+                    }
+                }
+            }
+            println!("{:#?}", Observe::from(ix));
+        }
+        instruction::IncreaseObservationCardinality::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::IncreaseObservationCardinality>(&mut ix_data)
+                    .unwrap();
+            #[derive(Debug)]
+            pub struct IncreaseObservationCardinality {
+                pub observation_cardinality_next: u16,
+            }
+            impl From<instruction::IncreaseObservationCardinality> for IncreaseObservationCardinality {
+                fn from(
+                    instr: instruction::IncreaseObservationCardinality,
+                ) -> IncreaseObservationCardinality {
+                    IncreaseObservationCardinality {
+                        observation_cardinality_next: instr.observation_cardinality_next,
+                    }
+                }
+            }
+            println!("{:#?}", IncreaseObservationCardinality::from(ix));
+        }
         _ => {
             println!("unknow instruction: {}", instr_data);
         }
@@ -387,6 +387,8 @@ pub enum CommandsName {
         trade_fee_rate: u32,
         protocol_fee_rate: u32,
         fund_fee_rate: u32,
+        #[arg(long)]
+        creator_fee_rate: Option<u32>,
     },
     UpdateConfig {
         config_index: u16,
@@ -435,6 +437,9 @@ pub enum CommandsName {
         input_amount: u64,
         #[arg(short, long)]
         with_metadata: bool,
+        /// Open the position from input_amount of a single token, part of it is swapped
+        #[arg(long)]
+        single_sided: bool,
     },
     IncreaseLiquidity {
         tick_lower_price: f64,
@@ -459,6 +464,8 @@ pub enum CommandsName {
         simulate: bool,
         amount: u64,
         limit_price: Option<f64>,
+        #[arg(long)]
+        deadline: Option<i64>,
     },
     SwapV2 {
         input_token: Pubkey,
@@ -469,6 +476,8 @@ pub enum CommandsName {
         simulate: bool,
         amount: u64,
         limit_price: Option<f64>,
+        #[arg(long)]
+        deadline: Option<i64>,
     },
     PPositionByOwner {
         user_wallet: Pubkey,
@@ -758,6 +767,7 @@ fn main() -> Result<()> {
             trade_fee_rate,
             protocol_fee_rate,
             fund_fee_rate,
+            creator_fee_rate,
         } => {
             let create_instr = create_amm_config_instr(
                 &pool_config.clone(),
@@ -766,6 +776,7 @@ fn main() -> Result<()> {
                 trade_fee_rate,
                 protocol_fee_rate,
                 fund_fee_rate,
+                creator_fee_rate,
             )?;
             // send
             let signers = vec![&payer, &admin];
@@ -792,6 +803,7 @@ fn main() -> Result<()> {
                 Some(0) => update_value = value,
                 Some(1) => update_value = value,
                 Some(2) => update_value = value,
+                Some(5) | Some(6) | Some(7) => update_value = value,
                 Some(3) => {
                     let remaining_key = remaining.unwrap();
                     remaing_accounts.push(AccountMeta::new_readonly(remaining_key, false));
@@ -1062,6 +1074,7 @@ fn main() -> Result<()> {
             is_base_0,
             input_amount,
             with_metadata,
+            single_sided,
         } => {
             // load pool to get observation
             let pool: raydium_amm_v3::states::PoolState =
@@ -1192,40 +1205,137 @@ fn main() -> Result<()> {
                     pool_config.tickarray_bitmap_extension.unwrap(),
                     false,
                 ));
+                // written with an oracle observation before the liquidity changes
+                remaining_accounts.push(AccountMeta::new(pool.observation_key, false));
 
                 let mut instructions = Vec::new();
                 let request_inits_instr =
                     ComputeBudgetInstruction::set_compute_unit_limit(1400_000u32);
                 instructions.push(request_inits_instr);
-                let open_position_instr = open_position_with_token22_nft_instr(
-                    &pool_config.clone(),
-                    pool_config.pool_id_account.unwrap(),
-                    pool.token_vault_0,
-                    pool.token_vault_1,
-                    pool.token_mint_0,
-                    pool.token_mint_1,
-                    nft_mint.pubkey(),
-                    payer.pubkey(),
+                let user_token_account_0 =
                     spl_associated_token_account::get_associated_token_address_with_program_id(
                         &payer.pubkey(),
                         &pool_config.mint0.unwrap(),
                         &transfer_fee.0.owner,
-                    ),
+                    );
+                let user_token_account_1 =
                     spl_associated_token_account::get_associated_token_address_with_program_id(
                         &payer.pubkey(),
                         &pool_config.mint1.unwrap(),
                         &transfer_fee.1.owner,
-                    ),
-                    remaining_accounts,
-                    liquidity,
-                    amount_0_max,
-                    amount_1_max,
-                    tick_lower_index,
-                    tick_upper_index,
-                    tick_array_lower_start_index,
-                    tick_array_upper_start_index,
-                    with_metadata,
-                )?;
+                    );
+                let open_position_instr = if single_sided {
+                    let amm_config: raydium_amm_v3::states::AmmConfig =
+                        program.account(pool.amm_config)?;
+                    let tickarray_bitmap_extension: TickArrayBitmapExtension =
+                        program.account(pool_config.tickarray_bitmap_extension.unwrap())?;
+                    // the part of the input swapped by the program, assuming no tick is crossed
+                    let swap_amount = raydium_amm_v3::instructions::get_single_sided_swap_amount(
+                        pool.sqrt_price_x64,
+                        pool.liquidity,
+                        amm_config.trade_fee_rate,
+                        tick_lower_price_x64,
+                        tick_upper_price_x64,
+                        input_amount,
+                        is_base_0,
+                    )?;
+                    let mut tick_arrays = load_cur_and_next_five_tick_array(
+                        &rpc_client,
+                        &pool_config,
+                        &pool,
+                        &tickarray_bitmap_extension,
+                        is_base_0,
+                    );
+                    let (swap_amount_out, tick_array_indexs) =
+                        utils::get_out_put_amount_and_remaining_accounts(
+                            swap_amount,
+                            None,
+                            is_base_0,
+                            true,
+                            &amm_config,
+                            &pool,
+                            &tickarray_bitmap_extension,
+                            &mut tick_arrays,
+                        )
+                        .unwrap();
+                    let (amount_0, amount_1) = if is_base_0 {
+                        (input_amount - swap_amount, swap_amount_out)
+                    } else {
+                        (swap_amount_out, input_amount - swap_amount)
+                    };
+                    let liquidity = liquidity_math::get_liquidity_from_amounts(
+                        pool.sqrt_price_x64,
+                        tick_lower_price_x64,
+                        tick_upper_price_x64,
+                        amount_0,
+                        amount_1,
+                    );
+                    let liquidity_min =
+                        (liquidity as f64 * (1_f64 - pool_config.slippage)).floor() as u128;
+                    println!(
+                        "swap_amount:{}, swap_amount_out:{}, liquidity_min:{}",
+                        swap_amount, swap_amount_out, liquidity_min
+                    );
+                    remaining_accounts.extend(tick_array_indexs.into_iter().map(|index| {
+                        AccountMeta::new(
+                            Pubkey::find_program_address(
+                                &[
+                                    raydium_amm_v3::states::TICK_ARRAY_SEED.as_bytes(),
+                                    pool_config.pool_id_account.unwrap().to_bytes().as_ref(),
+                                    &index.to_be_bytes(),
+                                ],
+                                &pool_config.raydium_v3_program,
+                            )
+                            .0,
+                            false,
+                        )
+                    }));
+                    open_position_single_sided_instr(
+                        &pool_config.clone(),
+                        pool.amm_config,
+                        pool_config.pool_id_account.unwrap(),
+                        pool.observation_key,
+                        pool.token_vault_0,
+                        pool.token_vault_1,
+                        pool.token_mint_0,
+                        pool.token_mint_1,
+                        nft_mint.pubkey(),
+                        payer.pubkey(),
+                        user_token_account_0,
+                        user_token_account_1,
+                        remaining_accounts,
+                        input_amount,
+                        is_base_0,
+                        liquidity_min,
+                        tick_lower_index,
+                        tick_upper_index,
+                        tick_array_lower_start_index,
+                        tick_array_upper_start_index,
+                        with_metadata,
+                    )?
+                } else {
+                    open_position_with_token22_nft_instr(
+                        &pool_config.clone(),
+                        pool_config.pool_id_account.unwrap(),
+                        pool.token_vault_0,
+                        pool.token_vault_1,
+                        pool.token_mint_0,
+                        pool.token_mint_1,
+                        nft_mint.pubkey(),
+                        payer.pubkey(),
+                        user_token_account_0,
+                        user_token_account_1,
+                        remaining_accounts,
+                        liquidity,
+                        amount_0_max,
+                        amount_1_max,
+                        tick_lower_index,
+                        tick_upper_index,
+                        tick_array_lower_start_index,
+                        tick_array_upper_start_index,
+                        with_metadata,
+                    )?
+                };
                 instructions.extend(open_position_instr);
                 // send
                 let signers = vec![&payer, &nft_mint];
@@ -1383,6 +1493,8 @@ fn main() -> Result<()> {
                     pool_config.tickarray_bitmap_extension.unwrap(),
                     false,
                 ));
+                // written with an oracle observation before the liquidity changes
+                remaining_accounts.push(AccountMeta::new(pool.observation_key, false));
 
                 let increase_instr = increase_liquidity_instr(
                     &pool_config.clone(),
@@ -1534,6 +1646,8 @@ fn main() -> Result<()> {
                     pool_config.tickarray_bitmap_extension.unwrap(),
                     false,
                 ));
+                // written with an oracle observation before the liquidity changes
+                remaining_accounts.push(AccountMeta::new(pool.observation_key, false));
 
                 let mut accounts = reward_vault_with_user_vault
                     .into_iter()
@@ -1611,6 +1725,7 @@ fn main() -> Result<()> {
             simulate,
             amount,
             limit_price,
+            deadline,
         } => {
             // load mult account
             let load_accounts = vec![
@@ -1744,6 +1859,7 @@ fn main() -> Result<()> {
                 other_amount_threshold,
                 sqrt_price_limit_x64,
                 base_in,
+                deadline,
             )
             .unwrap();
             instructions.extend(swap_instr);
@@ -1772,6 +1888,7 @@ fn main() -> Result<()> {
             simulate,
             amount,
             limit_price,
+            deadline,
         } => {
             // load mult account
             let load_accounts = vec![
@@ -1930,6 +2047,7 @@ fn main() -> Result<()> {
                 other_amount_threshold,
                 sqrt_price_limit_x64,
                 base_in,
+                deadline,
             )
             .unwrap();
             instructions.extend(swap_instr);
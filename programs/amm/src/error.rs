@@ -107,4 +107,94 @@ pub enum ErrorCode {
     CalculateOverflow,
     #[msg("TransferFee calculate not match")]
     TransferFeeCalculateNotMatch,
+
+    /// limit order errors
+    #[msg("Limit order amount can not be zero")]
+    ZeroLimitOrderAmount,
+    #[msg("Limit order tick is on the wrong side of the current price")]
+    InvalidLimitOrderTick,
+
+    /// oracle errors
+    #[msg("Observation is not initialized")]
+    ObservationNotInitialized,
+    #[msg("Observation is older than the oldest stored observation")]
+    ObservationTooOld,
+    #[msg("seconds_agos must span a time window")]
+    InvalidSecondsAgos,
+    #[msg("Observation cardinality must grow, by a limited amount per instruction")]
+    InvalidObservationCardinality,
+
+    /// position delegate errors
+    #[msg("Invalid position delegate permissions")]
+    InvalidPositionDelegatePermissions,
+    #[msg("Position delegate expiry must be in the future")]
+    InvalidPositionDelegateExpiry,
+
+    /// rebalance errors
+    #[msg("The new tick range must differ from the position's range")]
+    RebalanceToSameRange,
+
+    /// reward extension errors
+    #[msg("Missing reward extension account")]
+    MissingRewardExtensionAccount,
+
+    /// flash errors
+    #[msg("The flash loan callback program can not be this program")]
+    InvalidFlashCallbackProgram,
+    #[msg("The flash loan and its fee were not paid back")]
+    FlashLoanNotRepaid,
+
+    /// reward funding errors
+    #[msg("The reward has ended")]
+    RewardNotEmitting,
+    #[msg("The reward fund amount is too small to extend or raise the emissions")]
+    RewardFundAmountTooSmall,
+    #[msg("The funders of the previous emission have not all been refunded")]
+    RewardFundingOutstanding,
+    #[msg("Missing reward funding account")]
+    MissingRewardFundingAccount,
+
+    /// reward schedule errors
+    #[msg("Invalid reward schedule")]
+    InvalidRewardSchedule,
+    #[msg("Missing reward schedule account")]
+    MissingRewardScheduleAccount,
+    #[msg("The reward follows a schedule until it ends")]
+    RewardScheduled,
+
+    /// collect all errors
+    #[msg("Invalid collect position input account number")]
+    InvalidPositionInputAccountNumber,
+
+    /// pool open time errors
+    #[msg("The pool is not open for swaps yet")]
+    PoolNotOpen,
+    #[msg("The pool is already open for swaps")]
+    PoolAlreadyOpen,
+
+    /// price deviation errors
+    #[msg("The price is at the max deviation from the recent average price")]
+    PriceDeviationExceeded,
+
+    /// amm config switch errors
+    #[msg("The amm config does not match the tick spacing of the pool")]
+    AmmConfigTickSpacingMismatch,
+    #[msg("The protocol and fund fees must be collected before switching the amm config")]
+    UncollectedPoolFees,
+
+    /// swap hook errors
+    #[msg("The swap hook program of the pool is missing in the remaining accounts")]
+    MissingSwapHookAccount,
+    #[msg("Invalid swap hook program")]
+    InvalidSwapHookProgram,
+    #[msg("The pool has a swap hook, the instruction can not swap in it")]
+    SwapHookNotSupported,
+    #[msg("Not enough swap hook accounts")]
+    NotEnoughSwapHookAccounts,
+    #[msg("Too many swap hook accounts")]
+    TooManySwapHookAccounts,
+
+    /// reward range errors
+    #[msg("The extension rewards can not be restricted to a tick range")]
+    RewardRangeNotSupported,
 }
@@ -36,6 +36,7 @@ pub fn create_amm_config(
     trade_fee_rate: u32,
     protocol_fee_rate: u32,
     fund_fee_rate: u32,
+    creator_fee_rate: u32,
 ) -> Result<()> {
     let amm_config = ctx.accounts.amm_config.deref_mut();
     amm_config.owner = ctx.accounts.owner.key();
@@ -45,6 +46,7 @@ pub fn create_amm_config(
     amm_config.protocol_fee_rate = protocol_fee_rate;
     amm_config.tick_spacing = tick_spacing;
     amm_config.fund_fee_rate = fund_fee_rate;
+    amm_config.creator_fee_rate = creator_fee_rate;
     amm_config.fund_owner = ctx.accounts.owner.key();
 
     emit!(ConfigChangeEvent {
@@ -56,6 +58,12 @@ pub fn create_amm_config(
         fund_fee_rate: amm_config.fund_fee_rate,
         fund_owner: amm_config.fund_owner,
     });
+    if creator_fee_rate != 0 {
+        emit!(CreatorFeeRateChangeEvent {
+            index: amm_config.index,
+            creator_fee_rate,
+        });
+    }
 
     Ok(())
 }
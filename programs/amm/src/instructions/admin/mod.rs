@@ -22,5 +22,20 @@ pub use transfer_reward_owner::*;
 pub mod update_pool_status;
 pub use update_pool_status::*;
 
+pub mod update_observation_duration;
+pub use update_observation_duration::*;
+
+pub mod update_pool_tick_deviation;
+pub use update_pool_tick_deviation::*;
+
+pub mod update_pool_trade_fee_rate;
+pub use update_pool_trade_fee_rate::*;
+
+pub mod update_pool_amm_config;
+pub use update_pool_amm_config::*;
+
+pub mod update_pool_swap_hook;
+pub use update_pool_swap_hook::*;
+
 pub mod create_support_mint_associated;
 pub use create_support_mint_associated::*;
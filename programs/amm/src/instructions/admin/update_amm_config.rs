@@ -28,6 +28,25 @@ pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -
             let new_fund_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
             set_new_fund_owner(amm_config, new_fund_owner);
         }
+        Some(5) => {
+            update_dynamic_fee_max_rate(amm_config, value);
+            emit_dynamic_fee_change_event(amm_config);
+        }
+        Some(6) => {
+            update_dynamic_fee_control(amm_config, value);
+            emit_dynamic_fee_change_event(amm_config);
+        }
+        Some(7) => {
+            update_dynamic_fee_decay_period(amm_config, value);
+            emit_dynamic_fee_change_event(amm_config);
+        }
+        Some(8) => {
+            update_creator_fee_rate(amm_config, value);
+            emit!(CreatorFeeRateChangeEvent {
+                index: amm_config.index,
+                creator_fee_rate: amm_config.creator_fee_rate,
+            });
+        }
         _ => return err!(ErrorCode::InvalidUpdateConfigFlag),
     }
 
@@ -46,7 +65,10 @@ pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -
 
 fn update_protocol_fee_rate(amm_config: &mut Account<AmmConfig>, protocol_fee_rate: u32) {
     assert!(protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    assert!(protocol_fee_rate + amm_config.fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    assert!(
+        protocol_fee_rate + amm_config.fund_fee_rate + amm_config.creator_fee_rate
+            <= FEE_RATE_DENOMINATOR_VALUE
+    );
     amm_config.protocol_fee_rate = protocol_fee_rate;
 }
 
@@ -57,10 +79,51 @@ fn update_trade_fee_rate(amm_config: &mut Account<AmmConfig>, trade_fee_rate: u3
 
 fn update_fund_fee_rate(amm_config: &mut Account<AmmConfig>, fund_fee_rate: u32) {
     assert!(fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    assert!(fund_fee_rate + amm_config.protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    assert!(
+        fund_fee_rate + amm_config.protocol_fee_rate + amm_config.creator_fee_rate
+            <= FEE_RATE_DENOMINATOR_VALUE
+    );
     amm_config.fund_fee_rate = fund_fee_rate;
 }
 
+fn update_creator_fee_rate(amm_config: &mut Account<AmmConfig>, creator_fee_rate: u32) {
+    assert!(creator_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    assert!(
+        creator_fee_rate + amm_config.protocol_fee_rate + amm_config.fund_fee_rate
+            <= FEE_RATE_DENOMINATOR_VALUE
+    );
+    amm_config.creator_fee_rate = creator_fee_rate;
+}
+
+fn update_dynamic_fee_max_rate(amm_config: &mut Account<AmmConfig>, dynamic_fee_max_rate: u32) {
+    assert!(dynamic_fee_max_rate < FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.dynamic_fee_max_rate = dynamic_fee_max_rate;
+}
+
+fn update_dynamic_fee_control(amm_config: &mut Account<AmmConfig>, dynamic_fee_control: u32) {
+    assert!(dynamic_fee_control <= FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.dynamic_fee_control = dynamic_fee_control;
+}
+
+fn update_dynamic_fee_decay_period(
+    amm_config: &mut Account<AmmConfig>,
+    dynamic_fee_decay_period: u32,
+) {
+    assert!(
+        dynamic_fee_decay_period > 0 && dynamic_fee_decay_period <= MAX_DYNAMIC_FEE_DECAY_PERIOD
+    );
+    amm_config.dynamic_fee_decay_period = dynamic_fee_decay_period;
+}
+
+fn emit_dynamic_fee_change_event(amm_config: &Account<AmmConfig>) {
+    emit!(DynamicFeeChangeEvent {
+        index: amm_config.index,
+        dynamic_fee_max_rate: amm_config.dynamic_fee_max_rate,
+        dynamic_fee_control: amm_config.dynamic_fee_control,
+        dynamic_fee_decay_period: amm_config.dynamic_fee_decay_period,
+    });
+}
+
 fn set_new_owner(amm_config: &mut Account<AmmConfig>, new_owner: Pubkey) {
     #[cfg(feature = "enable-log")]
     msg!(
@@ -0,0 +1,29 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateObservationDuration<'info> {
+    #[account(
+        address = crate::admin::ID
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn update_observation_duration(
+    ctx: Context<UpdateObservationDuration>,
+    update_duration: u32,
+) -> Result<()> {
+    require_gt!(update_duration, 0);
+    require_gte!(MAX_OBSERVATION_UPDATE_DURATION, update_duration);
+    let mut observation_state = ctx.accounts.observation_state.load_mut()?;
+    observation_state.observation_update_duration = update_duration;
+
+    emit!(ObservationDurationChangeEvent {
+        pool_state: observation_state.pool_id,
+        update_duration,
+    });
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolAmmConfig<'info> {
+    #[account(
+        address = crate::admin::ID
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config the pool is moved to
+    #[account(
+        constraint = new_amm_config.key() != pool_state.load()?.amm_config,
+        constraint = new_amm_config.tick_spacing == pool_state.load()?.tick_spacing @ ErrorCode::AmmConfigTickSpacingMismatch
+    )]
+    pub new_amm_config: Account<'info, AmmConfig>,
+}
+
+/// Moves the pool to another amm config of the same tick spacing. The protocol and fund fees
+/// accrued under the current amm config must be collected before, in the same transaction. The
+/// creator fees can stay, they are paid to the pool owner whatever the amm config.
+pub fn update_pool_amm_config(ctx: Context<UpdatePoolAmmConfig>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require!(
+        pool_state.protocol_fees_token_0 == 0
+            && pool_state.protocol_fees_token_1 == 0
+            && pool_state.fund_fees_token_0 == 0
+            && pool_state.fund_fees_token_1 == 0,
+        ErrorCode::UncollectedPoolFees
+    );
+    let old_amm_config = pool_state.amm_config;
+    pool_state.set_amm_config(ctx.accounts.new_amm_config.key());
+
+    let new_amm_config = &ctx.accounts.new_amm_config;
+    emit!(PoolAmmConfigChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        old_amm_config,
+        amm_config: new_amm_config.key(),
+        trade_fee_rate: new_amm_config.trade_fee_rate,
+        protocol_fee_rate: new_amm_config.protocol_fee_rate,
+        fund_fee_rate: new_amm_config.fund_fee_rate,
+        creator_fee_rate: new_amm_config.creator_fee_rate,
+    });
+    Ok(())
+}
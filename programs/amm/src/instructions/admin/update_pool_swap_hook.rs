@@ -0,0 +1,48 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::MAX_SWAP_HOOK_ACCOUNTS;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolSwapHook<'info> {
+    #[account(
+        address = crate::admin::ID
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_swap_hook(
+    ctx: Context<UpdatePoolSwapHook>,
+    swap_hook_program: Option<Pubkey>,
+    swap_hook_account_count: u8,
+) -> Result<()> {
+    let swap_hook_program = swap_hook_program.unwrap_or_default();
+    require_keys_neq!(
+        swap_hook_program,
+        crate::id(),
+        ErrorCode::InvalidSwapHookProgram
+    );
+    let swap_hook_account_count = if swap_hook_program == Pubkey::default() {
+        0
+    } else {
+        require_gte!(
+            MAX_SWAP_HOOK_ACCOUNTS,
+            swap_hook_account_count,
+            ErrorCode::TooManySwapHookAccounts
+        );
+        swap_hook_account_count
+    };
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.swap_hook_program = swap_hook_program;
+    pool_state.swap_hook_account_count = swap_hook_account_count;
+
+    emit!(PoolSwapHookChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        swap_hook_program,
+        swap_hook_account_count,
+    });
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolTickDeviation<'info> {
+    #[account(
+        address = crate::admin::ID
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_tick_deviation(
+    ctx: Context<UpdatePoolTickDeviation>,
+    max_tick_deviation: u32,
+    tick_deviation_period: u32,
+) -> Result<()> {
+    if max_tick_deviation > 0 {
+        require_gt!(tick_deviation_period, 0);
+    }
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.max_tick_deviation = max_tick_deviation;
+    pool_state.tick_deviation_period = tick_deviation_period;
+
+    emit!(PoolTickDeviationChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        max_tick_deviation,
+        tick_deviation_period,
+    });
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolTradeFeeRate<'info> {
+    #[account(
+        address = crate::admin::ID
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_trade_fee_rate(
+    ctx: Context<UpdatePoolTradeFeeRate>,
+    trade_fee_rate: Option<u32>,
+) -> Result<()> {
+    if let Some(trade_fee_rate) = trade_fee_rate {
+        require_gt!(FEE_RATE_DENOMINATOR_VALUE, trade_fee_rate);
+    }
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.set_trade_fee_rate_override(trade_fee_rate);
+
+    emit!(PoolTradeFeeRateChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        trade_fee_rate,
+    });
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    /// The owner of the order
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The order account to be closed
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        constraint = limit_order.pool_id == pool_state.key()
+    )]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// Stores the tick the order rests on
+    #[account(mut, constraint = tick_array.load()?.pool_id == pool_state.key())]
+    pub tick_array: AccountLoader<'info, TickArrayState>,
+
+    /// The destination token account for receive amount_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive amount_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+}
+
+pub fn cancel_limit_order<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CancelLimitOrder<'info>>,
+) -> Result<()> {
+    let limit_order = &mut ctx.accounts.limit_order;
+    let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(limit_order.tick_index)?;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        // orders are withdrawn like liquidity
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity) {
+            return err!(ErrorCode::NotApproved);
+        }
+        let mut tick_array = ctx.accounts.tick_array.load_mut()?;
+        let tick_state =
+            tick_array.get_tick_state_mut(limit_order.tick_index, pool_state.tick_spacing)?;
+        limit_order.settle(tick_state)?;
+        tick_state.consume_orders(limit_order.unfilled_amount, false);
+        remove_limit_order(
+            &mut pool_state,
+            &mut tick_array,
+            limit_order.tick_index,
+            ctx.remaining_accounts,
+        )?;
+    }
+
+    let unfilled_amount = limit_order.unfilled_amount;
+    let proceeds_amount = limit_order.filled_proceeds(sqrt_price_x64);
+    let (amount_0, amount_1) = if limit_order.zero_for_one {
+        (unfilled_amount, proceeds_amount)
+    } else {
+        (proceeds_amount, unfilled_amount)
+    };
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program.to_account_info(),
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program.to_account_info(),
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+
+    emit!(CancelLimitOrderEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        owner: ctx.accounts.owner.key(),
+        tick_index: limit_order.tick_index,
+        zero_for_one: limit_order.zero_for_one,
+        unfilled_amount,
+        proceeds_amount,
+    });
+
+    Ok(())
+}
+
+/// Releases the tick reference of a closed order account, the tick is uninitialized when
+/// neither liquidity nor orders reference it anymore
+pub fn remove_limit_order<'c: 'info, 'info>(
+    pool_state: &mut PoolState,
+    tick_array: &mut TickArrayState,
+    tick_index: i32,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<()> {
+    let tick_state = tick_array.get_tick_state_mut(tick_index, pool_state.tick_spacing)?;
+    tick_state.order_count = tick_state.order_count.checked_sub(1).unwrap();
+    if tick_state.order_count == 0 && tick_state.order_amount != 0 {
+        // rounding dust nobody can claim anymore is left in the vault
+        tick_state.consume_orders(tick_state.order_amount, false);
+    }
+    if !tick_state.is_initialized() {
+        tick_state.clear();
+        tick_array.update_initialized_tick_count(false)?;
+        if tick_array.initialized_tick_count == 0 {
+            let use_tickarray_bitmap_extension =
+                pool_state.is_overflow_default_tickarray_bitmap(vec![tick_array.start_tick_index]);
+            pool_state.flip_tick_array_bit(
+                if use_tickarray_bitmap_extension {
+                    Some(
+                        remaining_accounts
+                            .get(0)
+                            .ok_or(ErrorCode::MissingTickArrayBitmapExtensionAccount)?,
+                    )
+                } else {
+                    None
+                },
+                tick_array.start_tick_index,
+            )?;
+        }
+    }
+    tick_array.recent_epoch = get_recent_epoch()?;
+    Ok(())
+}
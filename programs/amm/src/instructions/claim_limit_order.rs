@@ -0,0 +1,126 @@
+use super::remove_limit_order;
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct ClaimLimitOrder<'info> {
+    /// The owner of the order
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The order account, closed once the order is completely filled
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = limit_order.pool_id == pool_state.key()
+    )]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// Stores the tick the order rests on
+    #[account(mut, constraint = tick_array.load()?.pool_id == pool_state.key())]
+    pub tick_array: AccountLoader<'info, TickArrayState>,
+
+    /// The destination token account for the bought token
+    #[account(
+        mut,
+        token::mint = output_vault.mint
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool vault of the bought token
+    #[account(
+        mut,
+        constraint = output_vault.key() == if limit_order.zero_for_one {
+            pool_state.load()?.token_vault_1
+        } else {
+            pool_state.load()?.token_vault_0
+        }
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the bought token
+    #[account(
+        address = output_vault.mint
+    )]
+    pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+}
+
+pub fn claim_limit_order<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ClaimLimitOrder<'info>>,
+) -> Result<()> {
+    let limit_order = &mut ctx.accounts.limit_order;
+    let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(limit_order.tick_index)?;
+    let closed;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        // orders are withdrawn like liquidity
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity) {
+            return err!(ErrorCode::NotApproved);
+        }
+        let mut tick_array = ctx.accounts.tick_array.load_mut()?;
+        let tick_state =
+            tick_array.get_tick_state_mut(limit_order.tick_index, pool_state.tick_spacing)?;
+        limit_order.settle(tick_state)?;
+        closed = limit_order.unfilled_amount == 0;
+        if closed {
+            remove_limit_order(
+                &mut pool_state,
+                &mut tick_array,
+                limit_order.tick_index,
+                ctx.remaining_accounts,
+            )?;
+        }
+    }
+
+    let filled_amount = limit_order.filled_amount;
+    let proceeds_amount = limit_order.filled_proceeds(sqrt_price_x64);
+    limit_order.filled_amount = 0;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.output_vault.to_account_info(),
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        Some(ctx.accounts.output_vault_mint.clone()),
+        &ctx.accounts.token_program.to_account_info(),
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        proceeds_amount,
+    )?;
+
+    emit!(ClaimLimitOrderEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        owner: ctx.accounts.owner.key(),
+        tick_index: limit_order.tick_index,
+        zero_for_one: limit_order.zero_for_one,
+        filled_amount,
+        proceeds_amount,
+        closed,
+    });
+
+    if closed {
+        limit_order.close(ctx.accounts.owner.to_account_info())?;
+    }
+
+    Ok(())
+}
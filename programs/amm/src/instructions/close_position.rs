@@ -1,6 +1,6 @@
 use crate::error::ErrorCode;
 use crate::states::*;
-use crate::util::{burn, close_spl_account};
+use crate::util::{burn, close_account, close_spl_account, AccountLoad};
 use anchor_lang::prelude::*;
 use anchor_spl::token_2022::spl_token_2022;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
@@ -42,6 +42,8 @@ pub struct ClosePosition<'info> {
 
     /// Token/Token2022 program to close token/mint account
     pub token_program: Interface<'info, TokenInterface>,
+    // remaining account
+    // position_reward_extension: closed along if added, it must not have extension rewards owed
 }
 
 pub fn close_position<'a, 'b, 'c, 'info>(
@@ -71,6 +73,24 @@ pub fn close_position<'a, 'b, 'c, 'info>(
         }
     }
 
+    // the companion tracking the extension rewards is closed along, if passed
+    let position_reward_extension_key =
+        PositionRewardExtension::key(ctx.accounts.position_nft_mint.key());
+    if let Some(account_info) = ctx
+        .remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == position_reward_extension_key)
+    {
+        let reward_amounts_owed = AccountLoad::<PositionRewardExtension>::try_from(account_info)?
+            .load()?
+            .reward_amounts_owed;
+        if reward_amounts_owed.iter().any(|amount| *amount != 0) {
+            msg!("remaing extension rewards:{:?}", reward_amounts_owed);
+            return err!(ErrorCode::ClosePositionErr);
+        }
+        close_account(account_info, &ctx.accounts.nft_owner.to_account_info())?;
+    }
+
     let token_program = ctx.accounts.token_program.to_account_info();
     let position_nft_mint = ctx.accounts.position_nft_mint.to_account_info();
     let personal_nft_account = ctx.accounts.position_nft_account.to_account_info();
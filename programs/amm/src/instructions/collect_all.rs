@@ -0,0 +1,423 @@
+use super::{
+    calculate_latest_token_fees, check_required_accounts_length, check_unclaimed_fees_and_vault,
+    claim_extension_reward, claim_reward, next_reward_group, update_position,
+};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// The number of remaining accounts of a position group: the personal position, the NFT token
+/// account, the protocol position, the lower and the upper tick arrays
+pub const COLLECT_POSITION_ACCOUNT_NUM: usize = 5;
+
+#[derive(Accounts)]
+pub struct CollectAll<'info> {
+    /// The owner of the positions or a delegate allowed to collect them
+    pub nft_owner: Signer<'info>,
+
+    /// The pool of the positions
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for the token_0 fees of all positions
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for the token_1 fees of all positions
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // position groups, first: personal_position, nft_account, protocol_position,
+    // tick_array_lower and tick_array_upper of each position, all mutable but the nft account
+    // reward groups: reward_token_vault, recipient_token_account, reward_vault_mint of each pool
+    // reward then of each extension reward
+    // position_delegate: must add account for each position the signer is a delegate of
+    // reward extension accounts: must add the reward extension, the position companions and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
+}
+
+/// Collects the fees and rewards owed to `position_count` positions of the pool, paid out with
+/// one transfer per vault
+pub fn collect_all<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectAll<'info>>,
+    position_count: u8,
+) -> Result<()> {
+    let position_accounts_len = usize::from(position_count) * COLLECT_POSITION_ACCOUNT_NUM;
+    require!(
+        position_count > 0 && ctx.remaining_accounts.len() >= position_accounts_len,
+        ErrorCode::InvalidPositionInputAccountNumber
+    );
+    let (position_accounts, remaining_accounts) =
+        ctx.remaining_accounts.split_at(position_accounts_len);
+    let pool_state_loader = &ctx.accounts.pool_state;
+    let pool_id = pool_state_loader.key();
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    let collect_fee;
+    let collect_reward;
+    let mut reward_extension;
+    {
+        let mut pool_state = pool_state_loader.load_mut()?;
+        collect_fee = pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee);
+        collect_reward = pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward);
+        if !collect_fee && !collect_reward {
+            return err!(ErrorCode::NotApproved);
+        }
+        update_scheduled_reward_infos(
+            &mut pool_state,
+            pool_id,
+            remaining_accounts,
+            current_timestamp,
+        )?;
+        reward_extension = RewardExtensionAccounts::load(
+            &pool_state,
+            pool_id,
+            None,
+            remaining_accounts,
+            current_timestamp,
+        )?;
+    }
+
+    // the rewards are paid out together once all positions are settled
+    let mut reward_groups = Vec::new();
+    let mut pool_reward_count = 0;
+    if collect_reward {
+        let reward_accounts = remaining_accounts
+            .iter()
+            .filter(|account_info| {
+                !RewardExtensionAccounts::is_extension_account(account_info)
+                    && !RewardExtensionAccounts::has_discriminator(
+                        account_info,
+                        PositionDelegateState::DISCRIMINATOR,
+                    )
+            })
+            .collect::<Vec<_>>();
+        check_required_accounts_length(pool_state_loader, &reward_accounts, 3)?;
+        pool_reward_count = reward_accounts.len() / 3
+            - usize::from(pool_state_loader.load()?.extension_reward_count);
+        let mut reward_accounts = reward_accounts.iter();
+        while reward_accounts.len() > 0 {
+            let reward_group = next_reward_group(&mut reward_accounts, true, None)?;
+            let reward_index = reward_groups.len();
+            require_keys_eq!(
+                reward_group.0.key(),
+                match &reward_extension {
+                    Some(reward_extension) if reward_index >= pool_reward_count => {
+                        reward_extension.reward_infos[reward_index - pool_reward_count].token_vault
+                    }
+                    _ => pool_state_loader.load()?.reward_infos[reward_index].token_vault,
+                }
+            );
+            reward_groups.push(reward_group);
+        }
+    }
+    let mut vault_amounts = reward_groups
+        .iter()
+        .map(|(reward_token_vault, _, _)| reward_token_vault.amount)
+        .collect::<Vec<_>>();
+    let mut reward_amounts = vec![0u64; reward_groups.len()];
+
+    let mut nft_holder = None;
+    let mut fee_amount_0 = 0u64;
+    let mut fee_amount_1 = 0u64;
+    for accounts in position_accounts.chunks(COLLECT_POSITION_ACCOUNT_NUM) {
+        let mut personal_position = Account::<PersonalPositionState>::try_from(&accounts[0])?;
+        require_keys_eq!(personal_position.pool_id, pool_id);
+
+        let nft_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+        require_keys_eq!(nft_account.mint, personal_position.nft_mint);
+        require_eq!(nft_account.amount, 1);
+        let position_delegate = check_position_authority(
+            ctx.accounts.nft_owner.key(),
+            nft_account.owner,
+            personal_position.nft_mint,
+            remaining_accounts,
+            PositionDelegatePermission::Collect,
+        )?;
+        if let Some(position_delegate) = position_delegate {
+            // a delegate can only pay out to the position owner
+            require_keys_eq!(
+                ctx.accounts.recipient_token_account_0.owner,
+                position_delegate.nft_holder,
+                ErrorCode::NotApproved
+            );
+            require_keys_eq!(
+                ctx.accounts.recipient_token_account_1.owner,
+                position_delegate.nft_holder,
+                ErrorCode::NotApproved
+            );
+            nft_holder = Some(position_delegate.nft_holder);
+        }
+
+        let mut protocol_position = Account::<ProtocolPositionState>::try_from(&accounts[2])?;
+        let tick_lower = personal_position.tick_lower_index;
+        let tick_upper = personal_position.tick_upper_index;
+        require_keys_eq!(
+            protocol_position.key(),
+            Pubkey::create_program_address(
+                &[
+                    POSITION_SEED.as_bytes(),
+                    pool_id.as_ref(),
+                    &tick_lower.to_be_bytes(),
+                    &tick_upper.to_be_bytes(),
+                    &[protocol_position.bump],
+                ],
+                &crate::id(),
+            )
+            .map_err(|_| anchor_lang::error::ErrorCode::ConstraintSeeds)?,
+            anchor_lang::error::ErrorCode::ConstraintSeeds
+        );
+        let tick_array_lower_loader = AccountLoad::<TickArrayState>::try_from(&accounts[3])?;
+        let tick_array_upper_loader = AccountLoad::<TickArrayState>::try_from(&accounts[4])?;
+        let tick_array_lower = tick_array_lower_loader.load()?;
+        let tick_array_upper = tick_array_upper_loader.load()?;
+        require_keys_eq!(tick_array_lower.pool_id, pool_id);
+        require_keys_eq!(tick_array_upper.pool_id, pool_id);
+
+        let mut pool_state = pool_state_loader.load_mut()?;
+        // settle the fees and rewards earned up to now, the ticks are left unchanged
+        let mut tick_lower_state = tick_array_lower.ticks
+            [tick_array_lower.get_tick_offset_in_array(tick_lower, pool_state.tick_spacing)?];
+        let mut tick_upper_state = tick_array_upper.ticks
+            [tick_array_upper.get_tick_offset_in_array(tick_upper, pool_state.tick_spacing)?];
+        update_position(
+            0,
+            &mut pool_state,
+            &mut protocol_position,
+            &mut tick_lower_state,
+            &mut tick_upper_state,
+            current_timestamp,
+            0,
+        )?;
+
+        personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+            personal_position.token_fees_owed_0,
+            personal_position.fee_growth_inside_0_last_x64,
+            protocol_position.fee_growth_inside_0_last_x64,
+            personal_position.liquidity,
+        );
+        personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+            personal_position.token_fees_owed_1,
+            personal_position.fee_growth_inside_1_last_x64,
+            protocol_position.fee_growth_inside_1_last_x64,
+            personal_position.liquidity,
+        );
+        personal_position.fee_growth_inside_0_last_x64 =
+            protocol_position.fee_growth_inside_0_last_x64;
+        personal_position.fee_growth_inside_1_last_x64 =
+            protocol_position.fee_growth_inside_1_last_x64;
+        personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+        if let Some(reward_extension) = reward_extension.as_mut() {
+            reward_extension.position_extension = Some(
+                RewardExtensionAccounts::find_position_extension(
+                    personal_position.nft_mint,
+                    remaining_accounts,
+                )?
+                .ok_or(ErrorCode::MissingRewardExtensionAccount)?,
+            );
+            reward_extension.update_position(
+                pool_state.tick_current,
+                pool_state.tick_spacing,
+                &tick_array_lower,
+                &tick_array_upper,
+                tick_lower,
+                tick_upper,
+                0,
+                personal_position.liquidity,
+            )?;
+        }
+
+        let mut latest_fees_owed_0 = 0;
+        let mut latest_fees_owed_1 = 0;
+        if collect_fee {
+            latest_fees_owed_0 = personal_position.token_fees_owed_0;
+            latest_fees_owed_1 = personal_position.token_fees_owed_1;
+            require_gte!(
+                pool_state.total_fees_token_0 - pool_state.total_fees_claimed_token_0,
+                latest_fees_owed_0
+            );
+            require_gte!(
+                pool_state.total_fees_token_1 - pool_state.total_fees_claimed_token_1,
+                latest_fees_owed_1
+            );
+            personal_position.token_fees_owed_0 = 0;
+            personal_position.token_fees_owed_1 = 0;
+            pool_state.total_fees_claimed_token_0 = pool_state
+                .total_fees_claimed_token_0
+                .checked_add(latest_fees_owed_0)
+                .unwrap();
+            pool_state.total_fees_claimed_token_1 = pool_state
+                .total_fees_claimed_token_1
+                .checked_add(latest_fees_owed_1)
+                .unwrap();
+            fee_amount_0 = fee_amount_0.checked_add(latest_fees_owed_0).unwrap();
+            fee_amount_1 = fee_amount_1.checked_add(latest_fees_owed_1).unwrap();
+        }
+
+        let mut position_reward_amounts = [0u64; REWARD_NUM];
+        let mut extension_reward_amounts = [0u64; EXTENSION_REWARD_NUM];
+        for i in 0..reward_groups.len() {
+            let claim_amount = if i < pool_reward_count {
+                position_reward_amounts[i] =
+                    claim_reward(&mut pool_state, &mut personal_position, i, vault_amounts[i])?;
+                position_reward_amounts[i]
+            } else {
+                let reward_extension = reward_extension
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingRewardExtensionAccount)?;
+                extension_reward_amounts[i - pool_reward_count] = claim_extension_reward(
+                    reward_extension,
+                    i - pool_reward_count,
+                    vault_amounts[i],
+                )?;
+                extension_reward_amounts[i - pool_reward_count]
+            };
+            vault_amounts[i] -= claim_amount;
+            reward_amounts[i] = reward_amounts[i].checked_add(claim_amount).unwrap();
+        }
+        if collect_reward && reward_extension.is_some() {
+            emit!(CollectExtensionRewardEvent {
+                position_nft_mint: personal_position.nft_mint,
+                reward_amounts: extension_reward_amounts,
+            });
+        }
+        emit!(DecreaseLiquidityEvent {
+            position_nft_mint: personal_position.nft_mint,
+            liquidity: 0,
+            decrease_amount_0: 0,
+            decrease_amount_1: 0,
+            fee_amount_0: latest_fees_owed_0,
+            fee_amount_1: latest_fees_owed_1,
+            reward_amounts: position_reward_amounts,
+            transfer_fee_0: 0,
+            transfer_fee_1: 0,
+        });
+
+        drop(pool_state);
+        personal_position.exit(&crate::id())?;
+        protocol_position.exit(&crate::id())?;
+    }
+
+    if let Some(nft_holder) = nft_holder {
+        for (_, recipient_token_account, _) in reward_groups.iter() {
+            require_keys_eq!(
+                recipient_token_account.owner,
+                nft_holder,
+                ErrorCode::NotApproved
+            );
+        }
+    }
+
+    let token_program_2022 = Some(ctx.accounts.token_program_2022.to_account_info());
+    transfer_from_pool_vault_to_user(
+        pool_state_loader,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        token_program_2022.clone(),
+        fee_amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        pool_state_loader,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        token_program_2022.clone(),
+        fee_amount_1,
+    )?;
+    check_unclaimed_fees_and_vault(
+        pool_state_loader,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    for ((reward_token_vault, recipient_token_account, reward_vault_mint), reward_amount) in
+        reward_groups.into_iter().zip(reward_amounts)
+    {
+        transfer_from_pool_vault_to_user(
+            pool_state_loader,
+            &reward_token_vault.to_account_info(),
+            &recipient_token_account.to_account_info(),
+            reward_vault_mint,
+            &ctx.accounts.token_program,
+            token_program_2022.clone(),
+            reward_amount,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod collect_all_test {
+    use super::*;
+    use std::convert::identity;
+
+    #[test]
+    fn claim_reward_across_positions_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.reward_infos[0].reward_total_emissioned = 2000;
+        let mut positions = [600u64, 700].map(|reward_amount_owed| {
+            let mut personal_position = PersonalPositionState::default();
+            personal_position.reward_infos[0].reward_amount_owed = reward_amount_owed;
+            personal_position
+        });
+
+        // the vault holds less than owed, the positions claim in turn from what is left
+        let mut vault_amount = 1000;
+        let mut claim_amounts = Vec::new();
+        for personal_position in positions.iter_mut() {
+            let claim_amount =
+                claim_reward(&mut pool_state, personal_position, 0, vault_amount).unwrap();
+            vault_amount -= claim_amount;
+            claim_amounts.push(claim_amount);
+        }
+        assert_eq!(claim_amounts, vec![600, 400]);
+        assert_eq!(positions[0].reward_infos[0].reward_amount_owed, 0);
+        assert_eq!(positions[1].reward_infos[0].reward_amount_owed, 300);
+        assert_eq!(identity(pool_state.reward_infos[0].reward_claimed), 1000);
+    }
+}
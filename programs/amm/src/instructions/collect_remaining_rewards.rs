@@ -13,7 +13,7 @@ pub const COLLECT_REMAINING_MEMO_MSG: &'static [u8] = b"raydium_collect_remainin
 
 #[derive(Accounts)]
 pub struct CollectRemainingRewards<'info> {
-    /// The founder who init reward info previously
+    /// The founder who init reward info previously, or a funder who deposited with fund_reward
     pub reward_funder: Signer<'info>,
     /// The funder's reward token account
     #[account(mut)]
@@ -35,10 +35,15 @@ pub struct CollectRemainingRewards<'info> {
 
     /// memo program
     pub memo_program: Program<'info, Memo>,
+    // remaining account
+    // reward_extension: must add account if the reward index is of an extension reward
+    // reward_funding: must add account if the reward has funders
+    // reward_schedule: must add account for each pool reward following a schedule
+    // reward_funder: the deposits of the signer if it funded the reward, refunded pro rata
 }
 
-pub fn collect_remaining_rewards(
-    ctx: Context<CollectRemainingRewards>,
+pub fn collect_remaining_rewards<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectRemainingRewards<'info>>,
     reward_index: u8,
 ) -> Result<()> {
     // invoke_memo_instruction(
@@ -49,6 +54,7 @@ pub fn collect_remaining_rewards(
         &ctx.accounts.pool_state,
         &ctx.accounts.reward_token_vault,
         &ctx.accounts.reward_funder.key(),
+        ctx.remaining_accounts,
         reward_index,
     )?;
 
@@ -65,17 +71,101 @@ pub fn collect_remaining_rewards(
     Ok(())
 }
 
-fn get_remaining_reward_amount(
-    pool_state_loader: &AccountLoader<PoolState>,
-    reward_token_vault: &InterfaceAccount<TokenAccount>,
+fn get_remaining_reward_amount<'info>(
+    pool_state_loader: &AccountLoader<'info, PoolState>,
+    reward_token_vault: &InterfaceAccount<'info, TokenAccount>,
     reward_funder: &Pubkey,
+    remaining_accounts: &'info [AccountInfo<'info>],
     reward_index: u8,
 ) -> Result<u64> {
+    let reward_info = get_ended_reward_info(pool_state_loader, remaining_accounts, reward_index)?;
+    require_keys_eq!(reward_token_vault.key(), reward_info.token_vault);
+    let pool_state = pool_state_loader.load()?;
+
+    if pool_state.funded_reward_flags & (1 << reward_index) == 0 {
+        require_keys_eq!(reward_funder.key(), pool_state.owner);
+        let amount_remaining = reward_token_vault
+            .amount
+            .checked_sub(
+                reward_info
+                    .reward_total_emissioned
+                    .checked_sub(reward_info.reward_claimed)
+                    .unwrap(),
+            )
+            .unwrap();
+        return Ok(amount_remaining);
+    }
+
+    // the reward has funders, the tokens left unemitted are split pro rata on the deposits
+    let unowed_amount = get_unowed_reward_amount(&reward_info, reward_token_vault.amount);
+    let reward_funding_key = RewardFundingState::key(pool_state_loader.key(), reward_index);
+    let reward_funding_info = remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == reward_funding_key)
+        .ok_or(ErrorCode::MissingRewardFundingAccount)?;
+    let mut reward_funding = Account::<RewardFundingState>::try_from(reward_funding_info)?;
+    reward_funding.settle(unowed_amount);
+
+    let mut amount_remaining = 0;
+    let reward_funder_key = RewardFunderState::key(reward_funding_key, reward_funder.key());
+    let reward_funder_info = remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == reward_funder_key);
+    if let Some(reward_funder_info) = reward_funder_info {
+        let mut reward_funder_state = Account::<RewardFunderState>::try_from(reward_funder_info)?;
+        amount_remaining = reward_funding.refund(&mut reward_funder_state);
+        reward_funder_state.exit(&crate::id())?;
+    }
+    if reward_funder.key() == pool_state.owner {
+        // the owner collects what is not kept aside for the funders not refunded yet
+        amount_remaining += unowed_amount
+            .checked_sub(amount_remaining)
+            .unwrap()
+            .saturating_sub(reward_funding.reserved_amount());
+    } else {
+        require!(reward_funder_info.is_some(), ErrorCode::NotApproved);
+    }
+    reward_funding.exit(&crate::id())?;
+
+    Ok(amount_remaining)
+}
+
+/// Brings the rewards of the pool up to date and returns the reward info of `reward_index`, which
+/// must have ended
+pub fn get_ended_reward_info<'info>(
+    pool_state_loader: &AccountLoader<'info, PoolState>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    reward_index: u8,
+) -> Result<RewardInfo> {
     let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
     let mut pool_state = pool_state_loader.load_mut()?;
+    update_scheduled_reward_infos(
+        &mut pool_state,
+        pool_state_loader.key(),
+        remaining_accounts,
+        current_timestamp,
+    )?;
     pool_state.update_reward_infos(current_timestamp)?;
 
-    let reward_info = pool_state.reward_infos[reward_index as usize];
+    let reward_info = if reward_index as usize >= REWARD_NUM {
+        // the reward indexes from REWARD_NUM are of the reward extension
+        require_gt!(
+            REWARD_NUM + EXTENSION_REWARD_NUM,
+            reward_index as usize,
+            ErrorCode::InvalidRewardIndex
+        );
+        let reward_extension = RewardExtensionAccounts::find_reward_extension(
+            pool_state_loader.key(),
+            remaining_accounts,
+        )?
+        .ok_or(ErrorCode::MissingRewardExtensionAccount)?;
+        let reward_infos = reward_extension
+            .load_mut()?
+            .update_reward_infos(current_timestamp, pool_state.liquidity)?;
+        reward_infos[reward_index as usize - REWARD_NUM]
+    } else {
+        pool_state.reward_infos[reward_index as usize]
+    };
     if !reward_info.initialized() {
         return err!(ErrorCode::UnInitializedRewardInfo);
     }
@@ -84,18 +174,5 @@ fn get_remaining_reward_amount(
         reward_info.end_time,
         ErrorCode::NotApproved
     );
-    require_keys_eq!(reward_funder.key(), pool_state.owner);
-    require_keys_eq!(reward_token_vault.key(), reward_info.token_vault);
-
-    let amount_remaining = reward_token_vault
-        .amount
-        .checked_sub(
-            reward_info
-                .reward_total_emissioned
-                .checked_sub(reward_info.reward_claimed)
-                .unwrap(),
-        )
-        .unwrap();
-
-    Ok(amount_remaining)
+    Ok(reward_info)
 }
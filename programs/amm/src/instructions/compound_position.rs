@@ -0,0 +1,317 @@
+use super::{calculate_latest_token_fees, find_observation_state, mint_liquidity, update_position};
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct CompoundPosition<'info> {
+    /// The position owner or a delegate
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Reinvest the fees of this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // observation_state: should add the pool observation account, an oracle observation is
+    // written before the liquidity changes
+    // reward extension accounts: must add the reward extension, the position companion and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
+}
+
+pub fn compound_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CompoundPosition<'info>>,
+) -> Result<()> {
+    check_position_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        ctx.accounts.personal_position.nft_mint,
+        ctx.remaining_accounts,
+        PositionDelegatePermission::Compound,
+    )?;
+    let pool_state_loader = &ctx.accounts.pool_state;
+    let pool_state = &mut pool_state_loader.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+        || !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    let protocol_position = &mut ctx.accounts.protocol_position;
+    let personal_position = &mut ctx.accounts.personal_position;
+    let tick_lower = personal_position.tick_lower_index;
+    let tick_upper = personal_position.tick_upper_index;
+    let tick_array_lower_loader =
+        AccountLoad::<TickArrayState>::try_from(&ctx.accounts.tick_array_lower.to_account_info())?;
+    let tick_array_upper_loader =
+        AccountLoad::<TickArrayState>::try_from(&ctx.accounts.tick_array_upper.to_account_info())?;
+    update_scheduled_reward_infos(
+        pool_state,
+        pool_state_loader.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp as u64,
+    )?;
+    let reward_extension = RewardExtensionAccounts::load(
+        pool_state,
+        pool_state_loader.key(),
+        Some(personal_position.nft_mint),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp as u64,
+    )?;
+
+    // settle the fees and rewards earned up to now
+    let mut tick_lower_state = *tick_array_lower_loader
+        .load_mut()?
+        .get_tick_state_mut(tick_lower, pool_state.tick_spacing)?;
+    let mut tick_upper_state = *tick_array_upper_loader
+        .load_mut()?
+        .get_tick_state_mut(tick_upper, pool_state.tick_spacing)?;
+    update_position(
+        0,
+        pool_state,
+        protocol_position,
+        &mut tick_lower_state,
+        &mut tick_upper_state,
+        Clock::get()?.unix_timestamp as u64,
+        0,
+    )?;
+
+    personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_0,
+        personal_position.fee_growth_inside_0_last_x64,
+        protocol_position.fee_growth_inside_0_last_x64,
+        personal_position.liquidity,
+    );
+    personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_1,
+        personal_position.fee_growth_inside_1_last_x64,
+        protocol_position.fee_growth_inside_1_last_x64,
+        personal_position.liquidity,
+    );
+    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+
+    // update rewards, must update before increase liquidity
+    personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+
+    let (liquidity, amount_0, amount_1) = get_compound_liquidity(
+        pool_state.tick_current,
+        pool_state.sqrt_price_x64,
+        tick_lower,
+        tick_upper,
+        personal_position.token_fees_owed_0,
+        personal_position.token_fees_owed_1,
+    )?;
+    if liquidity > 0 {
+        let liquidity_before = pool_state.liquidity;
+        let use_tickarray_bitmap_extension =
+            pool_state.is_overflow_default_tickarray_bitmap(vec![tick_lower, tick_upper]);
+        let (amount_0, amount_1) = mint_liquidity(
+            pool_state,
+            &tick_array_lower_loader,
+            &tick_array_upper_loader,
+            protocol_position,
+            if use_tickarray_bitmap_extension {
+                require_keys_eq!(
+                    ctx.remaining_accounts[0].key(),
+                    TickArrayBitmapExtension::key(pool_state_loader.key())
+                );
+                Some(&ctx.remaining_accounts[0])
+            } else {
+                None
+            },
+            find_observation_state(pool_state, ctx.remaining_accounts),
+            liquidity,
+            tick_lower,
+            tick_upper,
+        )?;
+
+        // the reinvested fees stay in the vaults as liquidity, they are claimed from the fee side
+        personal_position.token_fees_owed_0 = personal_position
+            .token_fees_owed_0
+            .checked_sub(amount_0)
+            .unwrap();
+        personal_position.token_fees_owed_1 = personal_position
+            .token_fees_owed_1
+            .checked_sub(amount_1)
+            .unwrap();
+        pool_state.total_fees_claimed_token_0 = pool_state
+            .total_fees_claimed_token_0
+            .checked_add(amount_0)
+            .unwrap();
+        pool_state.total_fees_claimed_token_1 = pool_state
+            .total_fees_claimed_token_1
+            .checked_add(amount_1)
+            .unwrap();
+        personal_position.liquidity = personal_position.liquidity.checked_add(liquidity).unwrap();
+
+        emit!(LiquidityChangeEvent {
+            pool_state: pool_state_loader.key(),
+            tick: pool_state.tick_current,
+            tick_lower,
+            tick_upper,
+            liquidity_before,
+            liquidity_after: pool_state.liquidity,
+        });
+    }
+    if let Some(reward_extension) = reward_extension {
+        reward_extension.update_position(
+            pool_state.tick_current,
+            pool_state.tick_spacing,
+            &*tick_array_lower_loader.load()?,
+            &*tick_array_upper_loader.load()?,
+            tick_lower,
+            tick_upper,
+            i128::try_from(liquidity).unwrap(),
+            personal_position.liquidity,
+        )?;
+    }
+
+    emit!(CompoundPositionEvent {
+        position_nft_mint: personal_position.nft_mint,
+        liquidity,
+        amount_0,
+        amount_1,
+        token_fees_owed_0: personal_position.token_fees_owed_0,
+        token_fees_owed_1: personal_position.token_fees_owed_1,
+    });
+
+    Ok(())
+}
+
+/// Returns the largest liquidity that `amount_0` and `amount_1` can be added as at the current
+/// price, together with the token amounts it takes
+pub fn get_compound_liquidity(
+    tick_current: i32,
+    sqrt_price_x64: u128,
+    tick_lower: i32,
+    tick_upper: i32,
+    amount_0: u64,
+    amount_1: u64,
+) -> Result<(u128, u64, u64)> {
+    let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(tick_lower)?;
+    let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(tick_upper)?;
+    // the amounts of a liquidity are rounded up, so they may exceed the given amounts by one unit,
+    // in which case the liquidity of one unit less is used
+    for deduction in 0..2 {
+        let liquidity = liquidity_math::get_liquidity_from_amounts(
+            sqrt_price_x64,
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            amount_0.saturating_sub(deduction),
+            amount_1.saturating_sub(deduction),
+        );
+        if liquidity == 0 {
+            break;
+        }
+        let (liquidity_amount_0, liquidity_amount_1) = liquidity_math::get_delta_amounts_signed(
+            tick_current,
+            sqrt_price_x64,
+            tick_lower,
+            tick_upper,
+            i128::try_from(liquidity).unwrap(),
+        )?;
+        if liquidity_amount_0 <= amount_0 && liquidity_amount_1 <= amount_1 {
+            return Ok((liquidity, liquidity_amount_0, liquidity_amount_1));
+        }
+    }
+    Ok((0, 0, 0))
+}
+
+#[cfg(test)]
+mod compound_position_test {
+    use super::*;
+
+    #[test]
+    fn compound_liquidity_in_range_test() {
+        let tick_current = 10;
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+        let (liquidity, amount_0, amount_1) =
+            get_compound_liquidity(tick_current, sqrt_price_x64, -100, 100, 1_000_000, 30_000)
+                .unwrap();
+        assert!(liquidity > 0);
+        // token_1 is the limiting side, the rest of token_0 stays owed
+        assert!(amount_1 <= 30_000 && amount_1 > 29_990);
+        assert!(amount_0 < 1_000_000);
+    }
+
+    #[test]
+    fn compound_liquidity_out_of_range_test() {
+        let tick_current = 200;
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+        // only token_1 is needed above the range
+        let (liquidity, amount_0, amount_1) =
+            get_compound_liquidity(tick_current, sqrt_price_x64, -100, 100, 1_000_000, 30_000)
+                .unwrap();
+        assert!(liquidity > 0);
+        assert_eq!(amount_0, 0);
+        assert!(amount_1 <= 30_000);
+
+        // nothing to reinvest without token_1
+        assert_eq!(
+            get_compound_liquidity(tick_current, sqrt_price_x64, -100, 100, 1_000_000, 0).unwrap(),
+            (0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn compound_liquidity_fits_amounts_test() {
+        for tick_current in [-50, -1, 0, 7, 99] {
+            let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+            for (amount_0, amount_1) in [(1, 1), (3, 7), (12345, 678), (u32::MAX as u64, 999)] {
+                let (_, used_0, used_1) = get_compound_liquidity(
+                    tick_current,
+                    sqrt_price_x64,
+                    -100,
+                    100,
+                    amount_0,
+                    amount_1,
+                )
+                .unwrap();
+                assert!(used_0 <= amount_0);
+                assert!(used_1 <= amount_1);
+            }
+        }
+    }
+}
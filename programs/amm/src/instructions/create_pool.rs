@@ -1,7 +1,7 @@
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::{libraries::tick_math, util};
-use anchor_lang::{prelude::*, solana_program};
+use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 // use solana_program::{program::invoke_signed, system_instruction};
 #[derive(Accounts)]
@@ -143,8 +143,6 @@ pub fn create_pool(ctx: Context<CreatePool>, sqrt_price_x64: u128, open_time: u6
     {
         return err!(ErrorCode::NotSupportMint);
     }
-    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
-    require_gt!(block_timestamp, open_time);
     let pool_id = ctx.accounts.pool_state.key();
     let mut pool_state = ctx.accounts.pool_state.load_init()?;
 
@@ -165,7 +163,7 @@ pub fn create_pool(ctx: Context<CreatePool>, sqrt_price_x64: u128, open_time: u6
     pool_state.initialize(
         bump,
         sqrt_price_x64,
-        0,
+        open_time,
         tick,
         ctx.accounts.pool_creator.key(),
         ctx.accounts.token_vault_0.key(),
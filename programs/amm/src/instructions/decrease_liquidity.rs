@@ -1,5 +1,5 @@
 use super::calculate_latest_token_fees;
-use super::modify_position;
+use super::{modify_position, update_observation_before_liquidity_change};
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::util::{self, transfer_from_pool_vault_to_user};
@@ -20,8 +20,7 @@ pub struct DecreaseLiquidity<'info> {
     /// The token account for the tokenized position
     #[account(
         constraint = nft_account.mint == personal_position.nft_mint,
-        constraint = nft_account.amount == 1,
-        token::authority = nft_owner
+        constraint = nft_account.amount == 1
     )]
     pub nft_account: Box<Account<'info, TokenAccount>>,
 
@@ -92,6 +91,11 @@ pub struct DecreaseLiquidity<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // observation_state: should add the pool observation account, an oracle observation is
+    // written before the liquidity changes
+    // reward extension accounts: must add the reward extension, the position companion and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
 }
 
 pub fn decrease_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
@@ -100,6 +104,30 @@ pub fn decrease_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
     amount_0_min: u64,
     amount_1_min: u64,
 ) -> Result<()> {
+    let position_delegate = check_position_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        ctx.accounts.personal_position.nft_mint,
+        ctx.remaining_accounts,
+        if liquidity == 0 {
+            PositionDelegatePermission::Collect
+        } else {
+            PositionDelegatePermission::DecreaseLiquidity
+        },
+    )?;
+    if let Some(position_delegate) = position_delegate {
+        // a delegate can only pay out to the position owner
+        require_keys_eq!(
+            ctx.accounts.recipient_token_account_0.owner,
+            position_delegate.nft_holder,
+            ErrorCode::NotApproved
+        );
+        require_keys_eq!(
+            ctx.accounts.recipient_token_account_1.owner,
+            position_delegate.nft_holder,
+            ErrorCode::NotApproved
+        );
+    }
     decrease_liquidity(
         &ctx.accounts.pool_state,
         &mut ctx.accounts.protocol_position,
@@ -116,10 +144,12 @@ pub fn decrease_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
         None,
         None,
         &ctx.remaining_accounts,
+        position_delegate,
         liquidity,
         amount_0_min,
         amount_1_min,
-    )
+    )?;
+    Ok(())
 }
 
 pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
@@ -138,10 +168,11 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
     vault_0_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
     vault_1_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
     remaining_accounts: &'c [AccountInfo<'info>],
+    position_delegate: Option<PositionDelegateAuthority>,
     liquidity: u128,
     amount_0_min: u64,
     amount_1_min: u64,
-) -> Result<()> {
+) -> Result<LiquidityChangeResult> {
     // if accounts.memo_program.is_some() {
     //     let memp_program = accounts.memo_program.as_ref().unwrap().to_account_info();
     //     invoke_memo_instruction(DECREASE_MEMO_MSG, memp_program)?;
@@ -151,10 +182,12 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
     let pool_sqrt_price_x64;
     let pool_tick_current;
     let mut tickarray_bitmap_extension = None;
+    let mut observation_state = None;
+    let reward_extension;
 
     let remaining_collect_accounts = &mut Vec::new();
     {
-        let pool_state = pool_state_loader.load()?;
+        let mut pool_state = pool_state_loader.load_mut()?;
         if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
             && !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)
             && !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward)
@@ -164,6 +197,19 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         liquidity_before = pool_state.liquidity;
         pool_sqrt_price_x64 = pool_state.sqrt_price_x64;
         pool_tick_current = pool_state.tick_current;
+        update_scheduled_reward_infos(
+            &mut pool_state,
+            pool_state_loader.key(),
+            remaining_accounts,
+            Clock::get()?.unix_timestamp as u64,
+        )?;
+        reward_extension = RewardExtensionAccounts::load(
+            &pool_state,
+            pool_state_loader.key(),
+            Some(personal_position.nft_mint),
+            remaining_accounts,
+            Clock::get()?.unix_timestamp as u64,
+        )?;
 
         let use_tickarray_bitmap_extension = pool_state.is_overflow_default_tickarray_bitmap(vec![
             tick_array_lower_loader.load()?.start_tick_index,
@@ -178,6 +224,16 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
                 tickarray_bitmap_extension = Some(account_info);
                 continue;
             }
+            if account_info.key() == pool_state.observation_key {
+                observation_state = Some(account_info);
+                continue;
+            }
+            if position_delegate
+                .is_some_and(|delegate| account_info.key() == delegate.position_delegate)
+                || RewardExtensionAccounts::is_extension_account(account_info)
+            {
+                continue;
+            }
             remaining_collect_accounts.push(account_info);
         }
         if use_tickarray_bitmap_extension {
@@ -196,6 +252,8 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
             tick_array_lower_loader,
             tick_array_upper_loader,
             tickarray_bitmap_extension,
+            observation_state,
+            reward_extension.as_ref(),
             liquidity,
         )?;
 
@@ -283,6 +341,8 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         } else {
             true
         },
+        position_delegate.map(|delegate| delegate.nft_holder),
+        reward_extension.as_ref(),
     )?;
     emit!(DecreaseLiquidityEvent {
         position_nft_mint: personal_position.nft_mint,
@@ -296,7 +356,13 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         transfer_fee_1: transfer_fee_1,
     });
 
-    Ok(())
+    Ok(LiquidityChangeResult {
+        liquidity,
+        amount_0: decrease_amount_0,
+        amount_1: decrease_amount_1,
+        amount_0_transfer_fee: transfer_fee_0,
+        amount_1_transfer_fee: transfer_fee_1,
+    })
 }
 
 pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
@@ -306,6 +372,8 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
     tick_array_lower: &AccountLoader<'info, TickArrayState>,
     tick_array_upper: &AccountLoader<'info, TickArrayState>,
     tick_array_bitmap_extension: Option<&'c AccountInfo<'info>>,
+    observation_state: Option<&AccountInfo<'info>>,
+    reward_extension: Option<&RewardExtensionAccounts<'info>>,
     liquidity: u128,
 ) -> Result<(u64, u64, u64, u64)> {
     let mut pool_state = pool_state_loader.load_mut()?;
@@ -318,6 +386,7 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
             tick_array_upper,
             protocol_position,
             tick_array_bitmap_extension,
+            observation_state,
             liquidity,
         )?;
 
@@ -343,6 +412,18 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
         // update rewards, must update before decrease liquidity
         personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
         personal_position.liquidity = personal_position.liquidity.checked_sub(liquidity).unwrap();
+        if let Some(reward_extension) = reward_extension {
+            reward_extension.update_position(
+                pool_state.tick_current,
+                pool_state.tick_spacing,
+                &*tick_array_lower.load()?,
+                &*tick_array_upper.load()?,
+                personal_position.tick_lower_index,
+                personal_position.tick_upper_index,
+                -i128::try_from(liquidity).unwrap(),
+                personal_position.liquidity,
+            )?;
+        }
     }
 
     let mut latest_fees_owed_0 = 0;
@@ -387,6 +468,7 @@ pub fn burn_liquidity<'c: 'info, 'info>(
     tick_array_upper_loader: &AccountLoader<'info, TickArrayState>,
     protocol_position: &mut ProtocolPositionState,
     tickarray_bitmap_extension: Option<&'c AccountInfo<'info>>,
+    observation_state: Option<&AccountInfo<'info>>,
     liquidity: u128,
 ) -> Result<(u64, u64)> {
     require_keys_eq!(tick_array_lower_loader.load()?.pool_id, pool_state.key());
@@ -400,6 +482,11 @@ pub fn burn_liquidity<'c: 'info, 'info>(
         .load_mut()?
         .get_tick_state_mut(protocol_position.tick_upper_index, pool_state.tick_spacing)?;
     let clock = Clock::get()?;
+    let seconds_per_liquidity_cumulative_x64 = update_observation_before_liquidity_change(
+        pool_state,
+        observation_state,
+        clock.unix_timestamp as u32,
+    )?;
     let (amount_0, amount_1, flip_tick_lower, flip_tick_upper) = modify_position(
         -i128::try_from(liquidity).unwrap(),
         pool_state,
@@ -407,6 +494,7 @@ pub fn burn_liquidity<'c: 'info, 'info>(
         &mut tick_lower_state,
         &mut tick_upper_state,
         clock.unix_timestamp as u64,
+        seconds_per_liquidity_cumulative_x64,
     )?;
 
     // update tick_state
@@ -461,6 +549,8 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
     token_program_2022: Option<AccountInfo<'info>>,
     personal_position_state: &mut PersonalPositionState,
     need_reward_mint: bool,
+    recipient_owner: Option<Pubkey>,
+    reward_extension: Option<&RewardExtensionAccounts<'info>>,
 ) -> Result<[u64; REWARD_NUM]> {
     let mut reward_amounts: [u64; REWARD_NUM] = [0, 0, 0];
     if !pool_state_loader
@@ -479,41 +569,25 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
         reward_group_account_num,
     )?;
 
+    // the reward groups of the extension rewards follow the ones of the pool rewards
+    let extension_reward_count = usize::from(pool_state_loader.load()?.extension_reward_count);
     let remaining_accounts_len = remaining_accounts.len();
     let mut remaining_accounts = remaining_accounts.iter();
-    for i in 0..remaining_accounts_len / reward_group_account_num {
-        let reward_token_vault = InterfaceAccount::<token_interface::TokenAccount>::try_from(
-            remaining_accounts.next().unwrap(),
-        )?;
-        let recipient_token_account = InterfaceAccount::<token_interface::TokenAccount>::try_from(
-            remaining_accounts.next().unwrap(),
-        )?;
-
-        let mut reward_vault_mint: Option<Box<InterfaceAccount<Mint>>> = None;
-        if need_reward_mint {
-            reward_vault_mint = Some(Box::new(InterfaceAccount::<Mint>::try_from(
-                remaining_accounts.next().unwrap(),
-            )?));
-        }
-        require_keys_eq!(reward_token_vault.mint, recipient_token_account.mint);
+    for i in 0..remaining_accounts_len / reward_group_account_num - extension_reward_count {
+        let (reward_token_vault, recipient_token_account, reward_vault_mint) =
+            next_reward_group(&mut remaining_accounts, need_reward_mint, recipient_owner)?;
         require_keys_eq!(
             reward_token_vault.key(),
             pool_state_loader.load_mut()?.reward_infos[i].token_vault
         );
 
         let reward_amount_owed = personal_position_state.reward_infos[i].reward_amount_owed;
-        if reward_amount_owed == 0 {
-            continue;
-        }
-        pool_state_loader
-            .load()?
-            .check_unclaimed_reward(i, reward_amount_owed)?;
-
-        let transfer_amount = if reward_amount_owed > reward_token_vault.amount {
-            reward_token_vault.amount
-        } else {
-            reward_amount_owed
-        };
+        let transfer_amount = claim_reward(
+            &mut *pool_state_loader.load_mut()?,
+            personal_position_state,
+            i,
+            reward_token_vault.amount,
+        )?;
 
         if transfer_amount > 0 {
             msg!(
@@ -522,12 +596,6 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
                 transfer_amount,
                 reward_amount_owed
             );
-            personal_position_state.reward_infos[i].reward_amount_owed =
-                reward_amount_owed.checked_sub(transfer_amount).unwrap();
-            pool_state_loader
-                .load_mut()?
-                .add_reward_clamed(i, transfer_amount)?;
-
             transfer_from_pool_vault_to_user(
                 &pool_state_loader,
                 &reward_token_vault.to_account_info(),
@@ -541,16 +609,141 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
         reward_amounts[i] = transfer_amount
     }
 
+    if let Some(reward_extension) = reward_extension {
+        let mut extension_reward_amounts = [0u64; EXTENSION_REWARD_NUM];
+        for i in 0..extension_reward_count {
+            let (reward_token_vault, recipient_token_account, reward_vault_mint) =
+                next_reward_group(&mut remaining_accounts, need_reward_mint, recipient_owner)?;
+            require_keys_eq!(
+                reward_token_vault.key(),
+                reward_extension.reward_infos[i].token_vault
+            );
+
+            let transfer_amount =
+                claim_extension_reward(reward_extension, i, reward_token_vault.amount)?;
+            if transfer_amount > 0 {
+                transfer_from_pool_vault_to_user(
+                    &pool_state_loader,
+                    &reward_token_vault.to_account_info(),
+                    &recipient_token_account.to_account_info(),
+                    reward_vault_mint.clone(),
+                    &token_program,
+                    token_program_2022.clone(),
+                    transfer_amount,
+                )?;
+            }
+            extension_reward_amounts[i] = transfer_amount;
+        }
+        emit!(CollectExtensionRewardEvent {
+            position_nft_mint: personal_position_state.nft_mint,
+            reward_amounts: extension_reward_amounts,
+        });
+    }
+
     Ok(reward_amounts)
 }
 
-fn check_required_accounts_length(
+/// Takes what a position is owed of the pool reward `reward_index` off the records, up to
+/// `vault_amount` the tokens left in the reward vault. Returns the amount to pay out.
+pub fn claim_reward(
+    pool_state: &mut PoolState,
+    personal_position_state: &mut PersonalPositionState,
+    reward_index: usize,
+    vault_amount: u64,
+) -> Result<u64> {
+    let reward_amount_owed = personal_position_state.reward_infos[reward_index].reward_amount_owed;
+    if reward_amount_owed == 0 {
+        return Ok(0);
+    }
+    pool_state.check_unclaimed_reward(reward_index, reward_amount_owed)?;
+
+    let claim_amount = reward_amount_owed.min(vault_amount);
+    if claim_amount > 0 {
+        personal_position_state.reward_infos[reward_index].reward_amount_owed =
+            reward_amount_owed.checked_sub(claim_amount).unwrap();
+        pool_state.add_reward_clamed(reward_index, claim_amount)?;
+    }
+    Ok(claim_amount)
+}
+
+/// Takes what the position of `reward_extension` is owed of the extension reward `reward_index`
+/// off the records, up to `vault_amount`. Returns the amount to pay out.
+pub fn claim_extension_reward(
+    reward_extension: &RewardExtensionAccounts,
+    reward_index: usize,
+    vault_amount: u64,
+) -> Result<u64> {
+    let position_extension = reward_extension
+        .position_extension
+        .as_ref()
+        .ok_or(ErrorCode::MissingRewardExtensionAccount)?;
+    let reward_amount_owed = position_extension.load()?.reward_amounts_owed[reward_index];
+    if reward_amount_owed == 0 {
+        return Ok(0);
+    }
+    reward_extension
+        .reward_extension
+        .load()?
+        .check_unclaimed_reward(reward_index, reward_amount_owed)?;
+
+    let claim_amount = reward_amount_owed.min(vault_amount);
+    if claim_amount > 0 {
+        position_extension.load_mut()?.reward_amounts_owed[reward_index] =
+            reward_amount_owed.checked_sub(claim_amount).unwrap();
+        reward_extension
+            .reward_extension
+            .load_mut()?
+            .add_reward_clamed(reward_index, claim_amount)?;
+    }
+    Ok(claim_amount)
+}
+
+/// Reads the reward vault, the recipient token account and, if needed, the reward mint of the
+/// next reward group in the remaining accounts
+pub fn next_reward_group<'info>(
+    remaining_accounts: &mut std::slice::Iter<&'info AccountInfo<'info>>,
+    need_reward_mint: bool,
+    recipient_owner: Option<Pubkey>,
+) -> Result<(
+    InterfaceAccount<'info, token_interface::TokenAccount>,
+    InterfaceAccount<'info, token_interface::TokenAccount>,
+    Option<Box<InterfaceAccount<'info, Mint>>>,
+)> {
+    let reward_token_vault = InterfaceAccount::<token_interface::TokenAccount>::try_from(
+        remaining_accounts.next().unwrap(),
+    )?;
+    let recipient_token_account = InterfaceAccount::<token_interface::TokenAccount>::try_from(
+        remaining_accounts.next().unwrap(),
+    )?;
+
+    let mut reward_vault_mint: Option<Box<InterfaceAccount<Mint>>> = None;
+    if need_reward_mint {
+        reward_vault_mint = Some(Box::new(InterfaceAccount::<Mint>::try_from(
+            remaining_accounts.next().unwrap(),
+        )?));
+    }
+    require_keys_eq!(reward_token_vault.mint, recipient_token_account.mint);
+    if let Some(recipient_owner) = recipient_owner {
+        require_keys_eq!(
+            recipient_token_account.owner,
+            recipient_owner,
+            ErrorCode::NotApproved
+        );
+    }
+    Ok((
+        reward_token_vault,
+        recipient_token_account,
+        reward_vault_mint,
+    ))
+}
+
+pub fn check_required_accounts_length(
     pool_state_loader: &AccountLoader<PoolState>,
     remaining_accounts: &[&AccountInfo],
     reward_group_account_num: usize,
 ) -> Result<()> {
     let pool_state = pool_state_loader.load()?;
-    let mut valid_reward_count = 0;
+    let mut valid_reward_count = usize::from(pool_state.extension_reward_count);
     for item in pool_state.reward_infos {
         if item.initialized() {
             valid_reward_count = valid_reward_count + 1;
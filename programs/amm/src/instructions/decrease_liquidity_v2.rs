@@ -1,4 +1,5 @@
 use super::decrease_liquidity::decrease_liquidity;
+use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::prelude::*;
 use anchor_spl::memo::spl_memo;
@@ -13,8 +14,7 @@ pub struct DecreaseLiquidityV2<'info> {
     /// The token account for the tokenized position
     #[account(
         constraint = nft_account.mint == personal_position.nft_mint,
-        constraint = nft_account.amount == 1,
-        token::authority = nft_owner,
+        constraint = nft_account.amount == 1
     )]
     pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -106,6 +106,11 @@ pub struct DecreaseLiquidityV2<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // observation_state: should add the pool observation account, an oracle observation is
+    // written before the liquidity changes
+    // reward extension accounts: must add the reward extension, the position companion and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
 }
 
 pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
@@ -113,7 +118,31 @@ pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
     liquidity: u128,
     amount_0_min: u64,
     amount_1_min: u64,
-) -> Result<()> {
+) -> Result<LiquidityChangeResult> {
+    let position_delegate = check_position_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        ctx.accounts.personal_position.nft_mint,
+        ctx.remaining_accounts,
+        if liquidity == 0 {
+            PositionDelegatePermission::Collect
+        } else {
+            PositionDelegatePermission::DecreaseLiquidity
+        },
+    )?;
+    if let Some(position_delegate) = position_delegate {
+        // a delegate can only pay out to the position owner
+        require_keys_eq!(
+            ctx.accounts.recipient_token_account_0.owner,
+            position_delegate.nft_holder,
+            ErrorCode::NotApproved
+        );
+        require_keys_eq!(
+            ctx.accounts.recipient_token_account_1.owner,
+            position_delegate.nft_holder,
+            ErrorCode::NotApproved
+        );
+    }
     decrease_liquidity(
         &ctx.accounts.pool_state,
         &mut ctx.accounts.protocol_position,
@@ -130,6 +159,7 @@ pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
         Some(ctx.accounts.vault_0_mint.clone()),
         Some(ctx.accounts.vault_1_mint.clone()),
         &ctx.remaining_accounts,
+        position_delegate,
         liquidity,
         amount_0_min,
         amount_1_min,
@@ -0,0 +1,225 @@
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U128, full_math::MulDiv};
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct Flash<'info> {
+    /// The borrower, repays from its token accounts what the callback did not pay back
+    pub payer: Signer<'info>,
+
+    /// The factory state to read the trade fee rate
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The pool to borrow from
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The token account that receives and repays the token_0 borrowed
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account that receives and repays the token_1 borrowed
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: The borrower's program, called once the tokens are lent
+    #[account(
+        executable,
+        constraint = callback_program.key() != crate::id() @ ErrorCode::InvalidFlashCallbackProgram
+    )]
+    pub callback_program: UncheckedAccount<'info>,
+    // remaining accounts
+    // the accounts of the callback instruction
+}
+
+/// Lends `amount_0` and `amount_1` from the pool vaults and calls back the borrower's program
+/// with `data` followed by the two fees as little endian u64. The vaults must get back the
+/// principal plus the fees by the end of the instruction.
+pub fn flash<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, Flash<'info>>,
+    amount_0: u64,
+    amount_1: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    require!(amount_0 > 0 || amount_1 > 0, ErrorCode::ZeroAmountSpecified);
+    let trade_fee_rate = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap) {
+            return err!(ErrorCode::NotApproved);
+        }
+        require_gt!(pool_state.liquidity, 0, ErrorCode::LiquidityInsufficient);
+        pool_state.base_trade_fee_rate(&ctx.accounts.amm_config)
+    };
+    let fee_0 = get_flash_fee(amount_0, trade_fee_rate);
+    let fee_1 = get_flash_fee(amount_1, trade_fee_rate);
+    let balance_0_before = ctx.accounts.token_vault_0.amount;
+    let balance_1_before = ctx.accounts.token_vault_1.amount;
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+
+    let mut callback_data = data;
+    callback_data.extend_from_slice(&fee_0.to_le_bytes());
+    callback_data.extend_from_slice(&fee_1.to_le_bytes());
+    let callback_instruction = Instruction {
+        program_id: ctx.accounts.callback_program.key(),
+        accounts: ctx
+            .remaining_accounts
+            .iter()
+            .map(|account_info| {
+                if account_info.is_writable {
+                    AccountMeta::new(account_info.key(), account_info.is_signer)
+                } else {
+                    AccountMeta::new_readonly(account_info.key(), account_info.is_signer)
+                }
+            })
+            .collect(),
+        data: callback_data,
+    };
+    let mut callback_account_infos = ctx.remaining_accounts.to_vec();
+    callback_account_infos.push(ctx.accounts.callback_program.to_account_info());
+    invoke(&callback_instruction, &callback_account_infos)?;
+
+    // pull what the callback did not pay back from the borrower
+    ctx.accounts.token_vault_0.reload()?;
+    ctx.accounts.token_vault_1.reload()?;
+    let owed_0 = (balance_0_before + fee_0).saturating_sub(ctx.accounts.token_vault_0.amount);
+    let owed_1 = (balance_1_before + fee_1).saturating_sub(ctx.accounts.token_vault_1.amount);
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.payer,
+        &ctx.accounts.token_account_0.to_account_info(),
+        &ctx.accounts.token_vault_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        owed_0 + get_transfer_inverse_fee(ctx.accounts.vault_0_mint.clone(), owed_0)?,
+    )?;
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.payer,
+        &ctx.accounts.token_account_1.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        owed_1 + get_transfer_inverse_fee(ctx.accounts.vault_1_mint.clone(), owed_1)?,
+    )?;
+
+    ctx.accounts.token_vault_0.reload()?;
+    ctx.accounts.token_vault_1.reload()?;
+    require_gte!(
+        ctx.accounts.token_vault_0.amount,
+        balance_0_before + fee_0,
+        ErrorCode::FlashLoanNotRepaid
+    );
+    require_gte!(
+        ctx.accounts.token_vault_1.amount,
+        balance_1_before + fee_1,
+        ErrorCode::FlashLoanNotRepaid
+    );
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.accrue_fee(&ctx.accounts.amm_config, fee_0, true);
+    pool_state.accrue_fee(&ctx.accounts.amm_config, fee_1, false);
+
+    emit!(FlashEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        sender: ctx.accounts.payer.key(),
+        amount_0,
+        amount_1,
+        fee_0,
+        fee_1,
+        paid_0: ctx.accounts.token_vault_0.amount - (balance_0_before - amount_0),
+        paid_1: ctx.accounts.token_vault_1.amount - (balance_1_before - amount_1),
+    });
+
+    Ok(())
+}
+
+/// The fee of borrowing `amount`, charged at the trade fee rate and rounded up
+pub fn get_flash_fee(amount: u64, trade_fee_rate: u32) -> u64 {
+    U128::from(amount)
+        .mul_div_ceil(
+            U128::from(trade_fee_rate),
+            U128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+        .unwrap()
+        .as_u64()
+}
+
+#[cfg(test)]
+mod flash_test {
+    use super::*;
+
+    #[test]
+    fn get_flash_fee_test() {
+        assert_eq!(get_flash_fee(0, 2500), 0);
+        assert_eq!(get_flash_fee(1_000_000, 2500), 2500);
+        // rounded up in favor of the pool
+        assert_eq!(get_flash_fee(1, 2500), 1);
+        assert_eq!(get_flash_fee(1_000_001, 2500), 2501);
+        assert_eq!(get_flash_fee(u64::MAX, 0), 0);
+    }
+}
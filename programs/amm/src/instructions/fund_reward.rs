@@ -0,0 +1,308 @@
+use crate::error::ErrorCode;
+use crate::libraries::{fixed_point_64, full_math::MulDiv, U256};
+use crate::states::*;
+use crate::util::{get_transfer_inverse_fee, transfer_from_user_to_pool_vault};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct FundReward<'info> {
+    /// The funder, pays the deposit and the funding accounts rent
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// The pool the reward belongs to
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Tracks the deposits of the funders into the reward
+    #[account(
+        init_if_needed,
+        seeds = [
+            REWARD_FUNDING_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &[reward_index],
+        ],
+        bump,
+        payer = funder,
+        space = RewardFundingState::LEN
+    )]
+    pub reward_funding: Box<Account<'info, RewardFundingState>>,
+
+    /// Tracks the deposits of this funder
+    #[account(
+        init_if_needed,
+        seeds = [
+            REWARD_FUNDER_SEED.as_bytes(),
+            reward_funding.key().as_ref(),
+            funder.key().as_ref(),
+        ],
+        bump,
+        payer = funder,
+        space = RewardFunderState::LEN
+    )]
+    pub reward_funder: Box<Account<'info, RewardFunderState>>,
+
+    /// The funder's reward token account
+    #[account(
+        mut,
+        token::mint = reward_token_vault.mint
+    )]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The reward vault to deposit into
+    #[account(mut)]
+    pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of reward token vault
+    #[account(
+        address = reward_token_vault.mint
+    )]
+    pub reward_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Program to create the funding accounts
+    pub system_program: Program<'info, System>,
+    // remaining accounts
+    // reward_extension: must add account if the reward index is of an extension reward
+    // reward_schedule: must add account for each pool reward following a schedule
+}
+
+/// Deposits `amount` into the reward vault of a reward still emitting, and either extends its
+/// end time at the current emissions or raises its emissions until the end time
+pub fn fund_reward<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, FundReward<'info>>,
+    reward_index: u8,
+    amount: u64,
+    extend_end_time: bool,
+) -> Result<()> {
+    require_gt!(amount, 0, ErrorCode::ZeroAmountSpecified);
+    require_gt!(
+        REWARD_NUM + EXTENSION_REWARD_NUM,
+        reward_index as usize,
+        ErrorCode::InvalidRewardIndex
+    );
+    let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    update_scheduled_reward_infos(
+        &mut pool_state,
+        ctx.accounts.pool_state.key(),
+        ctx.remaining_accounts,
+        current_timestamp,
+    )?;
+    pool_state.update_reward_infos(current_timestamp)?;
+
+    // the reward indexes from REWARD_NUM are of the reward extension
+    let reward_extension = if reward_index as usize >= REWARD_NUM {
+        let reward_extension = RewardExtensionAccounts::find_reward_extension(
+            ctx.accounts.pool_state.key(),
+            ctx.remaining_accounts,
+        )?
+        .ok_or(ErrorCode::MissingRewardExtensionAccount)?;
+        reward_extension
+            .load_mut()?
+            .update_reward_infos(current_timestamp, pool_state.liquidity)?;
+        Some(reward_extension)
+    } else {
+        None
+    };
+
+    let mut reward_info = match &reward_extension {
+        Some(reward_extension) => {
+            reward_extension.load()?.reward_infos[reward_index as usize - REWARD_NUM]
+        }
+        None => pool_state.reward_infos[reward_index as usize],
+    };
+    if !reward_info.initialized() {
+        return err!(ErrorCode::UnInitializedRewardInfo);
+    }
+    require!(
+        reward_extension.is_some() || pool_state.scheduled_reward_flags & (1 << reward_index) == 0,
+        ErrorCode::RewardScheduled
+    );
+    require_gt!(
+        reward_info.end_time,
+        current_timestamp,
+        ErrorCode::RewardNotEmitting
+    );
+    require_keys_eq!(
+        ctx.accounts.reward_token_vault.key(),
+        reward_info.token_vault
+    );
+
+    let reward_funding = &mut ctx.accounts.reward_funding;
+    if reward_funding.pool_id == Pubkey::default() {
+        reward_funding.bump = ctx.bumps.reward_funding;
+        reward_funding.pool_id = ctx.accounts.pool_state.key();
+        reward_funding.reward_index = reward_index;
+        reward_funding.settled = true;
+    }
+    if reward_funding.settled {
+        // the funders of the previous emission must all be refunded before a new round starts,
+        // the pool owner can refund the ones that do not collect with refund_reward_funders
+        require_eq!(
+            reward_funding.total_funded,
+            0,
+            ErrorCode::RewardFundingOutstanding
+        );
+        reward_funding.reset(get_unowed_reward_amount(
+            &reward_info,
+            ctx.accounts.reward_token_vault.amount,
+        ));
+    }
+    let reward_funder = &mut ctx.accounts.reward_funder;
+    if reward_funder.funder == Pubkey::default() {
+        reward_funder.bump = ctx.bumps.reward_funder;
+        reward_funder.reward_funding = reward_funding.key();
+        reward_funder.funder = ctx.accounts.funder.key();
+    }
+
+    if extend_end_time {
+        extend_reward_end_time(&mut reward_info, amount)?;
+    } else {
+        increase_reward_emissions(&mut reward_info, current_timestamp, amount)?;
+    }
+
+    match &reward_extension {
+        Some(reward_extension) => {
+            reward_extension.load_mut()?.reward_infos[reward_index as usize - REWARD_NUM] =
+                reward_info
+        }
+        None => pool_state.reward_infos[reward_index as usize] = reward_info,
+    }
+    pool_state.funded_reward_flags |= 1 << reward_index;
+
+    reward_funding.fund(amount);
+    reward_funder.amount = reward_funder.amount.checked_add(amount).unwrap();
+
+    // the vault receives the full amount, the funder pays the transfer fee on top
+    let transfer_fee = get_transfer_inverse_fee(ctx.accounts.reward_vault_mint.clone(), amount)?;
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.funder,
+        &ctx.accounts.funder_token_account.to_account_info(),
+        &ctx.accounts.reward_token_vault.to_account_info(),
+        Some(ctx.accounts.reward_vault_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount.checked_add(transfer_fee).unwrap(),
+    )?;
+
+    emit!(FundRewardEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        funder: ctx.accounts.funder.key(),
+        reward_index,
+        amount,
+        end_time: reward_info.end_time,
+        emissions_per_second_x64: reward_info.emissions_per_second_x64,
+    });
+
+    Ok(())
+}
+
+/// Extends the end time by the seconds `amount` pays for at the current emissions
+fn extend_reward_end_time(reward_info: &mut RewardInfo, amount: u64) -> Result<()> {
+    // a reward emitting nothing is extended by raising its emissions instead
+    require_neq!(
+        reward_info.emissions_per_second_x64,
+        0,
+        ErrorCode::RewardFundAmountTooSmall
+    );
+    let extend_period = U256::from(amount)
+        .mul_div_floor(
+            U256::from(fixed_point_64::Q64),
+            U256::from(reward_info.emissions_per_second_x64),
+        )
+        .unwrap();
+    require!(
+        extend_period > U256::zero(),
+        ErrorCode::RewardFundAmountTooSmall
+    );
+    require!(
+        extend_period <= U256::from(reward_period_limit::MAX_REWARD_PERIOD),
+        ErrorCode::InvalidRewardPeriod
+    );
+    reward_info.end_time = reward_info
+        .end_time
+        .checked_add(extend_period.as_u64())
+        .unwrap();
+    Ok(())
+}
+
+/// Raises the emissions by what `amount` pays for until the end time
+fn increase_reward_emissions(
+    reward_info: &mut RewardInfo,
+    current_timestamp: u64,
+    amount: u64,
+) -> Result<()> {
+    let left_reward_time = reward_info
+        .end_time
+        .checked_sub(current_timestamp.max(reward_info.open_time))
+        .unwrap();
+    let emission_diff_x64 = U256::from(amount)
+        .mul_div_floor(
+            U256::from(fixed_point_64::Q64),
+            U256::from(left_reward_time),
+        )
+        .unwrap();
+    require!(
+        emission_diff_x64 > U256::zero(),
+        ErrorCode::RewardFundAmountTooSmall
+    );
+    reward_info.emissions_per_second_x64 = reward_info
+        .emissions_per_second_x64
+        .checked_add(emission_diff_x64.as_u128())
+        .unwrap();
+    Ok(())
+}
+
+#[cfg(test)]
+mod fund_reward_test {
+    use super::*;
+
+    fn reward_info(open_time: u64, end_time: u64, emissions_per_second_x64: u128) -> RewardInfo {
+        RewardInfo {
+            reward_state: RewardState::Opening as u8,
+            open_time,
+            end_time,
+            last_update_time: open_time,
+            emissions_per_second_x64,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn extend_reward_end_time_test() {
+        let mut info = reward_info(100, 1100, 10 * fixed_point_64::Q64);
+        extend_reward_end_time(&mut info, 1005).unwrap();
+        // the deposit pays for whole seconds only
+        assert_eq!({ info.end_time }, 1200);
+        assert_eq!({ info.emissions_per_second_x64 }, 10 * fixed_point_64::Q64);
+
+        assert!(extend_reward_end_time(&mut info, 9).is_err());
+
+        // nothing to extend at zero emissions
+        let mut info = reward_info(100, 1100, 0);
+        assert!(extend_reward_end_time(&mut info, 1000).is_err());
+    }
+
+    #[test]
+    fn increase_reward_emissions_test() {
+        let mut info = reward_info(100, 1100, 10 * fixed_point_64::Q64);
+        // 600 seconds left to emit
+        increase_reward_emissions(&mut info, 500, 1200).unwrap();
+        assert_eq!({ info.end_time }, 1100);
+        assert_eq!({ info.emissions_per_second_x64 }, 12 * fixed_point_64::Q64);
+
+        // not opened yet, spread over the whole period
+        let mut info = reward_info(100, 1100, 10 * fixed_point_64::Q64);
+        increase_reward_emissions(&mut info, 50, 1000).unwrap();
+        assert_eq!({ info.emissions_per_second_x64 }, 11 * fixed_point_64::Q64);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct GrantPositionDelegate<'info> {
+    /// The position NFT holder, pays the delegate account rent
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The key allowed to act on the position
+    pub delegate: UncheckedAccount<'info>,
+
+    /// The delegate account, updated if it already exists
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_DELEGATE_SEED.as_bytes(),
+            nft_account.mint.as_ref(),
+            delegate.key().as_ref(),
+        ],
+        bump,
+        payer = nft_owner,
+        space = PositionDelegateState::LEN
+    )]
+    pub position_delegate: Box<Account<'info, PositionDelegateState>>,
+
+    /// Program to create the delegate account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn grant_position_delegate(
+    ctx: Context<GrantPositionDelegate>,
+    permissions: u8,
+    expiry: u64,
+) -> Result<()> {
+    require!(
+        permissions != 0 && permissions & !PositionDelegatePermission::FULL == 0,
+        ErrorCode::InvalidPositionDelegatePermissions
+    );
+    require!(
+        expiry == 0 || expiry > Clock::get()?.unix_timestamp as u64,
+        ErrorCode::InvalidPositionDelegateExpiry
+    );
+    require_keys_neq!(ctx.accounts.delegate.key(), ctx.accounts.nft_owner.key());
+
+    let position_delegate = &mut ctx.accounts.position_delegate;
+    position_delegate.bump = ctx.bumps.position_delegate;
+    position_delegate.nft_mint = ctx.accounts.nft_account.mint;
+    position_delegate.owner = ctx.accounts.nft_owner.key();
+    position_delegate.delegate = ctx.accounts.delegate.key();
+    position_delegate.permissions = permissions;
+    position_delegate.expiry = expiry;
+
+    emit!(GrantPositionDelegateEvent {
+        position_nft_mint: position_delegate.nft_mint,
+        owner: position_delegate.owner,
+        delegate: position_delegate.delegate,
+        permissions,
+        expiry,
+    });
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use super::add_liquidity;
+use super::{add_liquidity, find_observation_state};
 use crate::error::ErrorCode;
 use crate::libraries::{big_num::U128, fixed_point_64, full_math::MulDiv};
 use crate::states::*;
@@ -9,14 +9,13 @@ use anchor_spl::token_interface::{Mint, Token2022};
 
 #[derive(Accounts)]
 pub struct IncreaseLiquidity<'info> {
-    /// Pays to mint the position
+    /// Pays to mint the position, the position owner or a delegate
     pub nft_owner: Signer<'info>,
 
     /// The token account for nft
     #[account(
         constraint = nft_account.mint == personal_position.nft_mint,
-        constraint = nft_account.amount == 1,
-        token::authority = nft_owner
+        constraint = nft_account.amount == 1
     )]
     pub nft_account: Box<Account<'info, TokenAccount>>,
 
@@ -87,6 +86,11 @@ pub struct IncreaseLiquidity<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // observation_state: should add the pool observation account, an oracle observation is
+    // written before the liquidity changes
+    // reward extension accounts: must add the reward extension, the position companion and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
 }
 
 pub fn increase_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
@@ -96,6 +100,13 @@ pub fn increase_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
     amount_1_max: u64,
     base_flag: Option<bool>,
 ) -> Result<()> {
+    check_position_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        ctx.accounts.personal_position.nft_mint,
+        ctx.remaining_accounts,
+        PositionDelegatePermission::IncreaseLiquidity,
+    )?;
     increase_liquidity(
         &ctx.accounts.nft_owner,
         &ctx.accounts.pool_state,
@@ -116,7 +127,8 @@ pub fn increase_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
         amount_0_max,
         amount_1_max,
         base_flag,
-    )
+    )?;
+    Ok(())
 }
 
 pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
@@ -140,7 +152,7 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
     amount_0_max: u64,
     amount_1_max: u64,
     base_flag: Option<bool>,
-) -> Result<()> {
+) -> Result<LiquidityChangeResult> {
     let mut liquidity = liquidity;
     let pool_state = &mut pool_state_loader.load_mut()?;
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
@@ -148,6 +160,19 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
     }
     let tick_lower = personal_position.tick_lower_index;
     let tick_upper = personal_position.tick_upper_index;
+    update_scheduled_reward_infos(
+        pool_state,
+        pool_state_loader.key(),
+        remaining_accounts,
+        Clock::get()?.unix_timestamp as u64,
+    )?;
+    let reward_extension = RewardExtensionAccounts::load(
+        pool_state,
+        pool_state_loader.key(),
+        Some(personal_position.nft_mint),
+        remaining_accounts,
+        Clock::get()?.unix_timestamp as u64,
+    )?;
 
     let use_tickarray_bitmap_extension =
         pool_state.is_overflow_default_tickarray_bitmap(vec![tick_lower, tick_upper]);
@@ -174,6 +199,7 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
         } else {
             None
         },
+        find_observation_state(pool_state, remaining_accounts),
         pool_state,
         &mut liquidity,
         amount_0_max,
@@ -202,6 +228,18 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
     // update rewards, must update before increase liquidity
     personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
     personal_position.liquidity = personal_position.liquidity.checked_add(liquidity).unwrap();
+    if let Some(reward_extension) = reward_extension {
+        reward_extension.update_position(
+            pool_state.tick_current,
+            pool_state.tick_spacing,
+            &*tick_array_lower_loader.load()?,
+            &*tick_array_upper_loader.load()?,
+            tick_lower,
+            tick_upper,
+            i128::try_from(liquidity).unwrap(),
+            personal_position.liquidity,
+        )?;
+    }
 
     emit!(IncreaseLiquidityEvent {
         position_nft_mint: personal_position.nft_mint,
@@ -212,7 +250,13 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
         amount_1_transfer_fee
     });
 
-    Ok(())
+    Ok(LiquidityChangeResult {
+        liquidity,
+        amount_0,
+        amount_1,
+        amount_0_transfer_fee,
+        amount_1_transfer_fee,
+    })
 }
 
 pub fn calculate_latest_token_fees(
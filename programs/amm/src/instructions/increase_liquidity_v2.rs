@@ -6,14 +6,13 @@ use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
 
 #[derive(Accounts)]
 pub struct IncreaseLiquidityV2<'info> {
-    /// Pays to mint the position
+    /// Pays to mint the position, the position owner or a delegate
     pub nft_owner: Signer<'info>,
 
     /// The token account for nft
     #[account(
         constraint = nft_account.mint == personal_position.nft_mint,
-        constraint = nft_account.amount == 1,
-        token::authority = nft_owner,
+        constraint = nft_account.amount == 1
     )]
     pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -99,6 +98,11 @@ pub struct IncreaseLiquidityV2<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // observation_state: should add the pool observation account, an oracle observation is
+    // written before the liquidity changes
+    // reward extension accounts: must add the reward extension, the position companion and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
 }
 
 pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
@@ -107,7 +111,14 @@ pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
     amount_0_max: u64,
     amount_1_max: u64,
     base_flag: Option<bool>,
-) -> Result<()> {
+) -> Result<LiquidityChangeResult> {
+    check_position_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        ctx.accounts.personal_position.nft_mint,
+        ctx.remaining_accounts,
+        PositionDelegatePermission::IncreaseLiquidity,
+    )?;
     increase_liquidity(
         &ctx.accounts.nft_owner,
         &ctx.accounts.pool_state,
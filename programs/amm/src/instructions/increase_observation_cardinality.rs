@@ -0,0 +1,50 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+
+#[derive(Accounts)]
+#[instruction(observation_cardinality_next: u16)]
+pub struct IncreaseObservationCardinality<'info> {
+    /// Pays the rent for the grown observation account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The observation account of the pool, reallocated to hold `observation_cardinality_next` observations
+    #[account(
+        mut,
+        address = pool_state.load()?.observation_key,
+        realloc = ObservationState::len_for_cardinality(observation_cardinality_next),
+        realloc::payer = payer,
+        realloc::zero = true
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn increase_observation_cardinality(
+    ctx: Context<IncreaseObservationCardinality>,
+    observation_cardinality_next: u16,
+) -> Result<()> {
+    let mut observation_state = ctx.accounts.observation_state.load_mut()?;
+    let observation_cardinality_next_old = observation_state.cardinality_next();
+    require_gt!(
+        observation_cardinality_next as usize,
+        observation_cardinality_next_old,
+        ErrorCode::InvalidObservationCardinality
+    );
+    // an account can only grow by MAX_PERMITTED_DATA_INCREASE in one instruction
+    require_gte!(
+        MAX_PERMITTED_DATA_INCREASE / Observation::LEN,
+        observation_cardinality_next as usize - observation_cardinality_next_old,
+        ErrorCode::InvalidObservationCardinality
+    );
+
+    // pin the cardinality of accounts created before it was stored
+    observation_state.observation_cardinality = observation_state.cardinality() as u16;
+    observation_state.observation_cardinality_next = observation_cardinality_next;
+    Ok(())
+}
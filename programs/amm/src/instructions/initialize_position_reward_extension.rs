@@ -0,0 +1,40 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializePositionRewardExtension<'info> {
+    /// Pays to create the position companion account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The position NFT mint, it may be created later by open position
+    pub position_nft_mint: UncheckedAccount<'info>,
+
+    /// Tracks the extension rewards of the position
+    #[account(
+        init,
+        seeds = [
+            POSITION_REWARD_EXTENSION_SEED.as_bytes(),
+            position_nft_mint.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = PositionRewardExtension::LEN
+    )]
+    pub position_reward_extension: AccountLoader<'info, PositionRewardExtension>,
+
+    /// Program to create the position companion account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_position_reward_extension(
+    ctx: Context<InitializePositionRewardExtension>,
+) -> Result<()> {
+    ctx.accounts
+        .position_reward_extension
+        .load_init()?
+        .initialize(
+            ctx.bumps.position_reward_extension,
+            ctx.accounts.position_nft_mint.key(),
+        )
+}
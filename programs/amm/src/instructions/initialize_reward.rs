@@ -66,6 +66,8 @@ pub struct InitializeReward<'info> {
     //     bump
     // )]
     // pub support_mint_account: Account<'info, SupportMint>,
+    // reward_extension: the reward goes to the reward extension if added once the pool reward
+    // slots are full
 }
 
 #[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
@@ -144,16 +146,37 @@ pub fn initialize_reward(
     );
 
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
-    pool_state.initialize_reward(
-        param.open_time,
-        param.end_time,
-        param.emissions_per_second_x64,
-        &ctx.accounts.reward_token_mint.key(),
-        ctx.accounts.reward_token_mint.freeze_authority,
-        &ctx.accounts.reward_token_vault.key(),
-        &ctx.accounts.reward_funder.key(),
-        &operation_state,
+    let pool_reward_infos = pool_state.reward_infos;
+    let reward_extension = RewardExtensionAccounts::find_reward_extension(
+        ctx.accounts.pool_state.key(),
+        ctx.remaining_accounts,
     )?;
+    match reward_extension {
+        Some(reward_extension) if pool_reward_infos.iter().all(|r| r.initialized()) => {
+            reward_extension.load_mut()?.initialize_reward(
+                &pool_state,
+                param.open_time,
+                param.end_time,
+                param.emissions_per_second_x64,
+                &ctx.accounts.reward_token_mint.key(),
+                ctx.accounts.reward_token_mint.freeze_authority,
+                &ctx.accounts.reward_token_vault.key(),
+                &ctx.accounts.reward_funder.key(),
+                &operation_state,
+            )?;
+            pool_state.extension_reward_count += 1;
+        }
+        _ => pool_state.initialize_reward(
+            param.open_time,
+            param.end_time,
+            param.emissions_per_second_x64,
+            &ctx.accounts.reward_token_mint.key(),
+            ctx.accounts.reward_token_mint.freeze_authority,
+            &ctx.accounts.reward_token_vault.key(),
+            &ctx.accounts.reward_funder.key(),
+            &operation_state,
+        )?,
+    }
 
     transfer_from_user_to_pool_vault(
         &ctx.accounts.reward_funder,
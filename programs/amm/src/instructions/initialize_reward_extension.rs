@@ -0,0 +1,37 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeRewardExtension<'info> {
+    /// Pays to create the reward extension account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The pool to extend the reward slots of
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Holds the reward slots beyond the pool ones
+    #[account(
+        init,
+        seeds = [
+            POOL_REWARD_EXTENSION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = RewardExtensionState::LEN
+    )]
+    pub reward_extension: AccountLoader<'info, RewardExtensionState>,
+
+    /// Program to create the reward extension account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_reward_extension(ctx: Context<InitializeRewardExtension>) -> Result<()> {
+    let pool_creator = ctx.accounts.pool_state.load()?.owner;
+    ctx.accounts.reward_extension.load_init()?.initialize(
+        ctx.bumps.reward_extension,
+        ctx.accounts.pool_state.key(),
+        pool_creator,
+    )
+}
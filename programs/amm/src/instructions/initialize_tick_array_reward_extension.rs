@@ -0,0 +1,92 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::AccountLoad;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(tick_array_start_index: i32)]
+pub struct InitializeTickArrayRewardExtension<'info> {
+    /// Pays to create the tick array companion account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The pool of the tick array
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The reward extension of the pool, accrued to initialize the ticks in use
+    #[account(
+        mut,
+        seeds = [
+            POOL_REWARD_EXTENSION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub reward_extension: AccountLoader<'info, RewardExtensionState>,
+
+    /// CHECK: The tick array the companion is of, it may not be initialized
+    #[account(
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array: UncheckedAccount<'info>,
+
+    /// Tracks the extension reward growths outside of the ticks of the tick array
+    #[account(
+        init,
+        seeds = [
+            TICK_ARRAY_REWARD_EXTENSION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_start_index.to_be_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = TickArrayRewardExtension::LEN
+    )]
+    pub tick_array_reward_extension: AccountLoader<'info, TickArrayRewardExtension>,
+
+    /// Program to create the tick array companion account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_tick_array_reward_extension(
+    ctx: Context<InitializeTickArrayRewardExtension>,
+    tick_array_start_index: i32,
+) -> Result<()> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    require!(
+        TickArrayState::check_is_valid_start_index(tick_array_start_index, pool_state.tick_spacing),
+        ErrorCode::InvalidTickIndex
+    );
+    let reward_infos = ctx
+        .accounts
+        .reward_extension
+        .load_mut()?
+        .update_reward_infos(Clock::get()?.unix_timestamp as u64, pool_state.liquidity)?;
+
+    let tick_array_info = ctx.accounts.tick_array.to_account_info();
+    let tick_array_loader = if tick_array_info.owner == &crate::id() {
+        Some(AccountLoad::<TickArrayState>::try_from(&tick_array_info)?)
+    } else {
+        None
+    };
+    let tick_array = match &tick_array_loader {
+        Some(tick_array_loader) => Some(tick_array_loader.load()?),
+        None => None,
+    };
+    ctx.accounts
+        .tick_array_reward_extension
+        .load_init()?
+        .initialize(
+            ctx.accounts.pool_state.key(),
+            tick_array_start_index,
+            tick_array.as_deref(),
+            pool_state.tick_spacing,
+            pool_state.tick_current,
+            &reward_infos,
+        )
+}
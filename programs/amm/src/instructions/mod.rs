@@ -1,6 +1,12 @@
 pub mod create_pool;
 pub use create_pool::*;
 
+pub mod set_pool_open_time;
+pub use set_pool_open_time::*;
+
+pub mod collect_creator_fee;
+pub use collect_creator_fee::*;
+
 pub mod open_position;
 pub use open_position::*;
 
@@ -10,6 +16,9 @@ pub use open_position_v2::*;
 pub mod open_position_with_token22_nft;
 pub use open_position_with_token22_nft::*;
 
+pub mod open_position_single_sided;
+pub use open_position_single_sided::*;
+
 pub mod close_position;
 pub use close_position::*;
 
@@ -25,6 +34,21 @@ pub use decrease_liquidity::*;
 pub mod decrease_liquidity_v2;
 pub use decrease_liquidity_v2::*;
 
+pub mod collect_all;
+pub use collect_all::*;
+
+pub mod compound_position;
+pub use compound_position::*;
+
+pub mod rebalance_position;
+pub use rebalance_position::*;
+
+pub mod grant_position_delegate;
+pub use grant_position_delegate::*;
+
+pub mod revoke_position_delegate;
+pub use revoke_position_delegate::*;
+
 pub mod swap;
 pub use swap::*;
 
@@ -34,17 +58,62 @@ pub use swap_v2::*;
 pub mod swap_router_base_in;
 pub use swap_router_base_in::*;
 
+pub mod swap_router_base_out;
+pub use swap_router_base_out::*;
+
+pub mod quote_swap;
+pub use quote_swap::*;
+
+pub mod flash;
+pub use flash::*;
+
 pub mod update_reward_info;
 pub use update_reward_info::*;
 
 pub mod initialize_reward;
 pub use initialize_reward::*;
 
+pub mod initialize_reward_extension;
+pub use initialize_reward_extension::*;
+
+pub mod initialize_tick_array_reward_extension;
+pub use initialize_tick_array_reward_extension::*;
+
+pub mod initialize_position_reward_extension;
+pub use initialize_position_reward_extension::*;
+
 pub mod set_reward_params;
 pub use set_reward_params::*;
 
+pub mod fund_reward;
+pub use fund_reward::*;
+
+pub mod set_reward_range;
+pub use set_reward_range::*;
+
+pub mod set_reward_schedule;
+pub use set_reward_schedule::*;
+
 pub mod collect_remaining_rewards;
 pub use collect_remaining_rewards::*;
 
+pub mod refund_reward_funders;
+pub use refund_reward_funders::*;
+
+pub mod open_limit_order;
+pub use open_limit_order::*;
+
+pub mod cancel_limit_order;
+pub use cancel_limit_order::*;
+
+pub mod claim_limit_order;
+pub use claim_limit_order::*;
+
+pub mod observe;
+pub use observe::*;
+
+pub mod increase_observation_cardinality;
+pub use increase_observation_cardinality::*;
+
 pub mod admin;
 pub use admin::*;
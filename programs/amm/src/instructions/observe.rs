@@ -0,0 +1,25 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Observe<'info> {
+    /// The pool to read the oracle of
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the oracle observations of the pool
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn observe(ctx: Context<Observe>, seconds_agos: Vec<u32>) -> Result<ObserveResult> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let (observation_state, extended_observations) =
+        ObservationState::load_extended(ctx.accounts.observation_state.as_ref())?;
+    observation_state.observe(
+        &extended_observations,
+        oracle::block_timestamp(),
+        pool_state.tick_current,
+        pool_state.liquidity,
+        &seconds_agos,
+    )
+}
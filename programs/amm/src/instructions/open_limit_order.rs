@@ -0,0 +1,192 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(tick_index: i32, tick_array_start_index: i32, zero_for_one: bool)]
+pub struct OpenLimitOrder<'info> {
+    /// The owner of the order, pays the sold token and the order account rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The order account, the order is increased if it already exists
+    #[account(
+        init_if_needed,
+        seeds = [
+            LIMIT_ORDER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &tick_index.to_be_bytes(),
+            &[zero_for_one as u8],
+        ],
+        bump,
+        payer = owner,
+        space = LimitOrderState::LEN
+    )]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// CHECK: Account to store data for the order tick, created if not exist
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array: UncheckedAccount<'info>,
+
+    /// The owner's token account of the sold token
+    #[account(
+        mut,
+        token::mint = input_vault.mint
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool vault of the sold token
+    #[account(
+        mut,
+        constraint = input_vault.key() == if zero_for_one {
+            pool_state.load()?.token_vault_0
+        } else {
+            pool_state.load()?.token_vault_1
+        }
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the sold token
+    #[account(
+        address = input_vault.mint
+    )]
+    pub input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Program to create the order and tick array accounts
+    pub system_program: Program<'info, System>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+}
+
+pub fn open_limit_order<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, OpenLimitOrder<'info>>,
+    tick_index: i32,
+    tick_array_start_index: i32,
+    zero_for_one: bool,
+    amount: u64,
+) -> Result<()> {
+    require!(amount != 0, ErrorCode::ZeroLimitOrderAmount);
+    let pool_state_loader = &ctx.accounts.pool_state;
+    let mut pool_state = pool_state_loader.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
+        return err!(ErrorCode::NotApproved);
+    }
+    check_tick_array_start_index(tick_array_start_index, tick_index, pool_state.tick_spacing)?;
+    // orders selling token_0 wait above the current tick, orders selling token_1 at or below it,
+    // so a tick only ever holds orders of one side
+    require!(
+        if zero_for_one {
+            tick_index > pool_state.tick_current
+        } else {
+            tick_index <= pool_state.tick_current
+        },
+        ErrorCode::InvalidLimitOrderTick
+    );
+
+    let transfer_fee = get_transfer_fee(ctx.accounts.input_vault_mint.clone(), amount)?;
+    let order_amount = amount.checked_sub(transfer_fee).unwrap();
+    require!(order_amount != 0, ErrorCode::ZeroLimitOrderAmount);
+
+    let tick_array_loader = TickArrayState::get_or_create_tick_array(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.tick_array.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        pool_state_loader,
+        tick_array_start_index,
+        pool_state.tick_spacing,
+    )?;
+    {
+        let mut tick_array = tick_array_loader.load_mut()?;
+        let tick_state = tick_array.get_tick_state_mut(tick_index, pool_state.tick_spacing)?;
+        let initialized_before = tick_state.is_initialized();
+        if tick_state.tick == 0 {
+            tick_state.tick = tick_index;
+        }
+
+        let limit_order = &mut ctx.accounts.limit_order;
+        if limit_order.pool_id == Pubkey::default() {
+            limit_order.bump = ctx.bumps.limit_order;
+            limit_order.pool_id = pool_state_loader.key();
+            limit_order.owner = ctx.accounts.owner.key();
+            limit_order.tick_index = tick_index;
+            limit_order.zero_for_one = zero_for_one;
+            tick_state.order_count = tick_state.order_count.checked_add(1).unwrap();
+        }
+        limit_order.settle(tick_state)?;
+        limit_order.unfilled_amount = limit_order
+            .unfilled_amount
+            .checked_add(order_amount)
+            .unwrap();
+        tick_state.order_amount = tick_state.order_amount.checked_add(order_amount).unwrap();
+
+        if !initialized_before {
+            tick_array.update_initialized_tick_count(true)?;
+            if tick_array.initialized_tick_count == 1 {
+                let use_tickarray_bitmap_extension =
+                    pool_state.is_overflow_default_tickarray_bitmap(vec![tick_array_start_index]);
+                pool_state.flip_tick_array_bit(
+                    if use_tickarray_bitmap_extension {
+                        Some(
+                            ctx.remaining_accounts
+                                .get(0)
+                                .ok_or(ErrorCode::MissingTickArrayBitmapExtensionAccount)?,
+                        )
+                    } else {
+                        None
+                    },
+                    tick_array_start_index,
+                )?;
+            }
+        }
+        tick_array.recent_epoch = get_recent_epoch()?;
+    }
+
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.owner,
+        &ctx.accounts.input_token_account.to_account_info(),
+        &ctx.accounts.input_vault.to_account_info(),
+        Some(ctx.accounts.input_vault_mint.clone()),
+        &ctx.accounts.token_program.to_account_info(),
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount,
+    )?;
+
+    emit!(OpenLimitOrderEvent {
+        pool_state: pool_state_loader.key(),
+        owner: ctx.accounts.owner.key(),
+        tick_index,
+        zero_for_one,
+        amount: order_amount,
+        transfer_fee,
+    });
+
+    Ok(())
+}
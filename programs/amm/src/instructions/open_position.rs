@@ -168,6 +168,11 @@ pub struct OpenPosition<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // observation_state: should add the pool observation account, an oracle observation is
+    // written before the liquidity changes
+    // reward extension accounts: must add the reward extension, the position companion and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
 }
 
 pub fn open_position_v1<'a, 'b, 'c: 'info, 'info>(
@@ -218,7 +223,8 @@ pub fn open_position_v1<'a, 'b, 'c: 'info, 'info>(
         with_metadata,
         base_flag,
         false,
-    )
+    )?;
+    Ok(())
 }
 
 pub fn open_position<'a, 'b, 'c: 'info, 'info>(
@@ -258,8 +264,9 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
     with_metadata: bool,
     base_flag: Option<bool>,
     use_metadata_extension: bool,
-) -> Result<()> {
+) -> Result<LiquidityChangeResult> {
     let mut liquidity = liquidity;
+    let liquidity_change_result;
     {
         let pool_state = &mut pool_state_loader.load_mut()?;
         if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
@@ -276,6 +283,19 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             tick_upper_index,
             pool_state.tick_spacing,
         )?;
+        update_scheduled_reward_infos(
+            pool_state,
+            pool_state_loader.key(),
+            remaining_accounts,
+            Clock::get()?.unix_timestamp as u64,
+        )?;
+        let reward_extension = RewardExtensionAccounts::load(
+            pool_state,
+            pool_state_loader.key(),
+            Some(position_nft_mint.key()),
+            remaining_accounts,
+            Clock::get()?.unix_timestamp as u64,
+        )?;
 
         // Why not use anchor's `init-if-needed` to create?
         // Beacuse `tick_array_lower` and `tick_array_upper` can be the same account, anchor can initialze tick_array_lower but it causes a crash when anchor to initialze the `tick_array_upper`,
@@ -347,6 +367,7 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             } else {
                 None
             },
+            find_observation_state(pool_state, remaining_accounts),
             pool_state,
             &mut liquidity,
             amount_0_max,
@@ -371,6 +392,18 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
         // update rewards, must update before update liquidity
         personal_position.update_rewards(protocol_position.reward_growth_inside, false)?;
         personal_position.liquidity = liquidity;
+        if let Some(reward_extension) = reward_extension {
+            reward_extension.update_position(
+                pool_state.tick_current,
+                pool_state.tick_spacing,
+                &*tick_array_lower_loader.load()?,
+                &*tick_array_upper_loader.load()?,
+                tick_lower_index,
+                tick_upper_index,
+                i128::try_from(liquidity).unwrap(),
+                liquidity,
+            )?;
+        }
 
         emit!(CreatePersonalPositionEvent {
             pool_state: pool_state_loader.key(),
@@ -384,6 +417,13 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             deposit_amount_0_transfer_fee: amount_0_transfer_fee,
             deposit_amount_1_transfer_fee: amount_1_transfer_fee
         });
+        liquidity_change_result = LiquidityChangeResult {
+            liquidity,
+            amount_0,
+            amount_1,
+            amount_0_transfer_fee,
+            amount_1_transfer_fee,
+        };
     }
 
     mint_nft_and_remove_mint_authority(
@@ -400,7 +440,8 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
         rent,
         with_metadata,
         use_metadata_extension,
-    )
+    )?;
+    Ok(liquidity_change_result)
 }
 
 /// Add liquidity to an initialized pool
@@ -418,6 +459,7 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
     vault_0_mint: Option<Box<InterfaceAccount<'info, token_interface::Mint>>>,
     vault_1_mint: Option<Box<InterfaceAccount<'info, token_interface::Mint>>>,
     tick_array_bitmap_extension: Option<&'c AccountInfo<'info>>,
+    observation_state: Option<&AccountInfo<'info>>,
     pool_state: &mut RefMut<PoolState>,
     liquidity: &mut u128,
     amount_0_max: u64,
@@ -471,73 +513,18 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
     }
     assert!(*liquidity > 0);
     let liquidity_before = pool_state.liquidity;
-    require_keys_eq!(tick_array_lower_loader.load()?.pool_id, pool_state.key());
-    require_keys_eq!(tick_array_upper_loader.load()?.pool_id, pool_state.key());
-
-    // get tick_state
-    let mut tick_lower_state = *tick_array_lower_loader
-        .load_mut()?
-        .get_tick_state_mut(tick_lower_index, pool_state.tick_spacing)?;
-    let mut tick_upper_state = *tick_array_upper_loader
-        .load_mut()?
-        .get_tick_state_mut(tick_upper_index, pool_state.tick_spacing)?;
-    if tick_lower_state.tick == 0 {
-        tick_lower_state.tick = tick_lower_index;
-    }
-    if tick_upper_state.tick == 0 {
-        tick_upper_state.tick = tick_upper_index;
-    }
-    let clock = Clock::get()?;
-    let (amount_0, amount_1, flip_tick_lower, flip_tick_upper) = modify_position(
-        i128::try_from(*liquidity).unwrap(),
+    let (amount_0, amount_1) = mint_liquidity(
         pool_state,
+        tick_array_lower_loader,
+        tick_array_upper_loader,
         protocol_position,
-        &mut tick_lower_state,
-        &mut tick_upper_state,
-        clock.unix_timestamp as u64,
-    )?;
-
-    // update tick_state
-    tick_array_lower_loader.load_mut()?.update_tick_state(
+        tick_array_bitmap_extension,
+        observation_state,
+        *liquidity,
         tick_lower_index,
-        pool_state.tick_spacing,
-        tick_lower_state,
-    )?;
-    tick_array_upper_loader.load_mut()?.update_tick_state(
         tick_upper_index,
-        pool_state.tick_spacing,
-        tick_upper_state,
     )?;
 
-    if flip_tick_lower {
-        let mut tick_array_lower = tick_array_lower_loader.load_mut()?;
-        let before_init_tick_count = tick_array_lower.initialized_tick_count;
-        tick_array_lower.update_initialized_tick_count(true)?;
-
-        if before_init_tick_count == 0 {
-            pool_state.flip_tick_array_bit(
-                tick_array_bitmap_extension,
-                tick_array_lower.start_tick_index,
-            )?;
-        }
-    }
-    if flip_tick_upper {
-        let mut tick_array_upper = tick_array_upper_loader.load_mut()?;
-        let before_init_tick_count = tick_array_upper.initialized_tick_count;
-        tick_array_upper.update_initialized_tick_count(true)?;
-
-        if before_init_tick_count == 0 {
-            pool_state.flip_tick_array_bit(
-                tick_array_bitmap_extension,
-                tick_array_upper.start_tick_index,
-            )?;
-        }
-    }
-    require!(
-        amount_0 > 0 || amount_1 > 0,
-        ErrorCode::ForbidBothZeroForSupplyLiquidity
-    );
-
     let mut amount_0_transfer_fee = 0;
     let mut amount_1_transfer_fee = 0;
     if vault_0_mint.is_some() {
@@ -615,6 +602,133 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
     ))
 }
 
+/// Adds liquidity to the ticks and the protocol position, returns the token amounts it takes
+pub fn mint_liquidity<'c: 'info, 'info>(
+    pool_state: &mut RefMut<PoolState>,
+    tick_array_lower_loader: &AccountLoad<'info, TickArrayState>,
+    tick_array_upper_loader: &AccountLoad<'info, TickArrayState>,
+    protocol_position: &mut ProtocolPositionState,
+    tick_array_bitmap_extension: Option<&'c AccountInfo<'info>>,
+    observation_state: Option<&AccountInfo<'info>>,
+    liquidity: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Result<(u64, u64)> {
+    require_keys_eq!(tick_array_lower_loader.load()?.pool_id, pool_state.key());
+    require_keys_eq!(tick_array_upper_loader.load()?.pool_id, pool_state.key());
+
+    // get tick_state
+    let mut tick_lower_state = *tick_array_lower_loader
+        .load_mut()?
+        .get_tick_state_mut(tick_lower_index, pool_state.tick_spacing)?;
+    let mut tick_upper_state = *tick_array_upper_loader
+        .load_mut()?
+        .get_tick_state_mut(tick_upper_index, pool_state.tick_spacing)?;
+    if tick_lower_state.tick == 0 {
+        tick_lower_state.tick = tick_lower_index;
+    }
+    if tick_upper_state.tick == 0 {
+        tick_upper_state.tick = tick_upper_index;
+    }
+    let clock = Clock::get()?;
+    let seconds_per_liquidity_cumulative_x64 = update_observation_before_liquidity_change(
+        pool_state,
+        observation_state,
+        clock.unix_timestamp as u32,
+    )?;
+    let (amount_0, amount_1, flip_tick_lower, flip_tick_upper) = modify_position(
+        i128::try_from(liquidity).unwrap(),
+        pool_state,
+        protocol_position,
+        &mut tick_lower_state,
+        &mut tick_upper_state,
+        clock.unix_timestamp as u64,
+        seconds_per_liquidity_cumulative_x64,
+    )?;
+
+    // update tick_state
+    tick_array_lower_loader.load_mut()?.update_tick_state(
+        tick_lower_index,
+        pool_state.tick_spacing,
+        tick_lower_state,
+    )?;
+    tick_array_upper_loader.load_mut()?.update_tick_state(
+        tick_upper_index,
+        pool_state.tick_spacing,
+        tick_upper_state,
+    )?;
+
+    if flip_tick_lower {
+        let mut tick_array_lower = tick_array_lower_loader.load_mut()?;
+        let before_init_tick_count = tick_array_lower.initialized_tick_count;
+        tick_array_lower.update_initialized_tick_count(true)?;
+
+        if before_init_tick_count == 0 {
+            pool_state.flip_tick_array_bit(
+                tick_array_bitmap_extension,
+                tick_array_lower.start_tick_index,
+            )?;
+        }
+    }
+    if flip_tick_upper {
+        let mut tick_array_upper = tick_array_upper_loader.load_mut()?;
+        let before_init_tick_count = tick_array_upper.initialized_tick_count;
+        tick_array_upper.update_initialized_tick_count(true)?;
+
+        if before_init_tick_count == 0 {
+            pool_state.flip_tick_array_bit(
+                tick_array_bitmap_extension,
+                tick_array_upper.start_tick_index,
+            )?;
+        }
+    }
+    require!(
+        amount_0 > 0 || amount_1 > 0,
+        ErrorCode::ForbidBothZeroForSupplyLiquidity
+    );
+    Ok((amount_0, amount_1))
+}
+
+/// Finds the observation account of the pool in the remaining accounts of the instructions that
+/// take it optionally
+pub fn find_observation_state<'c, 'info>(
+    pool_state: &PoolState,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Option<&'c AccountInfo<'info>> {
+    remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == pool_state.observation_key)
+}
+
+/// Writes an observation of the tick and the in-range liquidity before the liquidity changes,
+/// returns the seconds per liquidity cumulative the ticks are initialized with.
+/// Without the observation account nothing is written and the ticks are initialized with zero,
+/// which only shifts the relative value of their seconds per liquidity outside.
+pub fn update_observation_before_liquidity_change(
+    pool_state: &PoolState,
+    observation_state: Option<&AccountInfo>,
+    block_timestamp: u32,
+) -> Result<u128> {
+    let observation_state = match observation_state {
+        Some(observation_state) => observation_state,
+        None => return Ok(0),
+    };
+    let (mut observation_state, mut extended_observations) =
+        ObservationState::load_extended_mut(observation_state)?;
+    require_keys_eq!(observation_state.pool_id, pool_state.key());
+    observation_state.update(
+        &mut extended_observations,
+        block_timestamp,
+        pool_state.tick_current,
+        pool_state.liquidity,
+    );
+    Ok(observation_state.get_seconds_per_liquidity_cumulative_x64(
+        &extended_observations,
+        block_timestamp,
+        pool_state.liquidity,
+    ))
+}
+
 pub fn modify_position(
     liquidity_delta: i128,
     pool_state: &mut RefMut<PoolState>,
@@ -622,6 +736,7 @@ pub fn modify_position(
     tick_lower_state: &mut TickState,
     tick_upper_state: &mut TickState,
     timestamp: u64,
+    seconds_per_liquidity_cumulative_x64: u128,
 ) -> Result<(u64, u64, bool, bool)> {
     let (flip_tick_lower, flip_tick_upper) = update_position(
         liquidity_delta,
@@ -630,6 +745,7 @@ pub fn modify_position(
         tick_lower_state,
         tick_upper_state,
         timestamp,
+        seconds_per_liquidity_cumulative_x64,
     )?;
     let mut amount_0 = 0;
     let mut amount_1 = 0;
@@ -661,6 +777,7 @@ pub fn update_position(
     tick_lower_state: &mut TickState,
     tick_upper_state: &mut TickState,
     timestamp: u64,
+    seconds_per_liquidity_cumulative_x64: u128,
 ) -> Result<(bool, bool)> {
     let updated_reward_infos = pool_state.update_reward_infos(timestamp)?;
 
@@ -677,6 +794,7 @@ pub fn update_position(
             pool_state.fee_growth_global_1_x64,
             false,
             &updated_reward_infos,
+            seconds_per_liquidity_cumulative_x64,
         )?;
         flipped_upper = tick_upper_state.update(
             pool_state.tick_current,
@@ -685,6 +803,7 @@ pub fn update_position(
             pool_state.fee_growth_global_1_x64,
             true,
             &updated_reward_infos,
+            seconds_per_liquidity_cumulative_x64,
         )?;
         #[cfg(feature = "enable-log")]
         msg!(
@@ -730,7 +849,7 @@ pub fn update_position(
     Ok((flipped_lower, flipped_upper))
 }
 
-fn mint_nft_and_remove_mint_authority<'info>(
+pub fn mint_nft_and_remove_mint_authority<'info>(
     payer: &Signer<'info>,
     pool_state_loader: &AccountLoader<'info, PoolState>,
     personal_position: &Account<'info, PersonalPositionState>,
@@ -968,6 +1087,7 @@ mod modify_position_test {
             tick_lower_state,
             tick_upper_state,
             block_timestamp_mock(),
+            0,
         )
         .unwrap();
         assert!(amount_0_int != 0);
@@ -1035,6 +1155,7 @@ mod modify_position_test {
             tick_lower_state,
             tick_upper_state,
             block_timestamp_mock(),
+            0,
         )
         .unwrap();
         assert!(amount_0_int == 0);
@@ -1100,6 +1221,7 @@ mod modify_position_test {
             tick_lower_state,
             tick_upper_state,
             block_timestamp_mock(),
+            0,
         )
         .unwrap();
         assert!(amount_0_int != 0);
@@ -0,0 +1,795 @@
+use super::{
+    check_unclaimed_fees_and_vault, get_compound_liquidity, mint_liquidity,
+    mint_nft_and_remove_mint_authority,
+};
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, sqrt_price_math, tick_math};
+use crate::states::*;
+use crate::swap::swap_internal;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_upper_index: i32, tick_array_lower_start_index: i32, tick_array_upper_start_index: i32)]
+pub struct OpenPositionSingleSided<'info> {
+    /// Pays to mint the position and the single token deposited
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Receives the position NFT
+    pub position_nft_owner: UncheckedAccount<'info>,
+
+    /// Unique token mint address, initialize in contract
+    #[account(mut)]
+    pub position_nft_mint: Signer<'info>,
+
+    /// CHECK: ATA address where position NFT will be minted, initialize in contract
+    #[account(mut)]
+    pub position_nft_account: UncheckedAccount<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Swap and add liquidity for this pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Store the information of market marking in range
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = ProtocolPositionState::LEN
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// CHECK: Account to store data for the position's lower tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the position's upper tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// personal position state
+    #[account(
+        init,
+        seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PersonalPositionState::LEN
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// The token_0 account, pays the deposit if it is in token_0 and receives the token_0 left over
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_1 account, pays the deposit if it is in token_1 and receives the token_1 left over
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sysvar for token mint and ATA creation
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Program to create the position manager state account
+    pub system_program: Program<'info, System>,
+
+    /// Program to transfer for token account
+    pub token_program: Program<'info, Token>,
+
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Program to create NFT mint/token account and transfer for token22 account
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // reward extension accounts: must add the reward extension, the position companion and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
+    // tick_array_account_1, tick_array_account_2, ... for the swap
+}
+
+/// Opens a position from a single token. Part of `amount` is swapped through the pool so that
+/// the rest and the swap output fit the range at the price after the swap, both are added as
+/// liquidity and the tokens that don't fit are paid back to the token accounts.
+pub fn open_position_single_sided<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, OpenPositionSingleSided<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    amount: u64,
+    base_flag: bool,
+    liquidity_min: u128,
+    with_metadata: bool,
+) -> Result<LiquidityChangeResult> {
+    require!(amount != 0, ErrorCode::ZeroAmountSpecified);
+    create_position_nft_mint_with_extensions(
+        &ctx.accounts.payer,
+        &ctx.accounts.position_nft_mint,
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.personal_position.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program_2022,
+        with_metadata,
+    )?;
+
+    // create user position nft account
+    create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        Create {
+            payer: ctx.accounts.payer.to_account_info(),
+            associated_token: ctx.accounts.position_nft_account.to_account_info(),
+            authority: ctx.accounts.position_nft_owner.to_account_info(),
+            mint: ctx.accounts.position_nft_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program_2022.to_account_info(),
+        },
+    ))?;
+
+    // the whole amount goes to the vault, the pool swaps and deposits from there
+    let (input_token_account, input_vault, input_vault_mint) = if base_flag {
+        (
+            &ctx.accounts.token_account_0,
+            &ctx.accounts.token_vault_0,
+            &ctx.accounts.vault_0_mint,
+        )
+    } else {
+        (
+            &ctx.accounts.token_account_1,
+            &ctx.accounts.token_vault_1,
+            &ctx.accounts.vault_1_mint,
+        )
+    };
+    let transfer_fee = get_transfer_fee(input_vault_mint.clone(), amount)?;
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.payer,
+        &input_token_account.to_account_info(),
+        &input_vault.to_account_info(),
+        Some(input_vault_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount,
+    )?;
+
+    let pool_state_loader = &ctx.accounts.pool_state;
+    let personal_position = &mut ctx.accounts.personal_position;
+    let nft_mint = ctx.accounts.position_nft_mint.key();
+    let zero_for_one = base_flag;
+    let mut amount_0 = if zero_for_one {
+        amount - transfer_fee
+    } else {
+        0
+    };
+    let mut amount_1 = if zero_for_one {
+        0
+    } else {
+        amount - transfer_fee
+    };
+    let liquidity;
+    let deposit_amount_0;
+    let deposit_amount_1;
+    {
+        let pool_state = &mut pool_state_loader.load_mut()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
+            return err!(ErrorCode::NotApproved);
+        }
+        check_ticks_order(tick_lower_index, tick_upper_index)?;
+        check_tick_array_start_index(
+            tick_array_lower_start_index,
+            tick_lower_index,
+            pool_state.tick_spacing,
+        )?;
+        check_tick_array_start_index(
+            tick_array_upper_start_index,
+            tick_upper_index,
+            pool_state.tick_spacing,
+        )?;
+
+        let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
+        let tickarray_bitmap_extension = ctx
+            .remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == tick_array_bitmap_extension_key);
+        if pool_state.is_overflow_default_tickarray_bitmap(vec![
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+        ]) {
+            require!(
+                tickarray_bitmap_extension.is_some(),
+                ErrorCode::MissingTickArrayBitmapExtensionAccount
+            );
+        }
+
+        update_scheduled_reward_infos(
+            pool_state,
+            pool_state_loader.key(),
+            ctx.remaining_accounts,
+            Clock::get()?.unix_timestamp as u64,
+        )?;
+        let reward_extension = RewardExtensionAccounts::load(
+            pool_state,
+            pool_state_loader.key(),
+            Some(nft_mint),
+            ctx.remaining_accounts,
+            Clock::get()?.unix_timestamp as u64,
+        )?;
+
+        let (mut observation_state, mut extended_observations) =
+            ObservationState::load_extended_mut(ctx.accounts.observation_state.as_ref())?;
+        // the swap charges the same rate, it is needed before to size the swap
+        let trade_fee_rate = pool_state.get_trade_fee_rate(
+            &ctx.accounts.amm_config,
+            &observation_state,
+            &extended_observations,
+            oracle::block_timestamp(),
+        );
+        let swap_amount = get_single_sided_swap_amount(
+            pool_state.sqrt_price_x64,
+            pool_state.liquidity,
+            trade_fee_rate,
+            tick_math::get_sqrt_price_at_tick(tick_lower_index)?,
+            tick_math::get_sqrt_price_at_tick(tick_upper_index)?,
+            if zero_for_one { amount_0 } else { amount_1 },
+            zero_for_one,
+        )?;
+        if swap_amount > 0 {
+            // the swap hook is only called by the swap instructions
+            require_keys_eq!(
+                pool_state.swap_hook_program,
+                Pubkey::default(),
+                ErrorCode::SwapHookNotSupported
+            );
+            let mut tick_array_states = VecDeque::new();
+            for account_info in ctx.remaining_accounts.iter() {
+                if account_info.key() == tick_array_bitmap_extension_key
+                    || RewardExtensionAccounts::is_extension_account(account_info)
+                {
+                    continue;
+                }
+                tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
+            }
+            let tickarray_bitmap_extension = match tickarray_bitmap_extension {
+                Some(account_info) => Some(
+                    *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
+                        .load()?
+                        .deref()),
+                ),
+                None => None,
+            };
+            let tick_before = pool_state.tick_current;
+            let (swap_amount_0, swap_amount_1, trade_fee_rate) = swap_internal(
+                &ctx.accounts.amm_config,
+                pool_state,
+                &mut tick_array_states,
+                &mut observation_state,
+                &mut extended_observations,
+                &tickarray_bitmap_extension,
+                swap_amount,
+                if zero_for_one {
+                    tick_math::MIN_SQRT_PRICE_X64 + 1
+                } else {
+                    tick_math::MAX_SQRT_PRICE_X64 - 1
+                },
+                zero_for_one,
+                true,
+                oracle::block_timestamp(),
+            )?;
+            if let Some(reward_extension) = &reward_extension {
+                // release the tick arrays for the extension to read
+                tick_array_states.clear();
+                reward_extension.cross_ticks(
+                    pool_state_loader.key(),
+                    ctx.remaining_accounts,
+                    tick_before,
+                    pool_state.tick_current,
+                )?;
+            }
+            require!(
+                swap_amount_0 != 0 && swap_amount_1 != 0,
+                ErrorCode::TooSmallInputOrOutputAmount
+            );
+            if zero_for_one {
+                amount_0 -= swap_amount_0;
+                amount_1 = swap_amount_1;
+            } else {
+                amount_0 = swap_amount_0;
+                amount_1 -= swap_amount_1;
+            }
+            emit!(SwapEvent {
+                pool_state: pool_state_loader.key(),
+                sender: ctx.accounts.payer.key(),
+                token_account_0: ctx.accounts.token_account_0.key(),
+                token_account_1: ctx.accounts.token_account_1.key(),
+                amount_0: swap_amount_0,
+                transfer_fee_0: 0,
+                amount_1: swap_amount_1,
+                transfer_fee_1: 0,
+                zero_for_one,
+                sqrt_price_x64: pool_state.sqrt_price_x64,
+                liquidity: pool_state.liquidity,
+                tick: pool_state.tick_current,
+                trade_fee_rate,
+            });
+        }
+
+        // the tick arrays of the position are created after the swap, which can't run through
+        // uninitialized tick arrays
+        let tick_array_lower_loader = TickArrayState::get_or_create_tick_array(
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.tick_array_lower.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            pool_state_loader,
+            tick_array_lower_start_index,
+            pool_state.tick_spacing,
+        )?;
+        let tick_array_upper_loader =
+            if tick_array_lower_start_index == tick_array_upper_start_index {
+                AccountLoad::<TickArrayState>::try_from(
+                    &ctx.accounts.tick_array_upper.to_account_info(),
+                )?
+            } else {
+                TickArrayState::get_or_create_tick_array(
+                    ctx.accounts.payer.to_account_info(),
+                    ctx.accounts.tick_array_upper.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    pool_state_loader,
+                    tick_array_upper_start_index,
+                    pool_state.tick_spacing,
+                )?
+            };
+        let protocol_position = &mut ctx.accounts.protocol_position;
+        if protocol_position.pool_id == Pubkey::default() {
+            protocol_position.bump = ctx.bumps.protocol_position;
+            protocol_position.pool_id = pool_state_loader.key();
+            protocol_position.tick_lower_index = tick_lower_index;
+            protocol_position.tick_upper_index = tick_upper_index;
+        }
+
+        (liquidity, _, _) = get_compound_liquidity(
+            pool_state.tick_current,
+            pool_state.sqrt_price_x64,
+            tick_lower_index,
+            tick_upper_index,
+            amount_0,
+            amount_1,
+        )?;
+        require_gte!(liquidity, liquidity_min, ErrorCode::PriceSlippageCheck);
+        // release the observations for the liquidity change to write
+        drop(observation_state);
+        drop(extended_observations);
+        (deposit_amount_0, deposit_amount_1) = mint_liquidity(
+            pool_state,
+            &tick_array_lower_loader,
+            &tick_array_upper_loader,
+            protocol_position,
+            tickarray_bitmap_extension,
+            Some(ctx.accounts.observation_state.as_ref()),
+            liquidity,
+            tick_lower_index,
+            tick_upper_index,
+        )?;
+
+        personal_position.bump = [ctx.bumps.personal_position];
+        personal_position.nft_mint = nft_mint;
+        personal_position.pool_id = pool_state_loader.key();
+        personal_position.tick_lower_index = tick_lower_index;
+        personal_position.tick_upper_index = tick_upper_index;
+        personal_position.fee_growth_inside_0_last_x64 =
+            protocol_position.fee_growth_inside_0_last_x64;
+        personal_position.fee_growth_inside_1_last_x64 =
+            protocol_position.fee_growth_inside_1_last_x64;
+        personal_position.update_rewards(protocol_position.reward_growth_inside, false)?;
+        personal_position.liquidity = liquidity;
+        if let Some(reward_extension) = &reward_extension {
+            reward_extension.update_position(
+                pool_state.tick_current,
+                pool_state.tick_spacing,
+                &*tick_array_lower_loader.load()?,
+                &*tick_array_upper_loader.load()?,
+                tick_lower_index,
+                tick_upper_index,
+                i128::try_from(liquidity).unwrap(),
+                liquidity,
+            )?;
+        }
+    }
+
+    let (amount_0_transfer_fee, amount_1_transfer_fee) = if zero_for_one {
+        (transfer_fee, 0)
+    } else {
+        (0, transfer_fee)
+    };
+    emit!(CreatePersonalPositionEvent {
+        pool_state: pool_state_loader.key(),
+        minter: ctx.accounts.payer.key(),
+        nft_owner: ctx.accounts.position_nft_owner.key(),
+        tick_lower_index,
+        tick_upper_index,
+        liquidity,
+        deposit_amount_0,
+        deposit_amount_1,
+        deposit_amount_0_transfer_fee: amount_0_transfer_fee,
+        deposit_amount_1_transfer_fee: amount_1_transfer_fee,
+    });
+
+    mint_nft_and_remove_mint_authority(
+        &ctx.accounts.payer,
+        pool_state_loader,
+        personal_position,
+        &ctx.accounts.position_nft_mint,
+        &ctx.accounts.position_nft_account,
+        None,
+        None,
+        &ctx.accounts.token_program,
+        Some(&ctx.accounts.token_program_2022),
+        &ctx.accounts.system_program,
+        &ctx.accounts.rent,
+        with_metadata,
+        true,
+    )?;
+
+    // pay back the dust that did not fit the range
+    transfer_from_pool_vault_to_user(
+        pool_state_loader,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0 - deposit_amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        pool_state_loader,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1 - deposit_amount_1,
+    )?;
+    check_unclaimed_fees_and_vault(
+        pool_state_loader,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    Ok(LiquidityChangeResult {
+        liquidity,
+        amount_0: deposit_amount_0,
+        amount_1: deposit_amount_1,
+        amount_0_transfer_fee,
+        amount_1_transfer_fee,
+    })
+}
+
+/// Returns the part of `amount` to swap so that the rest and the swap output fit the range
+/// between `sqrt_price_lower_x64` and `sqrt_price_upper_x64` at the price after the swap.
+/// The swap is assumed to stay within the current liquidity, when it crosses ticks the amounts
+/// fit less well and more is paid back.
+pub fn get_single_sided_swap_amount(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    trade_fee_rate: u32,
+    sqrt_price_lower_x64: u128,
+    sqrt_price_upper_x64: u128,
+    amount: u64,
+    zero_for_one: bool,
+) -> Result<u64> {
+    if liquidity == 0 {
+        return Ok(0);
+    }
+    // the input that moves the price to the far boundary of the range, swapping it leaves
+    // nothing of the input token in the range
+    let amount_to_boundary = if zero_for_one {
+        if sqrt_price_x64 <= sqrt_price_lower_x64 {
+            return Ok(0);
+        }
+        liquidity_math::get_delta_amount_0_unsigned(
+            sqrt_price_lower_x64,
+            sqrt_price_x64,
+            liquidity,
+            true,
+        )
+    } else {
+        if sqrt_price_x64 >= sqrt_price_upper_x64 {
+            return Ok(0);
+        }
+        liquidity_math::get_delta_amount_1_unsigned(
+            sqrt_price_x64,
+            sqrt_price_upper_x64,
+            liquidity,
+            true,
+        )
+    }
+    .unwrap_or(u64::MAX);
+
+    // whether swapping `swap_amount` leaves too little of the input token for the output
+    let swaps_too_much = |swap_amount: u64| -> Result<bool> {
+        let amount_less_fee = u64::try_from(
+            u128::from(swap_amount) * u128::from(FEE_RATE_DENOMINATOR_VALUE - trade_fee_rate)
+                / u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+        .unwrap();
+        if amount_less_fee >= amount_to_boundary {
+            return Ok(true);
+        }
+        let sqrt_price_next_x64 = sqrt_price_math::get_next_sqrt_price_from_input(
+            sqrt_price_x64,
+            liquidity,
+            amount_less_fee,
+            zero_for_one,
+        );
+        let amount_left = amount - swap_amount;
+        Ok(if zero_for_one {
+            if sqrt_price_next_x64 >= sqrt_price_upper_x64 {
+                // still above the range, only token_1 is needed
+                return Ok(false);
+            }
+            let amount_out = liquidity_math::get_delta_amount_1_unsigned(
+                sqrt_price_next_x64,
+                sqrt_price_x64,
+                liquidity,
+                false,
+            )?;
+            liquidity_math::get_liquidity_from_single_amount_1(
+                sqrt_price_next_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                amount_out,
+            ) > liquidity_math::get_liquidity_from_single_amount_0(
+                sqrt_price_next_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                amount_left,
+            )
+        } else {
+            if sqrt_price_next_x64 <= sqrt_price_lower_x64 {
+                // still below the range, only token_0 is needed
+                return Ok(false);
+            }
+            let amount_out = liquidity_math::get_delta_amount_0_unsigned(
+                sqrt_price_x64,
+                sqrt_price_next_x64,
+                liquidity,
+                false,
+            )?;
+            liquidity_math::get_liquidity_from_single_amount_0(
+                sqrt_price_next_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                amount_out,
+            ) > liquidity_math::get_liquidity_from_single_amount_1(
+                sqrt_price_next_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                amount_left,
+            )
+        })
+    };
+
+    // the largest swap amount that doesn't swap too much
+    let mut low = 0;
+    let mut high = amount;
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if swaps_too_much(mid)? {
+            high = mid - 1;
+        } else {
+            low = mid;
+        }
+    }
+    Ok(low)
+}
+
+#[cfg(test)]
+mod open_position_single_sided_test {
+    use super::*;
+
+    fn get_amounts_after_swap(
+        sqrt_price_x64: u128,
+        liquidity: u128,
+        amount: u64,
+        swap_amount: u64,
+        zero_for_one: bool,
+    ) -> (u128, u64, u64) {
+        let sqrt_price_next_x64 = sqrt_price_math::get_next_sqrt_price_from_input(
+            sqrt_price_x64,
+            liquidity,
+            swap_amount,
+            zero_for_one,
+        );
+        if zero_for_one {
+            let amount_out = liquidity_math::get_delta_amount_1_unsigned(
+                sqrt_price_next_x64,
+                sqrt_price_x64,
+                liquidity,
+                false,
+            )
+            .unwrap();
+            (sqrt_price_next_x64, amount - swap_amount, amount_out)
+        } else {
+            let amount_out = liquidity_math::get_delta_amount_0_unsigned(
+                sqrt_price_x64,
+                sqrt_price_next_x64,
+                liquidity,
+                false,
+            )
+            .unwrap();
+            (sqrt_price_next_x64, amount_out, amount - swap_amount)
+        }
+    }
+
+    #[test]
+    fn swap_amount_in_range_test() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(-1000).unwrap();
+        let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(1000).unwrap();
+        let liquidity = 1_000_000_000_000;
+        let amount = 1_000_000_000;
+        for zero_for_one in [true, false] {
+            let swap_amount = get_single_sided_swap_amount(
+                sqrt_price_x64,
+                liquidity,
+                0,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                amount,
+                zero_for_one,
+            )
+            .unwrap();
+            // the range is symmetric around the price, about half is swapped
+            assert!(swap_amount > amount / 2 - amount / 100);
+            assert!(swap_amount < amount / 2 + amount / 100);
+
+            let (sqrt_price_next_x64, amount_0, amount_1) = get_amounts_after_swap(
+                sqrt_price_x64,
+                liquidity,
+                amount,
+                swap_amount,
+                zero_for_one,
+            );
+            let liquidity_0 = liquidity_math::get_liquidity_from_single_amount_0(
+                sqrt_price_next_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                amount_0,
+            );
+            let liquidity_1 = liquidity_math::get_liquidity_from_single_amount_1(
+                sqrt_price_next_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                amount_1,
+            );
+            // both tokens fit the range up to the rounding of a single unit
+            let liquidity_difference = liquidity_0.abs_diff(liquidity_1);
+            assert!(liquidity_difference * 100_000 < liquidity_0);
+        }
+    }
+
+    #[test]
+    fn swap_amount_with_fee_test() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(-1000).unwrap();
+        let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(1000).unwrap();
+        let swap_amount = |trade_fee_rate| {
+            get_single_sided_swap_amount(
+                sqrt_price_x64,
+                1_000_000_000_000,
+                trade_fee_rate,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                1_000_000_000,
+                true,
+            )
+            .unwrap()
+        };
+        // the fee takes part of the swap input, more is swapped to make up for it
+        assert!(swap_amount(3000) > swap_amount(0));
+    }
+
+    #[test]
+    fn swap_amount_out_of_range_test() {
+        let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(-1000).unwrap();
+        let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(1000).unwrap();
+        let liquidity = 1_000_000_000_000;
+        let amount = 1_000_000;
+
+        // below the range only token_0 is needed
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(-2000).unwrap();
+        let swap_amount = |zero_for_one| {
+            get_single_sided_swap_amount(
+                sqrt_price_x64,
+                liquidity,
+                0,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                amount,
+                zero_for_one,
+            )
+            .unwrap()
+        };
+        assert_eq!(swap_amount(true), 0);
+        // a small swap of token_1 doesn't reach the range, all of it is swapped
+        assert_eq!(swap_amount(false), amount);
+
+        // without liquidity there is nothing to swap against
+        let swap_amount = get_single_sided_swap_amount(
+            tick_math::get_sqrt_price_at_tick(0).unwrap(),
+            0,
+            0,
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            amount,
+            true,
+        )
+        .unwrap();
+        assert_eq!(swap_amount, 0);
+    }
+}
@@ -154,6 +154,11 @@ pub struct OpenPositionV2<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // observation_state: should add the pool observation account, an oracle observation is
+    // written before the liquidity changes
+    // reward extension accounts: must add the reward extension, the position companion and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
 }
 
 pub fn open_position_v2<'a, 'b, 'c: 'info, 'info>(
@@ -167,7 +172,7 @@ pub fn open_position_v2<'a, 'b, 'c: 'info, 'info>(
     tick_array_upper_start_index: i32,
     with_metadata: bool,
     base_flag: Option<bool>,
-) -> Result<()> {
+) -> Result<LiquidityChangeResult> {
     open_position(
         &ctx.accounts.payer,
         &ctx.accounts.position_nft_owner,
@@ -140,6 +140,11 @@ pub struct OpenPositionWithToken22Nft<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // observation_state: should add the pool observation account, an oracle observation is
+    // written before the liquidity changes
+    // reward extension accounts: must add the reward extension, the position companion and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
 }
 
 pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
@@ -213,5 +218,6 @@ pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
         with_metadata,
         base_flag,
         true,
-    )
+    )?;
+    Ok(())
 }
@@ -0,0 +1,131 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::swap::simulate_swap;
+use crate::util;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account of the pool to quote the swap against
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The vault token account for input token
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for output token
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// The mint of input token
+    #[account(
+        address = input_vault.mint
+    )]
+    pub input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of output token
+    #[account(
+        address = output_vault.mint
+    )]
+    pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // tick_array_account_1
+    // tick_array_account_2
+    // tick_array_account_...
+    // swap_hook_program: may add account after the tick arrays, it is not called, followed by the
+    // accounts of the hook
+}
+
+pub fn quote_swap<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, QuoteSwap<'info>>,
+    amount: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+) -> Result<QuoteSwapResult> {
+    require!(amount != 0, ErrorCode::ZeroAmountSpecified);
+    let zero_for_one = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let zero_for_one = ctx.accounts.input_vault.mint == pool_state.token_mint_0;
+        require!(
+            if zero_for_one {
+                ctx.accounts.input_vault.key() == pool_state.token_vault_0
+                    && ctx.accounts.output_vault.key() == pool_state.token_vault_1
+            } else {
+                ctx.accounts.input_vault.key() == pool_state.token_vault_1
+                    && ctx.accounts.output_vault.key() == pool_state.token_vault_0
+            },
+            ErrorCode::InvalidInputPoolVault
+        );
+        zero_for_one
+    };
+
+    // the amount the vaults take or pay, the transfer fee is paid on top of it by the user
+    let amount_calculate_specified = if is_base_input {
+        amount
+            .checked_sub(util::get_transfer_fee(
+                ctx.accounts.input_vault_mint.clone(),
+                amount,
+            )?)
+            .unwrap()
+    } else {
+        amount
+            .checked_add(util::get_transfer_inverse_fee(
+                ctx.accounts.output_vault_mint.clone(),
+                amount,
+            )?)
+            .unwrap()
+    };
+
+    let mut result = simulate_swap(
+        &ctx.accounts.amm_config,
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.observation_state.to_account_info(),
+        ctx.remaining_accounts,
+        amount_calculate_specified,
+        if sqrt_price_limit_x64 == 0 {
+            if zero_for_one {
+                tick_math::MIN_SQRT_PRICE_X64 + 1
+            } else {
+                tick_math::MAX_SQRT_PRICE_X64 - 1
+            }
+        } else {
+            sqrt_price_limit_x64
+        },
+        zero_for_one,
+        is_base_input,
+        oracle::block_timestamp(),
+    )?;
+
+    result.amount_in = if is_base_input && result.amount_in == amount_calculate_specified {
+        amount
+    } else {
+        result
+            .amount_in
+            .checked_add(util::get_transfer_inverse_fee(
+                ctx.accounts.input_vault_mint.clone(),
+                result.amount_in,
+            )?)
+            .unwrap()
+    };
+    result.amount_out = if !is_base_input && result.amount_out == amount_calculate_specified {
+        amount
+    } else {
+        result
+            .amount_out
+            .checked_sub(util::get_transfer_fee(
+                ctx.accounts.output_vault_mint.clone(),
+                result.amount_out,
+            )?)
+            .unwrap()
+    };
+    Ok(result)
+}
@@ -0,0 +1,737 @@
+use super::{
+    burn_liquidity, calculate_latest_token_fees, check_unclaimed_fees_and_vault,
+    get_compound_liquidity, mint_liquidity,
+};
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::swap::swap_internal;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_upper_index: i32, tick_array_lower_start_index: i32, tick_array_upper_start_index: i32)]
+pub struct RebalancePosition<'info> {
+    /// The position owner or a delegate, pays for the accounts of the new range
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Move the liquidity of this position
+    #[account(
+        mut,
+        constraint = personal_position.pool_id == pool_state.key(),
+        constraint = personal_position.tick_lower_index != tick_lower_index
+            || personal_position.tick_upper_index != tick_upper_index
+            @ ErrorCode::RebalanceToSameRange
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The protocol position of the current range
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick of the current range
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick of the current range
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The protocol position of the new range
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        payer = nft_owner,
+        space = ProtocolPositionState::LEN
+    )]
+    pub new_protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// CHECK: Account to store data for the lower tick of the new range
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub new_tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the upper tick of the new range
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub new_tick_array_upper: UncheckedAccount<'info>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for the token_0 left over
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for the token_1 left over
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Program to create the new protocol position and tick arrays
+    pub system_program: Program<'info, System>,
+    // remaining accounts
+    // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // position_delegate: must add account if signed by a delegate regardless the sequence
+    // reward extension accounts: must add the reward extension, the position companion and the
+    // tick array companions if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
+    // tick_array_account_1, tick_array_account_2, ... for the swap
+}
+
+/// Moves all liquidity of a position to a new tick range, swapping `swap_amount` of the removed
+/// tokens through the pool in between. The position keeps its NFT, fees and rewards owed, and
+/// the tokens that don't fit the new range are paid out to the recipient accounts.
+pub fn rebalance_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RebalancePosition<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    swap_amount: u64,
+    zero_for_one: bool,
+    liquidity_min: u128,
+) -> Result<()> {
+    let nft_mint = ctx.accounts.personal_position.nft_mint;
+    let position_delegate = check_position_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        nft_mint,
+        ctx.remaining_accounts,
+        PositionDelegatePermission::DecreaseLiquidity,
+    )?;
+    check_position_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        nft_mint,
+        ctx.remaining_accounts,
+        PositionDelegatePermission::IncreaseLiquidity,
+    )?;
+    if let Some(position_delegate) = position_delegate {
+        // a delegate can only pay out to the position owner
+        require_keys_eq!(
+            ctx.accounts.recipient_token_account_0.owner,
+            position_delegate.nft_holder,
+            ErrorCode::NotApproved
+        );
+        require_keys_eq!(
+            ctx.accounts.recipient_token_account_1.owner,
+            position_delegate.nft_holder,
+            ErrorCode::NotApproved
+        );
+    }
+
+    let pool_state_loader = &ctx.accounts.pool_state;
+    let personal_position = &mut ctx.accounts.personal_position;
+    let tick_lower_before = personal_position.tick_lower_index;
+    let tick_upper_before = personal_position.tick_upper_index;
+    let liquidity_before = personal_position.liquidity;
+    let decrease_amount_0;
+    let decrease_amount_1;
+    let mut swap_amount_0 = 0;
+    let mut swap_amount_1 = 0;
+    let liquidity;
+    let deposit_amount_0;
+    let deposit_amount_1;
+    {
+        let pool_state = &mut pool_state_loader.load_mut()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+            || !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+        {
+            return err!(ErrorCode::NotApproved);
+        }
+        check_ticks_order(tick_lower_index, tick_upper_index)?;
+        check_tick_array_start_index(
+            tick_array_lower_start_index,
+            tick_lower_index,
+            pool_state.tick_spacing,
+        )?;
+        check_tick_array_start_index(
+            tick_array_upper_start_index,
+            tick_upper_index,
+            pool_state.tick_spacing,
+        )?;
+
+        let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
+        let tickarray_bitmap_extension = ctx
+            .remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == tick_array_bitmap_extension_key);
+        let use_tickarray_bitmap_extension = pool_state.is_overflow_default_tickarray_bitmap(vec![
+            ctx.accounts.tick_array_lower.load()?.start_tick_index,
+            ctx.accounts.tick_array_upper.load()?.start_tick_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+        ]);
+        if use_tickarray_bitmap_extension {
+            require!(
+                tickarray_bitmap_extension.is_some(),
+                ErrorCode::MissingTickArrayBitmapExtensionAccount
+            );
+        }
+
+        update_scheduled_reward_infos(
+            pool_state,
+            pool_state_loader.key(),
+            ctx.remaining_accounts,
+            Clock::get()?.unix_timestamp as u64,
+        )?;
+        let reward_extension = RewardExtensionAccounts::load(
+            pool_state,
+            pool_state_loader.key(),
+            Some(nft_mint),
+            ctx.remaining_accounts,
+            Clock::get()?.unix_timestamp as u64,
+        )?;
+
+        // remove all liquidity, the fees and rewards earned stay owed to the position
+        let protocol_position = &mut ctx.accounts.protocol_position;
+        (decrease_amount_0, decrease_amount_1) = burn_liquidity(
+            pool_state,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            protocol_position,
+            tickarray_bitmap_extension,
+            Some(ctx.accounts.observation_state.as_ref()),
+            liquidity_before,
+        )?;
+        personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+            personal_position.token_fees_owed_0,
+            personal_position.fee_growth_inside_0_last_x64,
+            protocol_position.fee_growth_inside_0_last_x64,
+            personal_position.liquidity,
+        );
+        personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+            personal_position.token_fees_owed_1,
+            personal_position.fee_growth_inside_1_last_x64,
+            protocol_position.fee_growth_inside_1_last_x64,
+            personal_position.liquidity,
+        );
+        personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+        personal_position.liquidity = 0;
+        if let Some(reward_extension) = &reward_extension {
+            reward_extension.update_position(
+                pool_state.tick_current,
+                pool_state.tick_spacing,
+                &*ctx.accounts.tick_array_lower.load()?,
+                &*ctx.accounts.tick_array_upper.load()?,
+                tick_lower_before,
+                tick_upper_before,
+                -i128::try_from(liquidity_before).unwrap(),
+                0,
+            )?;
+        }
+
+        let mut amount_0 = decrease_amount_0;
+        let mut amount_1 = decrease_amount_1;
+        if swap_amount > 0 {
+            // the swap hook is only called by the swap instructions
+            require_keys_eq!(
+                pool_state.swap_hook_program,
+                Pubkey::default(),
+                ErrorCode::SwapHookNotSupported
+            );
+            require_gte!(
+                if zero_for_one { amount_0 } else { amount_1 },
+                swap_amount,
+                ErrorCode::TooMuchInputPaid
+            );
+            let mut tick_array_states = VecDeque::new();
+            for account_info in ctx.remaining_accounts.iter() {
+                if account_info.key() == tick_array_bitmap_extension_key
+                    || position_delegate
+                        .is_some_and(|delegate| account_info.key() == delegate.position_delegate)
+                    || RewardExtensionAccounts::is_extension_account(account_info)
+                {
+                    continue;
+                }
+                tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
+            }
+            let tickarray_bitmap_extension = match tickarray_bitmap_extension {
+                Some(account_info) => Some(
+                    *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
+                        .load()?
+                        .deref()),
+                ),
+                None => None,
+            };
+            let (mut observation_state, mut extended_observations) =
+                ObservationState::load_extended_mut(ctx.accounts.observation_state.as_ref())?;
+            let tick_before = pool_state.tick_current;
+            let trade_fee_rate;
+            (swap_amount_0, swap_amount_1, trade_fee_rate) = swap_internal(
+                &ctx.accounts.amm_config,
+                pool_state,
+                &mut tick_array_states,
+                &mut observation_state,
+                &mut extended_observations,
+                &tickarray_bitmap_extension,
+                swap_amount,
+                if zero_for_one {
+                    tick_math::MIN_SQRT_PRICE_X64 + 1
+                } else {
+                    tick_math::MAX_SQRT_PRICE_X64 - 1
+                },
+                zero_for_one,
+                true,
+                oracle::block_timestamp(),
+            )?;
+            if let Some(reward_extension) = &reward_extension {
+                // release the tick arrays for the extension to read
+                tick_array_states.clear();
+                reward_extension.cross_ticks(
+                    pool_state_loader.key(),
+                    ctx.remaining_accounts,
+                    tick_before,
+                    pool_state.tick_current,
+                )?;
+            }
+            require!(
+                swap_amount_0 != 0 && swap_amount_1 != 0,
+                ErrorCode::TooSmallInputOrOutputAmount
+            );
+            (amount_0, amount_1) = get_rebalance_amounts_after_swap(
+                decrease_amount_0,
+                decrease_amount_1,
+                swap_amount_0,
+                swap_amount_1,
+                zero_for_one,
+            )?;
+            emit!(SwapEvent {
+                pool_state: pool_state_loader.key(),
+                sender: ctx.accounts.nft_owner.key(),
+                token_account_0: ctx.accounts.recipient_token_account_0.key(),
+                token_account_1: ctx.accounts.recipient_token_account_1.key(),
+                amount_0: swap_amount_0,
+                transfer_fee_0: 0,
+                amount_1: swap_amount_1,
+                transfer_fee_1: 0,
+                zero_for_one,
+                sqrt_price_x64: pool_state.sqrt_price_x64,
+                liquidity: pool_state.liquidity,
+                tick: pool_state.tick_current,
+                trade_fee_rate,
+            });
+        }
+
+        // add the tokens to the new range, as much as fits the price after the swap
+        let new_tick_array_lower = TickArrayState::get_or_create_tick_array(
+            ctx.accounts.nft_owner.to_account_info(),
+            ctx.accounts.new_tick_array_lower.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            pool_state_loader,
+            tick_array_lower_start_index,
+            pool_state.tick_spacing,
+        )?;
+        let new_tick_array_upper = if tick_array_lower_start_index == tick_array_upper_start_index {
+            AccountLoad::<TickArrayState>::try_from(
+                &ctx.accounts.new_tick_array_upper.to_account_info(),
+            )?
+        } else {
+            TickArrayState::get_or_create_tick_array(
+                ctx.accounts.nft_owner.to_account_info(),
+                ctx.accounts.new_tick_array_upper.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                pool_state_loader,
+                tick_array_upper_start_index,
+                pool_state.tick_spacing,
+            )?
+        };
+        let new_protocol_position = &mut ctx.accounts.new_protocol_position;
+        if new_protocol_position.pool_id == Pubkey::default() {
+            new_protocol_position.bump = ctx.bumps.new_protocol_position;
+            new_protocol_position.pool_id = pool_state_loader.key();
+            new_protocol_position.tick_lower_index = tick_lower_index;
+            new_protocol_position.tick_upper_index = tick_upper_index;
+        }
+
+        (liquidity, _, _) = get_compound_liquidity(
+            pool_state.tick_current,
+            pool_state.sqrt_price_x64,
+            tick_lower_index,
+            tick_upper_index,
+            amount_0,
+            amount_1,
+        )?;
+        require_gte!(liquidity, liquidity_min, ErrorCode::PriceSlippageCheck);
+        (deposit_amount_0, deposit_amount_1) = mint_liquidity(
+            pool_state,
+            &new_tick_array_lower,
+            &new_tick_array_upper,
+            new_protocol_position,
+            tickarray_bitmap_extension,
+            Some(ctx.accounts.observation_state.as_ref()),
+            liquidity,
+            tick_lower_index,
+            tick_upper_index,
+        )?;
+
+        personal_position.tick_lower_index = tick_lower_index;
+        personal_position.tick_upper_index = tick_upper_index;
+        personal_position.fee_growth_inside_0_last_x64 =
+            new_protocol_position.fee_growth_inside_0_last_x64;
+        personal_position.fee_growth_inside_1_last_x64 =
+            new_protocol_position.fee_growth_inside_1_last_x64;
+        personal_position.update_rewards(new_protocol_position.reward_growth_inside, false)?;
+        personal_position.liquidity = liquidity;
+        if let Some(reward_extension) = &reward_extension {
+            reward_extension.update_position(
+                pool_state.tick_current,
+                pool_state.tick_spacing,
+                &*new_tick_array_lower.load()?,
+                &*new_tick_array_upper.load()?,
+                tick_lower_index,
+                tick_upper_index,
+                i128::try_from(liquidity).unwrap(),
+                liquidity,
+            )?;
+        }
+    }
+
+    // pay out what did not fit the new range
+    let (refund_amount_0, refund_amount_1) = get_rebalance_refund_amounts(
+        decrease_amount_0,
+        decrease_amount_1,
+        swap_amount_0,
+        swap_amount_1,
+        zero_for_one,
+        deposit_amount_0,
+        deposit_amount_1,
+    )?;
+    transfer_from_pool_vault_to_user(
+        pool_state_loader,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        refund_amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        pool_state_loader,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        refund_amount_1,
+    )?;
+    check_unclaimed_fees_and_vault(
+        pool_state_loader,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    emit!(RebalancePositionEvent {
+        position_nft_mint: nft_mint,
+        tick_lower_before,
+        tick_upper_before,
+        liquidity_before,
+        tick_lower: tick_lower_index,
+        tick_upper: tick_upper_index,
+        liquidity,
+        decrease_amount_0,
+        decrease_amount_1,
+        swap_amount_0,
+        swap_amount_1,
+        zero_for_one,
+        deposit_amount_0,
+        deposit_amount_1,
+        refund_amount_0,
+        refund_amount_1,
+    });
+
+    Ok(())
+}
+
+/// Returns the amounts of token_0 and token_1 held after the tokens removed from the position are
+/// swapped, `swap_amount_0` and `swap_amount_1` being the amounts the swap moved in `zero_for_one`.
+/// No swap moves zero amounts.
+pub fn get_rebalance_amounts_after_swap(
+    decrease_amount_0: u64,
+    decrease_amount_1: u64,
+    swap_amount_0: u64,
+    swap_amount_1: u64,
+    zero_for_one: bool,
+) -> Result<(u64, u64)> {
+    if zero_for_one {
+        Ok((
+            decrease_amount_0
+                .checked_sub(swap_amount_0)
+                .ok_or(ErrorCode::TooMuchInputPaid)?,
+            decrease_amount_1.checked_add(swap_amount_1).unwrap(),
+        ))
+    } else {
+        Ok((
+            decrease_amount_0.checked_add(swap_amount_0).unwrap(),
+            decrease_amount_1
+                .checked_sub(swap_amount_1)
+                .ok_or(ErrorCode::TooMuchInputPaid)?,
+        ))
+    }
+}
+
+/// Returns the amounts of token_0 and token_1 paid out to the owner, what is held after the swap
+/// and did not fit the new range
+pub fn get_rebalance_refund_amounts(
+    decrease_amount_0: u64,
+    decrease_amount_1: u64,
+    swap_amount_0: u64,
+    swap_amount_1: u64,
+    zero_for_one: bool,
+    deposit_amount_0: u64,
+    deposit_amount_1: u64,
+) -> Result<(u64, u64)> {
+    let (amount_0, amount_1) = get_rebalance_amounts_after_swap(
+        decrease_amount_0,
+        decrease_amount_1,
+        swap_amount_0,
+        swap_amount_1,
+        zero_for_one,
+    )?;
+    Ok((
+        amount_0.checked_sub(deposit_amount_0).unwrap(),
+        amount_1.checked_sub(deposit_amount_1).unwrap(),
+    ))
+}
+
+#[cfg(test)]
+mod rebalance_position_test {
+    use super::*;
+    use crate::libraries::swap_math;
+    use proptest::prelude::*;
+
+    #[test]
+    fn refund_without_swap_test() {
+        assert_eq!(
+            get_rebalance_refund_amounts(1000, 2000, 0, 0, true, 400, 2000).unwrap(),
+            (600, 0)
+        );
+        assert_eq!(
+            get_rebalance_refund_amounts(1000, 2000, 0, 0, false, 1000, 1500).unwrap(),
+            (0, 500)
+        );
+    }
+
+    #[test]
+    fn refund_after_zero_for_one_swap_test() {
+        // 300 token_0 are swapped for 250 token_1
+        assert_eq!(
+            get_rebalance_amounts_after_swap(1000, 2000, 300, 250, true).unwrap(),
+            (700, 2250)
+        );
+        assert_eq!(
+            get_rebalance_refund_amounts(1000, 2000, 300, 250, true, 700, 2200).unwrap(),
+            (0, 50)
+        );
+    }
+
+    #[test]
+    fn refund_after_one_for_zero_swap_test() {
+        // 300 token_1 are swapped for 250 token_0
+        assert_eq!(
+            get_rebalance_amounts_after_swap(1000, 2000, 250, 300, false).unwrap(),
+            (1250, 1700)
+        );
+        assert_eq!(
+            get_rebalance_refund_amounts(1000, 2000, 250, 300, false, 1200, 1700).unwrap(),
+            (50, 0)
+        );
+    }
+
+    #[test]
+    fn swap_more_than_decreased_test() {
+        assert_eq!(
+            get_rebalance_amounts_after_swap(1000, 2000, 1001, 10, true).unwrap_err(),
+            ErrorCode::TooMuchInputPaid.into()
+        );
+        assert_eq!(
+            get_rebalance_amounts_after_swap(1000, 2000, 10, 2001, false).unwrap_err(),
+            ErrorCode::TooMuchInputPaid.into()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn deposit_more_than_held_test() {
+        get_rebalance_refund_amounts(1000, 2000, 0, 0, true, 1001, 0).unwrap();
+    }
+
+    proptest! {
+        #[test]
+        fn refund_is_decrease_and_swap_less_deposit_test(
+            decrease_amount_0 in 0..u32::MAX as u64,
+            decrease_amount_1 in 0..u32::MAX as u64,
+            swap_percent in 0..=100u64,
+            zero_for_one in proptest::bool::ANY,
+            tick_current in -20000..20000i32,
+            tick_lower in -20000..20000i32,
+            tick_width in 1..20000i32,
+        ) {
+            let tick_upper = tick_lower + tick_width;
+            let mut sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+            let swap_amount = if zero_for_one {
+                decrease_amount_0
+            } else {
+                decrease_amount_1
+            } * swap_percent / 100;
+
+            // swap through a single range of liquidity, as the instruction swaps the input amount
+            let (mut swap_amount_0, mut swap_amount_1) = (0, 0);
+            if swap_amount > 0 {
+                let swap_step = swap_math::compute_swap_step(
+                    sqrt_price_x64,
+                    if zero_for_one {
+                        tick_math::MIN_SQRT_PRICE_X64 + 1
+                    } else {
+                        tick_math::MAX_SQRT_PRICE_X64 - 1
+                    },
+                    1 << 64,
+                    swap_amount,
+                    2500,
+                    true,
+                    zero_for_one,
+                    1,
+                )
+                .unwrap();
+                let amount_in = swap_step.amount_in + swap_step.fee_amount;
+                (swap_amount_0, swap_amount_1) = if zero_for_one {
+                    (amount_in, swap_step.amount_out)
+                } else {
+                    (swap_step.amount_out, amount_in)
+                };
+                sqrt_price_x64 = swap_step.sqrt_price_next_x64;
+            }
+            let tick_after_swap = tick_math::get_tick_at_sqrt_price(sqrt_price_x64).unwrap();
+
+            let (amount_0, amount_1) = get_rebalance_amounts_after_swap(
+                decrease_amount_0,
+                decrease_amount_1,
+                swap_amount_0,
+                swap_amount_1,
+                zero_for_one,
+            )
+            .unwrap();
+            let (_, deposit_amount_0, deposit_amount_1) = get_compound_liquidity(
+                tick_after_swap,
+                sqrt_price_x64,
+                tick_lower,
+                tick_upper,
+                amount_0,
+                amount_1,
+            )
+            .unwrap();
+            let (refund_amount_0, refund_amount_1) = get_rebalance_refund_amounts(
+                decrease_amount_0,
+                decrease_amount_1,
+                swap_amount_0,
+                swap_amount_1,
+                zero_for_one,
+                deposit_amount_0,
+                deposit_amount_1,
+            )
+            .unwrap();
+
+            let (swap_delta_0, swap_delta_1) = if zero_for_one {
+                (-i128::from(swap_amount_0), i128::from(swap_amount_1))
+            } else {
+                (i128::from(swap_amount_0), -i128::from(swap_amount_1))
+            };
+            assert_eq!(
+                i128::from(refund_amount_0),
+                i128::from(decrease_amount_0) + swap_delta_0 - i128::from(deposit_amount_0)
+            );
+            assert_eq!(
+                i128::from(refund_amount_1),
+                i128::from(decrease_amount_1) + swap_delta_1 - i128::from(deposit_amount_1)
+            );
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use super::get_ended_reward_info;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct RefundRewardFunders<'info> {
+    /// The pool owner, who initialized the reward
+    #[account(address = pool_state.load()?.owner)]
+    pub authority: Signer<'info>,
+
+    /// The pool the reward belongs to
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Tracks the deposits of the funders into the reward
+    #[account(
+        mut,
+        seeds = [
+            REWARD_FUNDING_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &[reward_index],
+        ],
+        bump = reward_funding.bump,
+    )]
+    pub reward_funding: Box<Account<'info, RewardFundingState>>,
+
+    /// The reward vault the funders are refunded from
+    #[account(mut)]
+    pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of reward token vault
+    #[account(
+        address = reward_token_vault.mint
+    )]
+    pub reward_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+    // remaining accounts
+    // reward_extension: must add account if the reward index is of an extension reward
+    // reward_schedule: must add account for each pool reward following a schedule
+    // the last `funder_count` pairs of accounts:
+    // reward_funder: the deposits of a funder not refunded yet
+    // funder_token_account: the reward token account of the funder
+}
+
+/// Settles a reward that has ended and refunds the funders who have not collected their share of
+/// the unemitted tokens yet, so a new round of deposits can start
+pub fn refund_reward_funders<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RefundRewardFunders<'info>>,
+    reward_index: u8,
+    funder_count: u8,
+) -> Result<()> {
+    let funder_accounts_len = 2 * usize::from(funder_count);
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        funder_accounts_len,
+        ErrorCode::InvalidRewardInputAccountNumber
+    );
+    let (remaining_accounts, funder_accounts) = ctx
+        .remaining_accounts
+        .split_at(ctx.remaining_accounts.len() - funder_accounts_len);
+
+    let reward_info =
+        get_ended_reward_info(&ctx.accounts.pool_state, remaining_accounts, reward_index)?;
+    require_keys_eq!(
+        ctx.accounts.reward_token_vault.key(),
+        reward_info.token_vault
+    );
+    let reward_funding = &mut ctx.accounts.reward_funding;
+    reward_funding.settle(get_unowed_reward_amount(
+        &reward_info,
+        ctx.accounts.reward_token_vault.amount,
+    ));
+
+    for accounts in funder_accounts.chunks(2) {
+        let mut reward_funder = Account::<RewardFunderState>::try_from(&accounts[0])?;
+        require_keys_eq!(reward_funder.reward_funding, reward_funding.key());
+        let funder_token_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+        require_keys_eq!(funder_token_account.owner, reward_funder.funder);
+        require_keys_eq!(
+            funder_token_account.mint,
+            ctx.accounts.reward_token_vault.mint
+        );
+
+        let amount = reward_funding.refund(&mut reward_funder);
+        reward_funder.exit(&crate::id())?;
+        transfer_from_pool_vault_to_user(
+            &ctx.accounts.pool_state,
+            &ctx.accounts.reward_token_vault.to_account_info(),
+            &funder_token_account.to_account_info(),
+            Some(ctx.accounts.reward_vault_mint.clone()),
+            &ctx.accounts.token_program,
+            Some(ctx.accounts.token_program_2022.to_account_info()),
+            amount,
+        )?;
+
+        emit!(RefundRewardFunderEvent {
+            pool_state: ctx.accounts.pool_state.key(),
+            funder: reward_funder.funder,
+            reward_index,
+            amount,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RevokePositionDelegate<'info> {
+    /// The holder who granted the delegate, receives the delegate account rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The delegate account to be closed
+    #[account(
+        mut,
+        has_one = owner,
+        close = owner
+    )]
+    pub position_delegate: Box<Account<'info, PositionDelegateState>>,
+}
+
+pub fn revoke_position_delegate(ctx: Context<RevokePositionDelegate>) -> Result<()> {
+    emit!(RevokePositionDelegateEvent {
+        position_nft_mint: ctx.accounts.position_delegate.nft_mint,
+        owner: ctx.accounts.owner.key(),
+        delegate: ctx.accounts.position_delegate.delegate,
+    });
+    Ok(())
+}
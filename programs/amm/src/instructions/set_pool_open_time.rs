@@ -0,0 +1,38 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolOpenTime<'info> {
+    /// The pool owner or the admin
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Moves the time the pool opens for swaps, only until it opens. A time already passed opens the
+/// pool at once, unless swaps are disabled by the pool status, which takes precedence.
+pub fn set_pool_open_time(ctx: Context<SetPoolOpenTime>, open_time: u64) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require!(
+        ctx.accounts.authority.key() == pool_state.owner
+            || ctx.accounts.authority.key() == crate::admin::ID,
+        ErrorCode::NotApproved
+    );
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    require_gt!(
+        pool_state.open_time,
+        block_timestamp,
+        ErrorCode::PoolAlreadyOpen
+    );
+    let old_open_time = pool_state.open_time;
+    pool_state.open_time = open_time;
+
+    emit!(PoolOpenTimeChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        old_open_time,
+        open_time,
+    });
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use crate::error::ErrorCode;
 use crate::libraries::{fixed_point_64, full_math::MulDiv, U256};
 use crate::states::pool::{reward_period_limit, PoolState, REWARD_NUM};
+use crate::states::reward_extension::EXTENSION_REWARD_NUM;
 use crate::util::transfer_from_user_to_pool_vault;
 use crate::{states::*, util};
 use anchor_lang::prelude::*;
@@ -36,6 +37,11 @@ pub struct SetRewardParams<'info> {
     pub token_program: Program<'info, Token>,
     /// Token program 2022
     pub token_program_2022: Program<'info, Token2022>,
+    // remaining accounts
+    // reward_extension: must add account if the reward index is of an extension reward
+    // reward_funding: must add account if the reward has funders
+    // reward_schedule: must add account for each pool reward following a schedule
+    // reward_token_vault, authority_token_account, reward_vault_mint: if more reward is needed
 }
 
 pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
@@ -45,7 +51,7 @@ pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
     open_time: u64,
     end_time: u64,
 ) -> Result<()> {
-    assert!((reward_index as usize) < REWARD_NUM);
+    assert!((reward_index as usize) < REWARD_NUM + EXTENSION_REWARD_NUM);
     require_gt!(end_time, open_time);
     require_gt!(emissions_per_second_x64, 0);
     let operation_state = ctx.accounts.operation_state.load()?;
@@ -62,12 +68,47 @@ pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
         require_keys_eq!(ctx.accounts.authority.key(), pool_state.owner);
     }
 
+    update_scheduled_reward_infos(
+        &mut pool_state,
+        ctx.accounts.pool_state.key(),
+        ctx.remaining_accounts,
+        current_timestamp,
+    )?;
     pool_state.update_reward_infos(current_timestamp)?;
 
-    let mut reward_info = pool_state.reward_infos[reward_index as usize];
+    // the reward indexes from REWARD_NUM are of the reward extension
+    let reward_extension = if reward_index as usize >= REWARD_NUM {
+        let reward_extension = RewardExtensionAccounts::find_reward_extension(
+            ctx.accounts.pool_state.key(),
+            ctx.remaining_accounts,
+        )?
+        .ok_or(ErrorCode::MissingRewardExtensionAccount)?;
+        reward_extension
+            .load_mut()?
+            .update_reward_infos(current_timestamp, pool_state.liquidity)?;
+        Some(reward_extension)
+    } else {
+        None
+    };
+
+    let mut reward_info = match &reward_extension {
+        Some(reward_extension) => {
+            reward_extension.load()?.reward_infos[reward_index as usize - REWARD_NUM]
+        }
+        None => pool_state.reward_infos[reward_index as usize],
+    };
     if !reward_info.initialized() {
         return err!(ErrorCode::UnInitializedRewardInfo);
     }
+    if reward_extension.is_none() && pool_state.scheduled_reward_flags & (1 << reward_index) != 0 {
+        // once its schedule ended the reward is back to constant emissions
+        require_eq!(
+            reward_info.last_update_time,
+            reward_info.end_time,
+            ErrorCode::RewardScheduled
+        );
+        pool_state.scheduled_reward_flags &= !(1 << reward_index);
+    }
 
     let reward_amount = if admin_operator {
         admin_update(
@@ -92,10 +133,61 @@ pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
         .unwrap()
     };
 
-    pool_state.reward_infos[reward_index as usize] = reward_info;
+    match &reward_extension {
+        Some(reward_extension) => {
+            reward_extension.load_mut()?.reward_infos[reward_index as usize - REWARD_NUM] =
+                reward_info
+        }
+        None => pool_state.reward_infos[reward_index as usize] = reward_info,
+    }
+
+    deposit_reward(
+        &ctx.accounts.authority,
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_program_2022,
+        &pool_state,
+        ctx.accounts.pool_state.key(),
+        reward_index,
+        &reward_info,
+        ctx.remaining_accounts,
+        reward_amount,
+    )?;
+
+    Ok(())
+}
+
+/// Transfers the `reward_amount` the reward authority adds to a reward from its token account,
+/// the reward_token_vault, authority_token_account and reward_vault_mint remaining accounts
+pub fn deposit_reward<'info>(
+    authority: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+    token_program_2022: &Program<'info, Token2022>,
+    pool_state: &PoolState,
+    pool_id: Pubkey,
+    reward_index: u8,
+    reward_info: &RewardInfo,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    reward_amount: u64,
+) -> Result<()> {
+    // the tokens added count in the base the funders are refunded on
+    let reward_funding_key = RewardFundingState::key(pool_id, reward_index);
+    if pool_state.funded_reward_flags & (1 << reward_index) != 0 {
+        let reward_funding_info = remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == reward_funding_key)
+            .ok_or(ErrorCode::MissingRewardFundingAccount)?;
+        let mut reward_funding = Account::<RewardFundingState>::try_from(reward_funding_info)?;
+        if !reward_funding.settled {
+            reward_funding.budget = reward_funding.budget.checked_add(reward_amount).unwrap();
+            reward_funding.exit(&crate::id())?;
+        }
+    }
 
     if reward_amount > 0 {
-        let mut remaining_accounts = ctx.remaining_accounts.iter();
+        let mut remaining_accounts = remaining_accounts.iter().filter(|account_info| {
+            !RewardExtensionAccounts::is_extension_account(account_info)
+                && account_info.key() != reward_funding_key
+        });
 
         let reward_token_vault =
             InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts.next().unwrap())?;
@@ -113,12 +205,12 @@ pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
         let reward_amount_with_transfer_fee = reward_amount.checked_add(transfer_fee).unwrap();
 
         transfer_from_user_to_pool_vault(
-            &ctx.accounts.authority,
+            authority,
             &authority_token_account.to_account_info(),
             &reward_token_vault.to_account_info(),
             Some(Box::new(reward_vault_mint)),
-            &ctx.accounts.token_program,
-            Some(ctx.accounts.token_program_2022.to_account_info()),
+            token_program,
+            Some(token_program_2022.to_account_info()),
             reward_amount_with_transfer_fee,
         )?;
     }
@@ -0,0 +1,121 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::reward_extension::EXTENSION_REWARD_NUM;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetRewardRange<'info> {
+    /// The pool owner or an operation admin
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// load info from the account to judge reward permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+    // remaining accounts
+    // reward_schedule: must add account for each pool reward following a schedule
+}
+
+pub fn set_reward_range(
+    ctx: Context<SetRewardRange>,
+    reward_index: u8,
+    enabled: bool,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> Result<()> {
+    check_reward_range_index(reward_index)?;
+    let operation_state = ctx.accounts.operation_state.load()?;
+    let admin_operator = operation_state
+        .operation_owners
+        .contains(&ctx.accounts.authority.key())
+        && ctx.accounts.authority.key() != Pubkey::default();
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    if !admin_operator {
+        require_keys_eq!(ctx.accounts.authority.key(), pool_state.owner);
+    }
+    if !pool_state.reward_infos[reward_index as usize].initialized() {
+        return err!(ErrorCode::UnInitializedRewardInfo);
+    }
+
+    let range = if enabled {
+        check_ticks_order(tick_lower, tick_upper)?;
+        require!(
+            tick_lower >= tick_math::MIN_TICK,
+            ErrorCode::TickLowerOverflow
+        );
+        require!(
+            tick_upper <= tick_math::MAX_TICK,
+            ErrorCode::TickUpperOverflow
+        );
+        let tick_spacing = i32::from(pool_state.tick_spacing);
+        require!(
+            tick_lower % tick_spacing == 0 && tick_upper % tick_spacing == 0,
+            ErrorCode::TickAndSpacingNotMatch
+        );
+        Some((tick_lower, tick_upper))
+    } else {
+        None
+    };
+
+    // accrue under the previous range first
+    let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    update_scheduled_reward_infos(
+        &mut pool_state,
+        ctx.accounts.pool_state.key(),
+        ctx.remaining_accounts,
+        current_timestamp,
+    )?;
+    pool_state.update_reward_infos(current_timestamp)?;
+    pool_state.set_reward_range(reward_index as usize, range);
+
+    emit!(RewardRangeChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        reward_index,
+        enabled,
+        tick_lower: range.map_or(0, |(tick_lower, _)| tick_lower),
+        tick_upper: range.map_or(0, |(_, tick_upper)| tick_upper),
+    });
+    Ok(())
+}
+
+/// Only the pool reward slots track a tick range, the extension reward slots that follow them are
+/// rejected explicitly
+pub fn check_reward_range_index(reward_index: u8) -> Result<()> {
+    let reward_index = reward_index as usize;
+    if (REWARD_NUM..REWARD_NUM + EXTENSION_REWARD_NUM).contains(&reward_index) {
+        return err!(ErrorCode::RewardRangeNotSupported);
+    }
+    require_gt!(REWARD_NUM, reward_index, ErrorCode::InvalidRewardIndex);
+    Ok(())
+}
+
+#[cfg(test)]
+mod set_reward_range_test {
+    use super::*;
+
+    #[test]
+    fn check_reward_range_index_test() {
+        for reward_index in 0..REWARD_NUM {
+            assert!(check_reward_range_index(reward_index as u8).is_ok());
+        }
+        for reward_index in REWARD_NUM..REWARD_NUM + EXTENSION_REWARD_NUM {
+            assert_eq!(
+                check_reward_range_index(reward_index as u8).unwrap_err(),
+                ErrorCode::RewardRangeNotSupported.into()
+            );
+        }
+        assert_eq!(
+            check_reward_range_index((REWARD_NUM + EXTENSION_REWARD_NUM) as u8).unwrap_err(),
+            ErrorCode::InvalidRewardIndex.into()
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::error::ErrorCode;
+use crate::libraries::{fixed_point_64, full_math::MulDiv, U256};
+use crate::set_reward_params::deposit_reward;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::Token2022;
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct SetRewardSchedule<'info> {
+    /// The pool owner or an operation admin, pays the reward schedule rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// load info from the account to judge reward permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+
+    /// The reward schedule, replaced if it already exists
+    #[account(
+        init_if_needed,
+        seeds = [
+            REWARD_SCHEDULE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &[reward_index],
+        ],
+        bump,
+        payer = authority,
+        space = RewardScheduleState::LEN
+    )]
+    pub reward_schedule: Box<Account<'info, RewardScheduleState>>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Program to create the reward schedule account
+    pub system_program: Program<'info, System>,
+    // remaining accounts
+    // reward_funding: must add account if the reward has funders
+    // reward_schedule: must add account for each other pool reward following a schedule
+    // reward_token_vault, authority_token_account, reward_vault_mint: if more reward is needed
+}
+
+/// Replaces the emissions of a pool reward from the first point on with the step schedule
+/// `points`, up to `end_time`. The authority deposits what the schedule emits beyond what the
+/// reward already had to emit from the first point.
+pub fn set_reward_schedule<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SetRewardSchedule<'info>>,
+    reward_index: u8,
+    points: Vec<RewardSchedulePoint>,
+    end_time: u64,
+) -> Result<()> {
+    require_gt!(
+        REWARD_NUM,
+        reward_index as usize,
+        ErrorCode::InvalidRewardIndex
+    );
+    let operation_state = ctx.accounts.operation_state.load()?;
+    let admin_operator = operation_state
+        .operation_owners
+        .contains(&ctx.accounts.authority.key())
+        && ctx.accounts.authority.key() != Pubkey::default();
+    let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    if !admin_operator {
+        require_keys_eq!(ctx.accounts.authority.key(), pool_state.owner);
+    }
+
+    // accrue with the schedule being replaced first
+    let mut schedule_accounts = ctx.remaining_accounts.to_vec();
+    schedule_accounts.push(ctx.accounts.reward_schedule.to_account_info());
+    update_scheduled_reward_infos(
+        &mut pool_state,
+        ctx.accounts.pool_state.key(),
+        &schedule_accounts,
+        current_timestamp,
+    )?;
+    pool_state.update_reward_infos(current_timestamp)?;
+
+    let mut reward_info = pool_state.reward_infos[reward_index as usize];
+    if !reward_info.initialized() {
+        return err!(ErrorCode::UnInitializedRewardInfo);
+    }
+    let start_time = if admin_operator {
+        current_timestamp.max(reward_info.open_time)
+    } else {
+        // the same notice as lowering the emissions with set_reward_params
+        if current_timestamp <= reward_info.open_time {
+            return err!(ErrorCode::NotApproved);
+        }
+        require_gte!(
+            reward_period_limit::MAX_REWARD_PERIOD,
+            end_time.saturating_sub(current_timestamp),
+            ErrorCode::InvalidRewardPeriod
+        );
+        reward_info
+            .end_time
+            .min(
+                current_timestamp
+                    .checked_add(reward_period_limit::INCREASE_EMISSIONES_PERIOD)
+                    .unwrap(),
+            )
+            .max(current_timestamp)
+    };
+    RewardScheduleState::check_points(&points, start_time, end_time)?;
+
+    // the reward must emit nothing between its end and a schedule starting later
+    let mut schedule_points = Vec::with_capacity(points.len() + 1);
+    if points[0].timestamp > reward_info.end_time {
+        if reward_info.last_update_time == reward_info.end_time {
+            reward_info.emissions_per_second_x64 = 0;
+        } else {
+            schedule_points.push(RewardSchedulePoint {
+                timestamp: reward_info.end_time,
+                emissions_per_second_x64: 0,
+            });
+        }
+    }
+    schedule_points.extend_from_slice(&points);
+    require_gte!(
+        REWARD_SCHEDULE_POINT_NUM,
+        schedule_points.len(),
+        ErrorCode::InvalidRewardSchedule
+    );
+
+    let committed_amount = U256::from(reward_info.end_time.saturating_sub(points[0].timestamp))
+        .mul_div_floor(
+            U256::from(reward_info.emissions_per_second_x64),
+            U256::from(fixed_point_64::Q64),
+        )
+        .unwrap()
+        .as_u64();
+    let reward_amount =
+        RewardScheduleState::get_reward_amount(&points, end_time).saturating_sub(committed_amount);
+
+    reward_info.end_time = end_time;
+    pool_state.reward_infos[reward_index as usize] = reward_info;
+    pool_state.scheduled_reward_flags |= 1 << reward_index;
+    ctx.accounts.reward_schedule.initialize(
+        ctx.bumps.reward_schedule,
+        ctx.accounts.pool_state.key(),
+        reward_index,
+        &schedule_points,
+    );
+
+    deposit_reward(
+        &ctx.accounts.authority,
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_program_2022,
+        &pool_state,
+        ctx.accounts.pool_state.key(),
+        reward_index,
+        &reward_info,
+        ctx.remaining_accounts,
+        reward_amount,
+    )
+}
@@ -103,6 +103,8 @@ pub struct SwapState {
     pub protocol_fee: u64,
     // amount of input token paid as fund fee
     pub fund_fee: u64,
+    // amount of input token paid as pool creator fee
+    pub creator_fee: u64,
     // the current liquidity in range
     pub liquidity: u128,
 }
@@ -125,22 +127,23 @@ struct StepComputations {
     fee_amount: u64,
 }
 
+/// Swaps in the pool, returns the amounts of token_0 and token_1 swapped and the trade fee rate
+/// charged, which includes the volatility surcharge if enabled
 pub fn swap_internal<'b, 'info>(
     amm_config: &AmmConfig,
     pool_state: &mut RefMut<PoolState>,
     tick_array_states: &mut VecDeque<RefMut<TickArrayState>>,
     observation_state: &mut RefMut<ObservationState>,
+    extended_observations: &mut [Observation],
     tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
     amount_specified: u64,
-    sqrt_price_limit_x64: u128,
+    mut sqrt_price_limit_x64: u128,
     zero_for_one: bool,
     is_base_input: bool,
     block_timestamp: u32,
-) -> Result<(u64, u64)> {
+) -> Result<(u64, u64, u32)> {
     require!(amount_specified != 0, ErrorCode::ZeroAmountSpecified);
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap) {
-        return err!(ErrorCode::NotApproved);
-    }
+    pool_state.check_swap_allowed(block_timestamp)?;
     require!(
         if zero_for_one {
             sqrt_price_limit_x64 < pool_state.sqrt_price_x64
@@ -169,12 +172,53 @@ pub fn swap_internal<'b, 'info>(
         fee_amount: 0,
         protocol_fee: 0,
         fund_fee: 0,
+        creator_fee: 0,
         liquidity: liquidity_start,
     };
 
     // check observation account is owned by the pool
     require_keys_eq!(observation_state.pool_id, pool_state.key());
 
+    // the trade fee rate is fixed for the whole swap, including the volatility surcharge if enabled
+    let trade_fee_rate = pool_state.get_trade_fee_rate(
+        amm_config,
+        observation_state,
+        extended_observations,
+        block_timestamp,
+    );
+
+    // the swap stops at the max tick deviation from the recent average tick
+    if let Some(sqrt_price_bound_x64) = pool_state.get_deviation_sqrt_price_bound(
+        observation_state,
+        extended_observations,
+        block_timestamp,
+        zero_for_one,
+    )? {
+        sqrt_price_limit_x64 = if zero_for_one {
+            require_gt!(
+                pool_state.sqrt_price_x64,
+                sqrt_price_bound_x64,
+                ErrorCode::PriceDeviationExceeded
+            );
+            sqrt_price_limit_x64.max(sqrt_price_bound_x64)
+        } else {
+            require_gt!(
+                sqrt_price_bound_x64,
+                pool_state.sqrt_price_x64,
+                ErrorCode::PriceDeviationExceeded
+            );
+            sqrt_price_limit_x64.min(sqrt_price_bound_x64)
+        };
+    }
+
+    // flipped into the crossed ticks, the in-range liquidity is unchanged since the last observation
+    let seconds_per_liquidity_cumulative_x64 = observation_state
+        .get_seconds_per_liquidity_cumulative_x64(
+            extended_observations,
+            block_timestamp,
+            pool_state.liquidity,
+        );
+
     let (mut is_match_pool_current_tick_array, first_vaild_tick_array_start_index) =
         pool_state.get_first_initialized_tick_array(&tickarray_bitmap_extension, zero_for_one)?;
     let mut current_vaild_tick_array_start_index = first_vaild_tick_array_start_index;
@@ -302,7 +346,7 @@ pub fn swap_internal<'b, 'info>(
             target_price,
             state.liquidity,
             state.amount_specified_remaining,
-            amm_config.trade_fee_rate,
+            trade_fee_rate,
             is_base_input,
             zero_for_one,
             block_timestamp,
@@ -367,6 +411,17 @@ pub fn swap_internal<'b, 'info>(
             step.fee_amount = step.fee_amount.checked_sub(delta).unwrap();
             state.fund_fee = state.fund_fee.checked_add(delta).unwrap();
         }
+        // if the creator fee is on, calculate how much is owed, decrement fee_amount, and increment creator_fee
+        if amm_config.creator_fee_rate > 0 {
+            let delta = U128::from(step_fee_amount)
+                .checked_mul(amm_config.creator_fee_rate.into())
+                .unwrap()
+                .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
+                .unwrap()
+                .as_u64();
+            step.fee_amount = step.fee_amount.checked_sub(delta).unwrap();
+            state.creator_fee = state.creator_fee.checked_add(delta).unwrap();
+        }
 
         // update global fee tracker
         if state.liquidity > 0 {
@@ -389,38 +444,77 @@ pub fn swap_internal<'b, 'info>(
         }
         // shift tick if we reached the next price
         if state.sqrt_price_x64 == step.sqrt_price_next_x64 {
+            let mut orders_pending = false;
             // if the tick is initialized, run the tick transition
             if step.initialized {
                 #[cfg(feature = "enable-log")]
                 msg!("loading next tick {}", step.tick_next);
 
-                let mut liquidity_net = next_initialized_tick.cross(
-                    if zero_for_one {
-                        state.fee_growth_global_x64
-                    } else {
-                        pool_state.fee_growth_global_0_x64
-                    },
+                // limit orders resting on the tick are filled at the tick price before it is crossed
+                let (order_amount_in, order_amount_out) = next_initialized_tick.fill_orders(
+                    state.amount_specified_remaining,
+                    zero_for_one,
+                    is_base_input,
+                )?;
+                if is_base_input {
+                    state.amount_specified_remaining = state
+                        .amount_specified_remaining
+                        .checked_sub(order_amount_in)
+                        .unwrap();
+                    state.amount_calculated = state
+                        .amount_calculated
+                        .checked_add(order_amount_out)
+                        .ok_or(ErrorCode::CalculateOverflow)?;
+                } else {
+                    state.amount_specified_remaining = state
+                        .amount_specified_remaining
+                        .checked_sub(order_amount_out)
+                        .unwrap();
+                    state.amount_calculated = state
+                        .amount_calculated
+                        .checked_add(order_amount_in)
+                        .ok_or(ErrorCode::CalculateOverflow)?;
+                }
+                orders_pending = next_initialized_tick.order_amount != 0;
+
+                // the tick is only crossed once all of its orders are filled, so price returning
+                // can never turn a filled order back
+                if !orders_pending {
+                    let mut liquidity_net = next_initialized_tick.cross(
+                        if zero_for_one {
+                            state.fee_growth_global_x64
+                        } else {
+                            pool_state.fee_growth_global_0_x64
+                        },
+                        if zero_for_one {
+                            pool_state.fee_growth_global_1_x64
+                        } else {
+                            state.fee_growth_global_x64
+                        },
+                        &updated_reward_infos,
+                        seconds_per_liquidity_cumulative_x64,
+                    );
                     if zero_for_one {
-                        pool_state.fee_growth_global_1_x64
-                    } else {
-                        state.fee_growth_global_x64
-                    },
-                    &updated_reward_infos,
-                );
+                        liquidity_net = liquidity_net.neg();
+                    }
+                    state.liquidity = liquidity_math::add_delta(state.liquidity, liquidity_net)?;
+                }
                 // update tick_state to tick_array account
                 tick_array_current.update_tick_state(
                     next_initialized_tick.tick,
                     pool_state.tick_spacing.into(),
                     *next_initialized_tick,
                 )?;
+            }
 
+            state.tick = if orders_pending {
+                // the price rests on the tick until its orders are filled, the tick stays uncrossed
                 if zero_for_one {
-                    liquidity_net = liquidity_net.neg();
+                    step.tick_next
+                } else {
+                    step.tick_next - 1
                 }
-                state.liquidity = liquidity_math::add_delta(state.liquidity, liquidity_net)?;
-            }
-
-            state.tick = if zero_for_one {
+            } else if zero_for_one {
                 step.tick_next - 1
             } else {
                 step.tick_next
@@ -462,8 +556,13 @@ pub fn swap_internal<'b, 'info>(
     }
     // update tick
     if state.tick != pool_state.tick_current {
-        // update the previous tick to the observation
-        observation_state.update(block_timestamp, pool_state.tick_current);
+        // update the previous tick and liquidity to the observation
+        observation_state.update(
+            extended_observations,
+            block_timestamp,
+            pool_state.tick_current,
+            pool_state.liquidity,
+        );
         pool_state.tick_current = state.tick;
     }
     pool_state.sqrt_price_x64 = state.sqrt_price_x64;
@@ -507,6 +606,12 @@ pub fn swap_internal<'b, 'info>(
                 .checked_add(state.fund_fee)
                 .unwrap();
         }
+        if state.creator_fee > 0 {
+            pool_state.creator_fees_token_0 = pool_state
+                .creator_fees_token_0
+                .checked_add(state.creator_fee)
+                .unwrap();
+        }
         pool_state.swap_in_amount_token_0 = pool_state
             .swap_in_amount_token_0
             .checked_add(u128::from(amount_0))
@@ -534,6 +639,12 @@ pub fn swap_internal<'b, 'info>(
                 .checked_add(state.fund_fee)
                 .unwrap();
         }
+        if state.creator_fee > 0 {
+            pool_state.creator_fees_token_1 = pool_state
+                .creator_fees_token_1
+                .checked_add(state.creator_fee)
+                .unwrap();
+        }
         pool_state.swap_in_amount_token_1 = pool_state
             .swap_in_amount_token_1
             .checked_add(u128::from(amount_1))
@@ -544,7 +655,121 @@ pub fn swap_internal<'b, 'info>(
             .unwrap();
     }
 
-    Ok((amount_0, amount_1))
+    Ok((amount_0, amount_1, trade_fee_rate))
+}
+
+/// Runs `swap_internal` against copies of the pool, observation and tick array accounts, so the
+/// amounts of a swap are known without writing to any account. The amounts returned are the
+/// ones the vaults would take and pay, without transfer fees.
+/// `tick_array_infos` may contain the tick array bitmap extension, the reward extension and the
+/// reward schedule accounts regardless the sequence, and end with the swap hook accounts.
+pub fn simulate_swap<'info>(
+    amm_config: &AmmConfig,
+    pool_state_info: &AccountInfo<'info>,
+    observation_state_info: &AccountInfo<'info>,
+    tick_array_infos: &[AccountInfo<'info>],
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+    block_timestamp: u32,
+) -> Result<QuoteSwapResult> {
+    // a simulation does not call the swap hook, its accounts are skipped
+    let swap_hook_program = AccountLoad::<PoolState>::try_from(pool_state_info)?
+        .load()?
+        .swap_hook_program;
+    let tick_array_infos = match tick_array_infos
+        .iter()
+        .position(|account_info| account_info.key() == swap_hook_program)
+    {
+        Some(hook_index) if swap_hook_program != Pubkey::default() => {
+            &tick_array_infos[..hook_index]
+        }
+        _ => tick_array_infos,
+    };
+    let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state_info.key());
+    let mut tickarray_bitmap_extension = None;
+    let mut copied_accounts = Vec::with_capacity(tick_array_infos.len() + 2);
+    for account_info in [pool_state_info, observation_state_info]
+        .into_iter()
+        .chain(tick_array_infos.iter())
+    {
+        if account_info.key().eq(&tick_array_bitmap_extension_key) {
+            tickarray_bitmap_extension = Some(
+                *(AccountLoad::<TickArrayBitmapExtension>::try_from(account_info)?
+                    .load()?
+                    .deref()),
+            );
+            continue;
+        }
+        if RewardExtensionAccounts::is_extension_account(account_info) {
+            continue;
+        }
+        copied_accounts.push((
+            account_info.key,
+            account_info.owner,
+            account_info.lamports(),
+            account_info.try_borrow_data()?.to_vec(),
+        ));
+    }
+    let copied_infos = copied_accounts
+        .iter_mut()
+        .map(|(key, owner, lamports, data)| {
+            AccountInfo::new(key, false, true, lamports, data, owner, false, 0)
+        })
+        .collect::<Vec<_>>();
+
+    let mut pool_state = AccountLoad::<PoolState>::load_data_mut(&copied_infos[0])?;
+    let (mut observation_state, mut extended_observations) =
+        ObservationState::load_extended_mut(&copied_infos[1])?;
+    let mut tick_array_states = copied_infos[2..]
+        .iter()
+        .map(AccountLoad::<TickArrayState>::load_data_mut)
+        .collect::<Result<VecDeque<_>>>()?;
+    let tick_array_start_indexes = tick_array_states
+        .iter()
+        .map(|tick_array_state| tick_array_state.start_tick_index)
+        .collect::<Vec<_>>();
+    let tick_array_start_index_before =
+        TickArrayState::get_array_start_index(pool_state.tick_current, pool_state.tick_spacing);
+    let fees_before = pool_state.get_accrued_fees(zero_for_one);
+
+    let (amount_0, amount_1, _) = swap_internal(
+        amm_config,
+        &mut pool_state,
+        &mut tick_array_states,
+        &mut observation_state,
+        &mut extended_observations,
+        &tickarray_bitmap_extension,
+        amount_specified,
+        sqrt_price_limit_x64,
+        zero_for_one,
+        is_base_input,
+        block_timestamp,
+    )?;
+
+    let tick_array_start_index_after =
+        TickArrayState::get_array_start_index(pool_state.tick_current, pool_state.tick_spacing);
+    let (amount_in, amount_out) = if zero_for_one {
+        (amount_0, amount_1)
+    } else {
+        (amount_1, amount_0)
+    };
+    Ok(QuoteSwapResult {
+        amount_in,
+        amount_out,
+        fee_amount: pool_state.get_accrued_fees(zero_for_one) - fees_before,
+        sqrt_price_x64: pool_state.sqrt_price_x64,
+        tick: pool_state.tick_current,
+        tick_array_start_indexes: tick_array_start_indexes
+            .into_iter()
+            .filter(|start_index| {
+                (*start_index >= tick_array_start_index_before.min(tick_array_start_index_after))
+                    && (*start_index
+                        <= tick_array_start_index_before.max(tick_array_start_index_after))
+            })
+            .collect(),
+    })
 }
 
 /// Performs a single exact input/output swap
@@ -558,10 +783,34 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
 ) -> Result<u64> {
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
 
+    // the swap hook accounts follow the tick arrays
+    let (remaining_accounts, swap_hook_accounts) =
+        split_swap_hook_accounts(&*ctx.pool_state.load()?, remaining_accounts)?;
+    let mut swap_hook_params = SwapHookParams::default();
+    if let Some(swap_hook_accounts) = swap_hook_accounts {
+        {
+            let pool_state = ctx.pool_state.load()?;
+            swap_hook_params = SwapHookParams::new(
+                &pool_state,
+                ctx.input_vault.mint == pool_state.token_mint_0,
+                is_base_input,
+                amount_specified,
+            );
+        }
+        invoke_swap_hook(
+            swap_hook_accounts,
+            &ctx.signer.to_account_info(),
+            &ctx.pool_state.to_account_info(),
+            BEFORE_SWAP_HOOK,
+            &swap_hook_params,
+        )?;
+    }
+
     let amount_0;
     let amount_1;
     let zero_for_one;
     let swap_price_before;
+    let trade_fee_rate;
 
     let input_balance_before = ctx.input_vault.amount;
     let output_balance_before = ctx.output_vault.amount;
@@ -571,8 +820,6 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
         let pool_state = &mut ctx.pool_state.load_mut()?;
         zero_for_one = ctx.input_vault.mint == pool_state.token_mint_0;
 
-        require_gt!(block_timestamp, pool_state.open_time);
-
         require!(
             if zero_for_one {
                 ctx.input_vault.key() == pool_state.token_vault_0
@@ -584,6 +831,21 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             ErrorCode::InvalidInputPoolVault
         );
 
+        update_scheduled_reward_infos(
+            pool_state,
+            ctx.pool_state.key(),
+            remaining_accounts,
+            block_timestamp,
+        )?;
+        let reward_extension = RewardExtensionAccounts::load(
+            pool_state,
+            ctx.pool_state.key(),
+            None,
+            remaining_accounts,
+            block_timestamp,
+        )?;
+        let tick_before = pool_state.tick_current;
+
         let mut tickarray_bitmap_extension = None;
         let tick_array_states = &mut VecDeque::new();
         tick_array_states.push_back(ctx.tick_array_state.load_mut()?);
@@ -598,14 +860,20 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
                 );
                 continue;
             }
+            if RewardExtensionAccounts::is_extension_account(account_info) {
+                continue;
+            }
             tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
         }
 
-        (amount_0, amount_1) = swap_internal(
+        let (mut observation_state, mut extended_observations) =
+            ObservationState::load_extended_mut(ctx.observation_state.as_ref())?;
+        (amount_0, amount_1, trade_fee_rate) = swap_internal(
             &ctx.amm_config,
             pool_state,
             tick_array_states,
-            &mut ctx.observation_state.load_mut()?,
+            &mut observation_state,
+            &mut extended_observations,
             &tickarray_bitmap_extension,
             amount_specified,
             if sqrt_price_limit_x64 == 0 {
@@ -622,6 +890,19 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             oracle::block_timestamp(),
         )?;
 
+        if let Some(reward_extension) = reward_extension {
+            // release the tick arrays for the extension to read
+            tick_array_states.clear();
+            let mut tick_array_infos = vec![ctx.tick_array_state.to_account_info()];
+            tick_array_infos.extend_from_slice(remaining_accounts);
+            reward_extension.cross_ticks(
+                ctx.pool_state.key(),
+                &tick_array_infos,
+                tick_before,
+                pool_state.tick_current,
+            )?;
+        }
+
         #[cfg(feature = "enable-log")]
         msg!(
             "exact_swap_internal, is_base_input:{}, amount_0: {}, amount_1: {}",
@@ -702,6 +983,17 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
     ctx.output_vault.reload()?;
     ctx.input_vault.reload()?;
 
+    if let Some(swap_hook_accounts) = swap_hook_accounts {
+        swap_hook_params.set_after_swap(&*ctx.pool_state.load()?, amount_0, amount_1);
+        invoke_swap_hook(
+            swap_hook_accounts,
+            &ctx.signer.to_account_info(),
+            &ctx.pool_state.to_account_info(),
+            AFTER_SWAP_HOOK,
+            &swap_hook_params,
+        )?;
+    }
+
     let pool_state = ctx.pool_state.load()?;
     emit!(SwapEvent {
         pool_state: pool_state.key(),
@@ -715,12 +1007,18 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
         zero_for_one,
         sqrt_price_x64: pool_state.sqrt_price_x64,
         liquidity: pool_state.liquidity,
-        tick: pool_state.tick_current
+        tick: pool_state.tick_current,
+        trade_fee_rate,
     });
-    if zero_for_one {
-        require_gt!(swap_price_before, pool_state.sqrt_price_x64);
-    } else {
-        require_gt!(pool_state.sqrt_price_x64, swap_price_before);
+    // filling limit orders resting on the current tick is the only way a swap pays out without
+    // moving the price, any other swap must move it
+    let amount_out = if zero_for_one { amount_1 } else { amount_0 };
+    if amount_out == 0 || pool_state.sqrt_price_x64 != swap_price_before {
+        if zero_for_one {
+            require_gt!(swap_price_before, pool_state.sqrt_price_x64);
+        } else {
+            require_gt!(pool_state.sqrt_price_x64, swap_price_before);
+        }
     }
     if sqrt_price_limit_x64 == 0 {
         // Does't allow partial filled without specified limit_price.
@@ -819,6 +1117,34 @@ mod swap_test {
         RefCell::new(tick_array_states)
     }
 
+    /// Swaps in a pool whose observation account has no extended observations
+    fn swap_internal_without_extended_observations(
+        amm_config: &AmmConfig,
+        pool_state: &mut RefMut<PoolState>,
+        tick_array_states: &mut VecDeque<RefMut<TickArrayState>>,
+        observation_state: &mut RefMut<ObservationState>,
+        tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
+        amount_specified: u64,
+        sqrt_price_limit_x64: u128,
+        zero_for_one: bool,
+        is_base_input: bool,
+        block_timestamp: u32,
+    ) -> Result<(u64, u64, u32)> {
+        swap_internal(
+            amm_config,
+            pool_state,
+            tick_array_states,
+            observation_state,
+            &mut [],
+            tickarray_bitmap_extension,
+            amount_specified,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            is_base_input,
+            block_timestamp,
+        )
+    }
+
     fn build_swap_param<'info>(
         tick_current: i32,
         tick_spacing: u16,
@@ -957,6 +1283,7 @@ mod swap_test {
                             0,
                             false,
                             &[RewardInfo::default(); 3],
+                            0,
                         )
                         .unwrap();
 
@@ -978,6 +1305,7 @@ mod swap_test {
                             0,
                             false,
                             &[RewardInfo::default(); 3],
+                            0,
                         )
                         .unwrap();
                 }
@@ -1005,6 +1333,7 @@ mod swap_test {
                             0,
                             true,
                             &[RewardInfo::default(); 3],
+                            0,
                         )
                         .unwrap();
 
@@ -1027,6 +1356,7 @@ mod swap_test {
                             0,
                             true,
                             &[RewardInfo::default(); 3],
+                            0,
                         )
                         .unwrap();
                 }
@@ -1115,7 +1445,7 @@ mod swap_test {
                 );
 
             // just cross the tickarray boundary(-32400), hasn't reached the next tick array initialized tick
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1146,7 +1476,7 @@ mod swap_test {
             // so we pop the tickarray with start_index -32400
             // in this swap we will cross the tick(-32460), but not reach next tick (-32520)
             tick_array_states.pop_front();
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1174,7 +1504,7 @@ mod swap_test {
             liquidity = pool_state.borrow().liquidity;
 
             // swap in tickarray with start_index -36000, cross the tick -32520
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1231,7 +1561,7 @@ mod swap_test {
                 );
 
             // just cross the tickarray boundary(-32400), hasn't reached the next tick array initialized tick
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1262,7 +1592,7 @@ mod swap_test {
             // so we pop the tickarray with start_index -32400
             // in this swap we will cross the tick(-32460), but not reach next tick (-32520)
             tick_array_states.pop_front();
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1290,7 +1620,7 @@ mod swap_test {
             liquidity = pool_state.borrow().liquidity;
 
             // swap in tickarray with start_index -36000
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1347,7 +1677,7 @@ mod swap_test {
                 );
 
             // just cross the tickarray boundary(-32460), hasn't reached the next tick array initialized tick
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1377,7 +1707,7 @@ mod swap_test {
             // cross the tickarray boundary(-32460) in last step, but not reached tick -32400, because -32400 is the next tickarray boundary,
             // so the tickarray_current still is the tick array with start_index -36000
             // in this swap we will cross the tick(-32400), but not reach next tick (-29220)
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1406,7 +1736,7 @@ mod swap_test {
 
             // swap in tickarray with start_index -32400, cross the tick -29220
             tick_array_states.pop_front();
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1463,7 +1793,7 @@ mod swap_test {
                 );
 
             // just cross the tickarray boundary(-32460), hasn't reached the next tick array initialized tick
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1493,7 +1823,7 @@ mod swap_test {
             // cross the tickarray boundary(-32460) in last step, but not reached tick -32400, because -32400 is the next tickarray boundary,
             // so the tickarray_current still is the tick array with start_index -36000
             // in this swap we will cross the tick(-32400), but not reach next tick (-29220)
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1522,7 +1852,7 @@ mod swap_test {
 
             // swap in tickarray with start_index -32400, cross the tick -29220
             tick_array_states.pop_front();
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1572,7 +1902,7 @@ mod swap_test {
             );
 
             // find the first initialzied tick(-28860) and cross it in tickarray
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1617,7 +1947,7 @@ mod swap_test {
             );
 
             // find the first initialzied tick(-32400) and cross it in tickarray
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1662,7 +1992,7 @@ mod swap_test {
                 }],
             );
 
-            let result = swap_internal(
+            let result = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1703,7 +2033,7 @@ mod swap_test {
         );
 
         // not cross tick(-28860), but pool.tick_current = -28860
-        let (amount_0, amount_1) = swap_internal(
+        let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
             &amm_config,
             &mut pool_state.borrow_mut(),
             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1726,7 +2056,7 @@ mod swap_test {
         assert!(amount_0 == 25);
 
         // just cross tick(-28860), pool.tick_current = -28861
-        let (amount_0, amount_1) = swap_internal(
+        let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
             &amm_config,
             &mut pool_state.borrow_mut(),
             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1752,7 +2082,7 @@ mod swap_test {
 
         // we swap just a little amount, let pool tick_current also equal -28861
         // but pool.sqrt_price_x64 > tick_math::get_sqrt_price_at_tick(-28861)
-        let (amount_0, amount_1) = swap_internal(
+        let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
             &amm_config,
             &mut pool_state.borrow_mut(),
             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1805,7 +2135,7 @@ mod swap_test {
             );
 
             // zero for one, just cross tick(-28860),  pool.tick_current = -28861 and pool.sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(-28860)
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1832,7 +2162,7 @@ mod swap_test {
             sqrt_price_x64 = pool_state.borrow().sqrt_price_x64;
 
             // we swap just a little amount, it is completely taken by fees, the sqrt price and the tick will remain the same
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1857,7 +2187,7 @@ mod swap_test {
             // Actually, the loop for this swap was executed twice because the previous swap happened to have `pool.tick_current` exactly on the boundary that is divisible by `tick_spacing`.
             // In the first iteration of this swap's loop, it found the initial tick (-28860), but at this point, both the initial and final prices were equal to the price at tick -28860.
             // This did not meet the conditions for swapping so both swap_amount_input and swap_amount_output were 0. The actual output was calculated in the second iteration of the loop.
-            let (amount_0, amount_1) = swap_internal(
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1880,6 +2210,424 @@ mod swap_test {
         }
     }
 
+    mod limit_order_fill_test {
+        use super::*;
+        use std::convert::identity;
+
+        #[test]
+        fn one_for_zero_fill_limit_order_test() {
+            let liquidity = 1_000_000_000;
+            let mut order_tick = build_tick(100, 0, 0).take();
+            order_tick.order_amount = 1000;
+            order_tick.order_count = 1;
+            // the price rests on tick 100 with the orders unfilled
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                99,
+                10,
+                tick_math::get_sqrt_price_at_tick(100).unwrap(),
+                liquidity,
+                vec![TickArrayInfo {
+                    start_tick_index: 0,
+                    ticks: vec![
+                        build_tick(10, liquidity, liquidity as i128).take(),
+                        order_tick,
+                        build_tick(500, liquidity, -(liquidity as i128)).take(),
+                    ],
+                }],
+            );
+
+            // the swap is absorbed by the orders, price and tick do not move
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                200,
+                tick_math::get_sqrt_price_at_tick(200).unwrap(),
+                false,
+                true,
+                oracle::block_timestamp_mock() as u32,
+            )
+            .unwrap();
+            assert_eq!(amount_1, 200);
+            assert_eq!(amount_0, 198);
+            assert!(pool_state.borrow().tick_current == 99);
+            assert!(
+                pool_state.borrow().sqrt_price_x64
+                    == tick_math::get_sqrt_price_at_tick(100).unwrap()
+            );
+            let tick_state = *tick_array_states[0]
+                .borrow_mut()
+                .get_tick_state_mut(100, 10)
+                .unwrap();
+            assert_eq!(identity(tick_state.order_amount), 802);
+            assert_eq!(identity(tick_state.order_phase), 0);
+
+            // the rest of the orders are filled before the tick is crossed
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                10_000_000,
+                tick_math::get_sqrt_price_at_tick(200).unwrap(),
+                false,
+                true,
+                oracle::block_timestamp_mock() as u32,
+            )
+            .unwrap();
+            assert!(amount_0 > 802);
+            assert!(amount_1 < 10_000_000);
+            assert!(pool_state.borrow().tick_current == 200);
+            assert!(pool_state.borrow().liquidity == liquidity);
+            let tick_state = *tick_array_states[0]
+                .borrow_mut()
+                .get_tick_state_mut(100, 10)
+                .unwrap();
+            assert_eq!(identity(tick_state.order_amount), 0);
+            assert_eq!(identity(tick_state.order_phase), 1);
+
+            // price returning below the tick leaves the filled orders untouched
+            swap_internal_without_extended_observations(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                10_000_000,
+                tick_math::get_sqrt_price_at_tick(50).unwrap(),
+                true,
+                true,
+                oracle::block_timestamp_mock() as u32,
+            )
+            .unwrap();
+            assert!(pool_state.borrow().tick_current == 50);
+            let tick_state = *tick_array_states[0]
+                .borrow_mut()
+                .get_tick_state_mut(100, 10)
+                .unwrap();
+            assert_eq!(identity(tick_state.order_amount), 0);
+            assert_eq!(identity(tick_state.order_phase), 1);
+        }
+    }
+
+    #[cfg(test)]
+    mod simulate_swap_test {
+        use super::*;
+
+        fn build_account_data<T: anchor_lang::ZeroCopy>(state: &T, len: usize) -> Vec<u8> {
+            let mut data = T::DISCRIMINATOR.to_vec();
+            data.extend_from_slice(bytemuck::bytes_of(state));
+            data.resize(len.max(data.len()), 0);
+            data
+        }
+
+        #[test]
+        fn simulate_swap_matches_swap_without_writing_test() {
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                -32395,
+                60,
+                3651942632306380802,
+                5124165121219,
+                vec![
+                    TickArrayInfo {
+                        start_tick_index: -32400,
+                        ticks: vec![
+                            build_tick(-32400, 277065331032, -277065331032).take(),
+                            build_tick(-29220, 1330680689, -1330680689).take(),
+                        ],
+                    },
+                    TickArrayInfo {
+                        start_tick_index: -36000,
+                        ticks: vec![
+                            build_tick(-32460, 1194569667438, 536061033698).take(),
+                            build_tick(-32520, 790917615645, 790917615645).take(),
+                        ],
+                    },
+                ],
+            );
+            let owner = crate::id();
+            let mut accounts = vec![
+                (
+                    pool_state.borrow().key(),
+                    build_account_data(&*pool_state.borrow(), PoolState::LEN),
+                ),
+                (
+                    Pubkey::new_unique(),
+                    build_account_data(&*observation_state.borrow(), ObservationState::LEN),
+                ),
+            ];
+            for tick_array_state in tick_array_states.iter() {
+                accounts.push((
+                    Pubkey::new_unique(),
+                    build_account_data(&*tick_array_state.borrow(), TickArrayState::LEN),
+                ));
+            }
+            let accounts_before = accounts.clone();
+            let mut lamports = vec![0; accounts.len()];
+            let account_infos = accounts
+                .iter_mut()
+                .zip(lamports.iter_mut())
+                .map(|((key, data), lamports)| {
+                    AccountInfo::new(key, false, true, lamports, data, &owner, false, 0)
+                })
+                .collect::<Vec<_>>();
+
+            let block_timestamp = oracle::block_timestamp_mock() as u32;
+            let simulated = simulate_swap(
+                &amm_config,
+                &account_infos[0],
+                &account_infos[1],
+                &account_infos[2..],
+                12188240002,
+                tick_math::MIN_SQRT_PRICE_X64 + 1,
+                true,
+                true,
+                block_timestamp,
+            )
+            .unwrap();
+            drop(account_infos);
+            assert_eq!(accounts, accounts_before);
+
+            let fees_before = pool_state.borrow().get_accrued_fees(true);
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                12188240002,
+                tick_math::MIN_SQRT_PRICE_X64 + 1,
+                true,
+                true,
+                block_timestamp,
+            )
+            .unwrap();
+            let pool_state = pool_state.borrow();
+            assert_eq!(simulated.amount_in, amount_0);
+            assert_eq!(simulated.amount_out, amount_1);
+            assert_eq!(
+                simulated.fee_amount,
+                pool_state.get_accrued_fees(true) - fees_before
+            );
+            assert!(simulated.fee_amount > 0);
+            assert_eq!(simulated.sqrt_price_x64, { pool_state.sqrt_price_x64 });
+            assert_eq!(simulated.tick, { pool_state.tick_current });
+            // the swap crosses from the first tick array into the second
+            assert!({ pool_state.tick_current } < -32400);
+            assert_eq!(simulated.tick_array_start_indexes, vec![-32400, -36000]);
+        }
+    }
+
+    #[cfg(test)]
+    mod tick_deviation_guard_test {
+        use super::*;
+
+        #[test]
+        fn swap_stops_at_max_tick_deviation_test() {
+            let tick_current = -32395;
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                tick_current,
+                60,
+                3651942632306380802,
+                5124165121219,
+                vec![
+                    TickArrayInfo {
+                        start_tick_index: -32400,
+                        ticks: vec![
+                            build_tick(-32400, 277065331032, -277065331032).take(),
+                            build_tick(-29220, 1330680689, -1330680689).take(),
+                        ],
+                    },
+                    TickArrayInfo {
+                        start_tick_index: -36000,
+                        ticks: vec![
+                            build_tick(-32460, 1194569667438, 536061033698).take(),
+                            build_tick(-32520, 790917615645, 790917615645).take(),
+                        ],
+                    },
+                ],
+            );
+            let block_timestamp = oracle::block_timestamp_mock() as u32;
+            // the price has been at the current tick for the last minute
+            for seconds_ago in [60, 30] {
+                observation_state.borrow_mut().update(
+                    &mut [],
+                    block_timestamp - seconds_ago,
+                    tick_current,
+                    pool_state.borrow().liquidity,
+                );
+            }
+            pool_state.borrow_mut().max_tick_deviation = 5;
+            pool_state.borrow_mut().tick_deviation_period = 60;
+
+            let (amount_0, amount_1, _) = swap_internal_without_extended_observations(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                12188240002,
+                tick_math::MIN_SQRT_PRICE_X64 + 1,
+                true,
+                true,
+                block_timestamp,
+            )
+            .unwrap();
+            // capped, only part of the input is swapped
+            assert!(amount_0 < 12188240002);
+            assert!(amount_1 > 0);
+            assert_eq!(
+                { pool_state.borrow().sqrt_price_x64 },
+                tick_math::get_sqrt_price_at_tick(tick_current - 5).unwrap()
+            );
+
+            // the price can not be pushed further until the average catches up
+            let result = swap_internal_without_extended_observations(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                1000,
+                tick_math::MIN_SQRT_PRICE_X64 + 1,
+                true,
+                true,
+                block_timestamp,
+            );
+            assert_eq!(
+                result.unwrap_err(),
+                ErrorCode::PriceDeviationExceeded.into()
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod pool_open_time_test {
+        use super::*;
+
+        #[test]
+        fn swap_before_open_time_test() {
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                -32395,
+                60,
+                3651942632306380802,
+                5124165121219,
+                vec![TickArrayInfo {
+                    start_tick_index: -36000,
+                    ticks: vec![
+                        build_tick(-32460, 1194569667438, 536061033698).take(),
+                        build_tick(-32520, 790917615645, 790917615645).take(),
+                    ],
+                }],
+            );
+            let block_timestamp = oracle::block_timestamp_mock() as u32;
+            pool_state.borrow_mut().open_time = u64::from(block_timestamp);
+
+            let result = swap_internal_without_extended_observations(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                1000,
+                tick_math::MIN_SQRT_PRICE_X64 + 1,
+                true,
+                true,
+                block_timestamp,
+            );
+            assert_eq!(result.unwrap_err(), ErrorCode::PoolNotOpen.into());
+
+            // open once the time has passed
+            swap_internal_without_extended_observations(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                1000,
+                tick_math::MIN_SQRT_PRICE_X64 + 1,
+                true,
+                true,
+                block_timestamp + 1,
+            )
+            .unwrap();
+        }
+    }
+
+    mod trade_fee_rate_test {
+        use super::*;
+
+        #[test]
+        fn swap_returns_dynamic_trade_fee_rate_test() {
+            let tick_current = -32395;
+            let (mut amm_config, pool_state, tick_array_states, observation_state) =
+                build_swap_param(
+                    tick_current,
+                    60,
+                    3651942632306380802,
+                    5124165121219,
+                    vec![TickArrayInfo {
+                        start_tick_index: -36000,
+                        ticks: vec![
+                            build_tick(-32460, 1194569667438, 536061033698).take(),
+                            build_tick(-32520, 790917615645, 790917615645).take(),
+                        ],
+                    }],
+                );
+            let block_timestamp = oracle::block_timestamp_mock() as u32;
+
+            let (_, _, trade_fee_rate) = swap_internal_without_extended_observations(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                1000,
+                tick_math::MIN_SQRT_PRICE_X64 + 1,
+                true,
+                true,
+                block_timestamp,
+            )
+            .unwrap();
+            assert_eq!(trade_fee_rate, amm_config.trade_fee_rate);
+
+            // the tick was 100 ticks lower over the last half minute
+            for seconds in [0, 30] {
+                observation_state.borrow_mut().update(
+                    &mut [],
+                    block_timestamp + seconds,
+                    tick_current - 100,
+                    pool_state.borrow().liquidity,
+                );
+            }
+            amm_config.dynamic_fee_max_rate = 10000;
+            amm_config.dynamic_fee_control = 10;
+            amm_config.dynamic_fee_decay_period = 60;
+            let tick_deviation = (pool_state.borrow().tick_current - (tick_current - 100)) as u32;
+
+            let (_, _, trade_fee_rate) = swap_internal_without_extended_observations(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                1000,
+                tick_math::MIN_SQRT_PRICE_X64 + 1,
+                true,
+                true,
+                block_timestamp + 31,
+            )
+            .unwrap();
+            assert_eq!(
+                trade_fee_rate,
+                amm_config.trade_fee_rate + tick_deviation * 10
+            );
+        }
+    }
+
     #[cfg(test)]
     mod sqrt_price_limit_optimization_min_specified_test {
         use super::*;
@@ -1918,7 +2666,7 @@ mod swap_test {
                 sum_amount_0, sum_amount_1,
             );
             let amount_specified = 1;
-            let result = swap_internal(
+            let result = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1972,7 +2720,7 @@ mod swap_test {
                 sum_amount_0, sum_amount_1,
             );
             let amount_specified = 1;
-            let result = swap_internal(
+            let result = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2026,7 +2774,7 @@ mod swap_test {
                 sum_amount_0, sum_amount_1,
             );
             let amount_specified = 1;
-            let result = swap_internal(
+            let result = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2079,7 +2827,7 @@ mod swap_test {
                 sum_amount_0, sum_amount_1,
             );
             let amount_specified = 1;
-            let result = swap_internal(
+            let result = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2136,7 +2884,7 @@ mod swap_test {
                 sum_amount_0, sum_amount_1,
             );
             let amount_specified = u64::MAX / 2;
-            let result = swap_internal(
+            let result = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2190,7 +2938,7 @@ mod swap_test {
                 sum_amount_0, sum_amount_1,
             );
             let amount_specified = u64::MAX / 4;
-            let result = swap_internal(
+            let result = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2244,7 +2992,7 @@ mod swap_test {
                 sum_amount_0, sum_amount_1,
             );
             let amount_specified = u64::MAX / 2;
-            let result = swap_internal(
+            let result = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2297,7 +3045,7 @@ mod swap_test {
                 sum_amount_0, sum_amount_1,
             );
             let amount_specified = u64::MAX / 4;
-            let result = swap_internal(
+            let result = swap_internal_without_extended_observations(
                 &amm_config,
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2350,7 +3098,7 @@ mod swap_test {
                     let mut rng = rand::thread_rng();
                     let amount_specified  = rng.gen_range(1..u64::MAX - sum_amount_0);
 
-                    let result = swap_internal(
+                    let result = swap_internal_without_extended_observations(
                         &amm_config,
                         &mut pool_state.borrow_mut(),
                         &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2364,7 +3112,7 @@ mod swap_test {
                     );
 
                     if result.is_ok() {
-                        let ( amount_0_before, amount_1_before) = result.unwrap();
+                        let (amount_0_before, amount_1_before, _) = result.unwrap();
 
                         let (amm_config, pool_state, tick_array_states, observation_state,bitmap_extension_state,  _sum_amount_0, _sum_amount_1) = setup_swap_test(
                             tick_current,
@@ -2372,7 +3120,7 @@ mod swap_test {
                             vec![OpenPositionParam{amount_0:amount_0,amount_1:amount_1, tick_lower:tick_lower, tick_upper:tick_upper}],
                             zero_for_one
                         );
-                        let result = swap_internal(
+                        let result = swap_internal_without_extended_observations(
                             &amm_config,
                             &mut pool_state.borrow_mut(),
                             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2388,7 +3136,7 @@ mod swap_test {
 
                         // println!("----- input: tick_current:{}, amount_0:{}, amount_1:{}, amount_specified:{},tick_lower:{}, tick_upper:{},liquidity:{}", tick_current, amount_0, amount_1,amount_specified, tick_lower, tick_upper, identity(pool_state.borrow().liquidity));
 
-                        let ( amount_0_after, amount_1_after) = result.unwrap();
+                        let (amount_0_after, amount_1_after, _) = result.unwrap();
                         assert_eq!(amount_0_before, amount_0_after);
                         assert_eq!(amount_1_before, amount_1_after);
 
@@ -2396,7 +3144,7 @@ mod swap_test {
                         let err =  result.err().unwrap();
                         if err == crate::error::ErrorCode::MaxTokenOverflow.into(){
                             println!("##### original swap is overflow ");
-                            let result = swap_internal(
+                            let result = swap_internal_without_extended_observations(
                                 &amm_config,
                                 &mut pool_state.borrow_mut(),
                                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2441,7 +3189,7 @@ mod swap_test {
                     let mut rng = rand::thread_rng();
                     let amount_specified  = rng.gen_range(1..sum_amount_1);
                     // println!("----- input: tick_current:{}, amount_0:{}, amount_1:{}, amount_specified:{},tick_lower:{}, tick_upper:{}", tick_current, amount_0, amount_1,amount_specified, tick_lower, tick_upper);
-                    let result = swap_internal(
+                    let result = swap_internal_without_extended_observations(
                         &amm_config,
                         &mut pool_state.borrow_mut(),
                         &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2455,7 +3203,7 @@ mod swap_test {
                     );
 
                     if result.is_ok() {
-                        let ( amount_0_before, amount_1_before) = result.unwrap();
+                        let (amount_0_before, amount_1_before, _) = result.unwrap();
 
                         let (amm_config, pool_state, tick_array_states, observation_state,bitmap_extension_state, _sum_amount_0, _sum_amount_1) = setup_swap_test(
                             tick_current,
@@ -2463,7 +3211,7 @@ mod swap_test {
                             vec![OpenPositionParam{amount_0:amount_0,amount_1:amount_1, tick_lower:tick_lower, tick_upper:tick_upper}],
                             zero_for_one
                         );
-                        let result = swap_internal(
+                        let result = swap_internal_without_extended_observations(
                             &amm_config,
                             &mut pool_state.borrow_mut(),
                             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2479,7 +3227,7 @@ mod swap_test {
 
                         println!("----- input: tick_current:{}, amount_0:{}, amount_1:{}, amount_specified:{},tick_lower:{}, tick_upper:{},liquidity:{}", tick_current, amount_0, amount_1,amount_specified, tick_lower, tick_upper, identity(pool_state.borrow().liquidity));
 
-                        let ( amount_0_after, amount_1_after) = result.unwrap();
+                        let (amount_0_after, amount_1_after, _) = result.unwrap();
                         assert_eq!(amount_0_before, amount_0_after);
                         assert_eq!(amount_1_before, amount_1_after);
 
@@ -2487,7 +3235,7 @@ mod swap_test {
                         let err =  result.err().unwrap();
                         if err == crate::error::ErrorCode::MaxTokenOverflow.into(){
                             println!("##### original swap is overflow");
-                            let result = swap_internal(
+                            let result = swap_internal_without_extended_observations(
                                 &amm_config,
                                 &mut pool_state.borrow_mut(),
                                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2533,7 +3281,7 @@ mod swap_test {
                     let mut rng = rand::thread_rng();
                     let amount_specified  = rng.gen_range(1..u64::MAX - sum_amount_1);
 
-                    let result = swap_internal(
+                    let result = swap_internal_without_extended_observations(
                         &amm_config,
                         &mut pool_state.borrow_mut(),
                         &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2548,7 +3296,7 @@ mod swap_test {
 
 
                     if result.is_ok() {
-                        let ( amount_0_before, amount_1_before) = result.unwrap();
+                        let (amount_0_before, amount_1_before, _) = result.unwrap();
 
                         let (amm_config, pool_state, tick_array_states, observation_state,bitmap_extension_state,  _sum_amount_0, _sum_amount_1) = setup_swap_test(
                             tick_current,
@@ -2556,7 +3304,7 @@ mod swap_test {
                             vec![OpenPositionParam{amount_0:amount_0,amount_1:amount_1, tick_lower:tick_lower, tick_upper:tick_upper}],
                             zero_for_one
                         );
-                        let result = swap_internal(
+                        let result = swap_internal_without_extended_observations(
                             &amm_config,
                             &mut pool_state.borrow_mut(),
                             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2572,7 +3320,7 @@ mod swap_test {
 
                         // println!("----- input: tick_current:{}, amount_0:{}, amount_1:{}, amount_specified:{},tick_lower:{}, tick_upper:{},liquidity:{}", tick_current, amount_0, amount_1,amount_specified, tick_lower, tick_upper, identity(pool_state.borrow().liquidity));
 
-                        let (amount_0_after, amount_1_after) = result.unwrap();
+                        let (amount_0_after, amount_1_after, _) = result.unwrap();
                         assert_eq!(amount_0_before, amount_0_after);
                         assert_eq!(amount_1_before, amount_1_after);
 
@@ -2580,7 +3328,7 @@ mod swap_test {
                         let err =  result.err().unwrap();
                         if err == crate::error::ErrorCode::MaxTokenOverflow.into(){
                             // println!("##### original swap is overflow ");
-                            let _result = swap_internal(
+                            let _result = swap_internal_without_extended_observations(
                                 &amm_config,
                                 &mut pool_state.borrow_mut(),
                                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2624,7 +3372,7 @@ mod swap_test {
                     let mut rng = rand::thread_rng();
                     let amount_specified  = rng.gen_range(1..sum_amount_0);
 
-                    let result = swap_internal(
+                    let result = swap_internal_without_extended_observations(
                         &amm_config,
                         &mut pool_state.borrow_mut(),
                         &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2638,7 +3386,7 @@ mod swap_test {
                     );
 
                     if result.is_ok() {
-                        let ( amount_0_before, amount_1_before) = result.unwrap();
+                        let (amount_0_before, amount_1_before, _) = result.unwrap();
 
                         let (amm_config, pool_state, tick_array_states, observation_state,bitmap_extension_state,  _sum_amount_0, _sum_amount_1) = setup_swap_test(
                             tick_current,
@@ -2646,7 +3394,7 @@ mod swap_test {
                             vec![OpenPositionParam{amount_0:amount_0,amount_1:amount_1, tick_lower:tick_lower, tick_upper:tick_upper}],
                             zero_for_one
                         );
-                        let result = swap_internal(
+                        let result = swap_internal_without_extended_observations(
                             &amm_config,
                             &mut pool_state.borrow_mut(),
                             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -2662,7 +3410,7 @@ mod swap_test {
 
                         // println!("----- input: tick_current:{}, amount_0:{}, amount_1:{}, amount_specified:{},tick_lower:{}, tick_upper:{},liquidity:{}", tick_current, amount_0, amount_1,amount_specified, tick_lower, tick_upper, identity(pool_state.borrow().liquidity));
 
-                        let (amount_0_after, amount_1_after) = result.unwrap();
+                        let (amount_0_after, amount_1_after, _) = result.unwrap();
                         assert_eq!(amount_0_before, amount_0_after);
                         assert_eq!(amount_1_before, amount_1_after);
 
@@ -2670,7 +3418,7 @@ mod swap_test {
                         let err =  result.err().unwrap();
                         if err == crate::error::ErrorCode::MaxTokenOverflow.into(){
                             println!("##### original swap is overflow ");
-                            let _result = swap_internal(
+                            let _result = swap_internal_without_extended_observations(
                                 &amm_config,
                                 &mut pool_state.borrow_mut(),
                                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
@@ -1,6 +1,7 @@
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use crate::util::split_swap_hook_accounts;
 use anchor_lang::prelude::*;
 use anchor_spl::memo::Memo;
 use anchor_spl::{
@@ -8,6 +9,10 @@ use anchor_spl::{
     token_interface::{Mint, Token2022, TokenAccount},
 };
 
+/// The accounts of a hop before its tick arrays: amm_config, pool_state, output_token_account,
+/// input_vault, output_vault, output_token_mint and observation_state
+pub const HOP_ACCOUNTS_LEN: usize = 7;
+
 #[derive(Accounts)]
 pub struct SwapRouterBaseIn<'info> {
     /// The user performing the swap
@@ -38,33 +43,14 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
     let mut amount_in_internal = amount_in;
     let mut input_token_account = Box::new(ctx.accounts.input_token_account.clone());
     let mut input_token_mint = Box::new(ctx.accounts.input_token_mint.clone());
-    let mut accounts: &[AccountInfo] = ctx.remaining_accounts;
-    while !accounts.is_empty() {
-        let mut remaining_accounts = accounts.iter();
-        let account_info = remaining_accounts.next().unwrap();
-        if accounts.len() != ctx.remaining_accounts.len()
-            && account_info.data_len() != AmmConfig::LEN
-        {
-            accounts = remaining_accounts.as_slice();
-            continue;
-        }
-        let amm_config = Box::new(Account::<AmmConfig>::try_from(account_info)?);
-        let pool_state_loader =
-            AccountLoader::<PoolState>::try_from(remaining_accounts.next().unwrap())?;
-        let output_token_account = Box::new(InterfaceAccount::<TokenAccount>::try_from(
-            &remaining_accounts.next().unwrap(),
-        )?);
-        let input_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
-            remaining_accounts.next().unwrap(),
-        )?);
-        let output_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
-            remaining_accounts.next().unwrap(),
-        )?);
-        let output_token_mint = Box::new(InterfaceAccount::<Mint>::try_from(
-            remaining_accounts.next().unwrap(),
-        )?);
-        let observation_state =
-            AccountLoader::<ObservationState>::try_from(remaining_accounts.next().unwrap())?;
+    for hop in split_route_hops(ctx.remaining_accounts)? {
+        let amm_config = Box::new(Account::<AmmConfig>::try_from(&hop[0])?);
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(&hop[1])?;
+        let output_token_account = Box::new(InterfaceAccount::<TokenAccount>::try_from(&hop[2])?);
+        let input_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(&hop[3])?);
+        let output_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(&hop[4])?);
+        let output_token_mint = Box::new(InterfaceAccount::<Mint>::try_from(&hop[5])?);
+        let observation_state = AccountLoader::<ObservationState>::try_from(&hop[6])?;
 
         {
             let pool_state = pool_state_loader.load()?;
@@ -75,7 +61,6 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
         }
 
         // solana_program::log::sol_log_compute_units();
-        accounts = remaining_accounts.as_slice();
         amount_in_internal = exact_internal_v2(
             &mut SwapSingleV2 {
                 payer: ctx.accounts.payer.clone(),
@@ -92,7 +77,7 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
                 token_program_2022: ctx.accounts.token_program_2022.clone(),
                 memo_program: ctx.accounts.memo_program.clone(),
             },
-            accounts,
+            &hop[HOP_ACCOUNTS_LEN..],
             amount_in_internal,
             0,
             true,
@@ -109,3 +94,37 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
 
     Ok(())
 }
+
+/// Splits the remaining accounts of a route into hops. The tick arrays of a hop run up to the next
+/// amm_config, or up to the swap hook program if the pool has a swap hook, in which case the hop
+/// ends after the accounts of the hook.
+pub fn split_route_hops<'c: 'info, 'info>(
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<Vec<&'c [AccountInfo<'info>]>> {
+    let mut hops = Vec::new();
+    let mut accounts = remaining_accounts;
+    while !accounts.is_empty() {
+        require_gt!(
+            accounts.len(),
+            HOP_ACCOUNTS_LEN,
+            ErrorCode::NotEnoughTickArrayAccount
+        );
+        let hop_len = {
+            let pool_state_loader = AccountLoader::<PoolState>::try_from(&accounts[1])?;
+            let pool_state = pool_state_loader.load()?;
+            match split_swap_hook_accounts(&pool_state, &accounts[HOP_ACCOUNTS_LEN..])? {
+                (swap_accounts, Some(hook_accounts)) => {
+                    HOP_ACCOUNTS_LEN + swap_accounts.len() + hook_accounts.len()
+                }
+                (_, None) => accounts[HOP_ACCOUNTS_LEN..]
+                    .iter()
+                    .position(|account_info| account_info.data_len() == AmmConfig::LEN)
+                    .map_or(accounts.len(), |index| HOP_ACCOUNTS_LEN + index),
+            }
+        };
+        let (hop, rest) = accounts.split_at(hop_len);
+        hops.push(hop);
+        accounts = rest;
+    }
+    Ok(hops)
+}
@@ -0,0 +1,142 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::swap::simulate_swap;
+use crate::swap_router_base_in::{split_route_hops, HOP_ACCOUNTS_LEN};
+use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use crate::util::get_transfer_inverse_fee;
+use anchor_lang::prelude::*;
+use anchor_spl::memo::Memo;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct SwapRouterBaseOut<'info> {
+    /// The user performing the swap
+    pub payer: Signer<'info>,
+
+    /// The token account that pays input tokens for the swap
+    #[account(mut)]
+    pub input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of input token
+    #[account(mut)]
+    pub input_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+    /// SPL program 2022 for token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Memo program
+    pub memo_program: Program<'info, Memo>,
+}
+
+pub fn swap_router_base_out<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+    amount_out: u64,
+    amount_in_maximum: u64,
+) -> Result<()> {
+    let hops = split_route_hops(ctx.remaining_accounts)?;
+
+    // walk the route backwards, the input a hop requires is the output of the hop before
+    let mut hop_amounts_out = vec![0; hops.len()];
+    let mut amount_out_internal = amount_out;
+    for (i, &hop) in hops.iter().enumerate().rev() {
+        hop_amounts_out[i] = amount_out_internal;
+        let amm_config = Account::<AmmConfig>::try_from(&hop[0])?;
+        let input_vault = InterfaceAccount::<TokenAccount>::try_from(&hop[3])?;
+        let output_token_mint = Box::new(InterfaceAccount::<Mint>::try_from(&hop[5])?);
+        let input_token_mint = if i == 0 {
+            Box::new(ctx.accounts.input_token_mint.clone())
+        } else {
+            Box::new(InterfaceAccount::<Mint>::try_from(&hops[i - 1][5])?)
+        };
+        let zero_for_one = input_vault.mint
+            == AccountLoader::<PoolState>::try_from(&hop[1])?
+                .load()?
+                .token_mint_0;
+
+        // the pool pays out the transfer fee of the output token on top of the amount received
+        let amount_specified = amount_out_internal
+            .checked_add(get_transfer_inverse_fee(
+                output_token_mint,
+                amount_out_internal,
+            )?)
+            .unwrap();
+        let amount_in = simulate_swap(
+            &amm_config,
+            &hop[1],
+            &hop[6],
+            &hop[HOP_ACCOUNTS_LEN..],
+            amount_specified,
+            if zero_for_one {
+                tick_math::MIN_SQRT_PRICE_X64 + 1
+            } else {
+                tick_math::MAX_SQRT_PRICE_X64 - 1
+            },
+            zero_for_one,
+            false,
+            oracle::block_timestamp(),
+        )?
+        .amount_in;
+        // the payer sends the transfer fee of the input token on top of the amount the pool takes
+        amount_out_internal = amount_in
+            .checked_add(get_transfer_inverse_fee(input_token_mint, amount_in)?)
+            .unwrap();
+    }
+
+    let mut amount_in = 0;
+    let mut input_token_account = Box::new(ctx.accounts.input_token_account.clone());
+    let mut input_token_mint = Box::new(ctx.accounts.input_token_mint.clone());
+    for (i, hop) in hops.into_iter().enumerate() {
+        let amm_config = Box::new(Account::<AmmConfig>::try_from(&hop[0])?);
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(&hop[1])?;
+        let output_token_account = Box::new(InterfaceAccount::<TokenAccount>::try_from(&hop[2])?);
+        let input_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(&hop[3])?);
+        let output_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(&hop[4])?);
+        let output_token_mint = Box::new(InterfaceAccount::<Mint>::try_from(&hop[5])?);
+        let observation_state = AccountLoader::<ObservationState>::try_from(&hop[6])?;
+
+        {
+            let pool_state = pool_state_loader.load()?;
+            // check observation account is owned by the pool
+            require_keys_eq!(pool_state.observation_key, observation_state.key());
+            // check ammConfig account is associate with the pool
+            require_keys_eq!(pool_state.amm_config, amm_config.key());
+        }
+
+        let hop_amount_in = exact_internal_v2(
+            &mut SwapSingleV2 {
+                payer: ctx.accounts.payer.clone(),
+                amm_config,
+                input_token_account: input_token_account.clone(),
+                pool_state: pool_state_loader,
+                output_token_account: output_token_account.clone(),
+                input_vault,
+                output_vault,
+                input_vault_mint: input_token_mint.clone(),
+                output_vault_mint: output_token_mint.clone(),
+                observation_state,
+                token_program: ctx.accounts.token_program.clone(),
+                token_program_2022: ctx.accounts.token_program_2022.clone(),
+                memo_program: ctx.accounts.memo_program.clone(),
+            },
+            &hop[HOP_ACCOUNTS_LEN..],
+            hop_amounts_out[i],
+            0,
+            false,
+        )?;
+        if i == 0 {
+            amount_in = hop_amount_in;
+        }
+        // output token is the new swap input token
+        input_token_account = output_token_account;
+        input_token_mint = output_token_mint;
+    }
+    require_gte!(amount_in_maximum, amount_in, ErrorCode::TooMuchInputPaid);
+
+    Ok(())
+}
@@ -68,9 +68,14 @@ pub struct SwapSingleV2<'info> {
     pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
     // remaining accounts
     // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // reward extension accounts: must add the reward extension and the companions of the tick
+    // arrays crossed if the pool has extension rewards regardless the sequence
+    // reward_schedule: must add account for each pool reward following a schedule
     // tick_array_account_1
     // tick_array_account_2
     // tick_array_account_...
+    // swap_hook_program: must add account after the tick arrays if the pool has a swap hook,
+    // followed by the accounts of the hook
 }
 
 /// Performs a single exact input/output swap
@@ -86,10 +91,34 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
 
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
 
+    // the swap hook accounts follow the tick arrays
+    let (remaining_accounts, swap_hook_accounts) =
+        split_swap_hook_accounts(&*ctx.pool_state.load()?, remaining_accounts)?;
+    let mut swap_hook_params = SwapHookParams::default();
+    if let Some(swap_hook_accounts) = swap_hook_accounts {
+        {
+            let pool_state = ctx.pool_state.load()?;
+            swap_hook_params = SwapHookParams::new(
+                &pool_state,
+                ctx.input_vault.mint == pool_state.token_mint_0,
+                is_base_input,
+                amount_specified,
+            );
+        }
+        invoke_swap_hook(
+            swap_hook_accounts,
+            &ctx.payer.to_account_info(),
+            &ctx.pool_state.to_account_info(),
+            BEFORE_SWAP_HOOK,
+            &swap_hook_params,
+        )?;
+    }
+
     let amount_0;
     let amount_1;
     let zero_for_one;
     let swap_price_before;
+    let trade_fee_rate;
 
     let input_balance_before = ctx.input_token_account.amount;
     let output_balance_before = ctx.output_token_account.amount;
@@ -111,8 +140,6 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         let pool_state = &mut ctx.pool_state.load_mut()?;
         zero_for_one = ctx.input_vault.mint == pool_state.token_mint_0;
 
-        require_gt!(block_timestamp, pool_state.open_time);
-
         require!(
             if zero_for_one {
                 ctx.input_vault.key() == pool_state.token_vault_0
@@ -124,6 +151,21 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             ErrorCode::InvalidInputPoolVault
         );
 
+        update_scheduled_reward_infos(
+            pool_state,
+            ctx.pool_state.key(),
+            remaining_accounts,
+            block_timestamp,
+        )?;
+        let reward_extension = RewardExtensionAccounts::load(
+            pool_state,
+            ctx.pool_state.key(),
+            None,
+            remaining_accounts,
+            block_timestamp,
+        )?;
+        let tick_before = pool_state.tick_current;
+
         let mut tickarray_bitmap_extension = None;
         let tick_array_states = &mut VecDeque::new();
 
@@ -137,14 +179,20 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
                 );
                 continue;
             }
+            if RewardExtensionAccounts::is_extension_account(account_info) {
+                continue;
+            }
             tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
         }
 
-        (amount_0, amount_1) = swap_internal(
+        let (mut observation_state, mut extended_observations) =
+            ObservationState::load_extended_mut(ctx.observation_state.as_ref())?;
+        (amount_0, amount_1, trade_fee_rate) = swap_internal(
             &ctx.amm_config,
             pool_state,
             tick_array_states,
-            &mut ctx.observation_state.load_mut()?,
+            &mut observation_state,
+            &mut extended_observations,
             &tickarray_bitmap_extension,
             amount_calculate_specified,
             if sqrt_price_limit_x64 == 0 {
@@ -161,6 +209,17 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             oracle::block_timestamp(),
         )?;
 
+        if let Some(reward_extension) = reward_extension {
+            // release the tick arrays for the extension to read
+            tick_array_states.clear();
+            reward_extension.cross_ticks(
+                ctx.pool_state.key(),
+                remaining_accounts,
+                tick_before,
+                pool_state.tick_current,
+            )?;
+        }
+
         #[cfg(feature = "enable-log")]
         msg!(
             "exact_swap_internal, is_base_input:{}, amount_0: {}, amount_1: {}",
@@ -291,6 +350,17 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
     ctx.output_token_account.reload()?;
     ctx.input_token_account.reload()?;
 
+    if let Some(swap_hook_accounts) = swap_hook_accounts {
+        swap_hook_params.set_after_swap(&*ctx.pool_state.load()?, amount_0, amount_1);
+        invoke_swap_hook(
+            swap_hook_accounts,
+            &ctx.payer.to_account_info(),
+            &ctx.pool_state.to_account_info(),
+            AFTER_SWAP_HOOK,
+            &swap_hook_params,
+        )?;
+    }
+
     let pool_state = ctx.pool_state.load()?;
     emit!(SwapEvent {
         pool_state: pool_state.key(),
@@ -304,12 +374,18 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         zero_for_one,
         sqrt_price_x64: pool_state.sqrt_price_x64,
         liquidity: pool_state.liquidity,
-        tick: pool_state.tick_current
+        tick: pool_state.tick_current,
+        trade_fee_rate,
     });
-    if zero_for_one {
-        require_gt!(swap_price_before, pool_state.sqrt_price_x64);
-    } else {
-        require_gt!(pool_state.sqrt_price_x64, swap_price_before);
+    // filling limit orders resting on the current tick is the only way a swap pays out without
+    // moving the price, any other swap must move it
+    let amount_out = if zero_for_one { amount_1 } else { amount_0 };
+    if amount_out == 0 || pool_state.sqrt_price_x64 != swap_price_before {
+        if zero_for_one {
+            require_gt!(swap_price_before, pool_state.sqrt_price_x64);
+        } else {
+            require_gt!(pool_state.sqrt_price_x64, swap_price_before);
+        }
     }
     if sqrt_price_limit_x64 == 0 {
         // Does't allow partial filled without specified limit_price.
@@ -347,7 +423,14 @@ pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
     other_amount_threshold: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
-) -> Result<()> {
+) -> Result<SwapResult> {
+    let input_balance_before = ctx.accounts.input_token_account.amount;
+    let output_balance_before = ctx.accounts.output_token_account.amount;
+    let (zero_for_one, fees_before) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let zero_for_one = ctx.accounts.input_vault.mint == pool_state.token_mint_0;
+        (zero_for_one, pool_state.get_accrued_fees(zero_for_one))
+    };
     let amount_result = exact_internal_v2(
         ctx.accounts,
         ctx.remaining_accounts,
@@ -369,5 +452,19 @@ pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
         );
     }
 
-    Ok(())
+    let pool_state = ctx.accounts.pool_state.load()?;
+    Ok(SwapResult {
+        amount_in: input_balance_before
+            .checked_sub(ctx.accounts.input_token_account.amount)
+            .unwrap(),
+        amount_out: ctx
+            .accounts
+            .output_token_account
+            .amount
+            .checked_sub(output_balance_before)
+            .unwrap(),
+        fee_amount: pool_state.get_accrued_fees(zero_for_one) - fees_before,
+        sqrt_price_x64: pool_state.sqrt_price_x64,
+        tick: pool_state.tick_current,
+    })
 }
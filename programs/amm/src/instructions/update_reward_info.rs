@@ -6,6 +6,9 @@ pub struct UpdateRewardInfos<'info> {
     /// The liquidity pool for which reward info to update
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
+    // remaining account
+    // reward_extension: the extension rewards are updated as well if added
+    // reward_schedule: must add account for each pool reward following a schedule
 }
 
 pub fn update_reward_infos<'a, 'b, 'c, 'info>(
@@ -13,8 +16,23 @@ pub fn update_reward_infos<'a, 'b, 'c, 'info>(
 ) -> Result<()> {
     let clock = Clock::get()?;
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    update_scheduled_reward_infos(
+        &mut pool_state,
+        ctx.accounts.pool_state.key(),
+        ctx.remaining_accounts,
+        u64::try_from(clock.unix_timestamp).unwrap(),
+    )?;
     let updated_reward_infos =
         pool_state.update_reward_infos(u64::try_from(clock.unix_timestamp).unwrap())?;
+    if let Some(reward_extension) = RewardExtensionAccounts::find_reward_extension(
+        ctx.accounts.pool_state.key(),
+        ctx.remaining_accounts,
+    )? {
+        reward_extension.load_mut()?.update_reward_infos(
+            u64::try_from(clock.unix_timestamp).unwrap(),
+            pool_state.liquidity,
+        )?;
+    }
 
     emit!(UpdateRewardInfosEvent {
         reward_growth_global_x64: RewardInfo::get_reward_growths(&updated_reward_infos)
@@ -67,6 +67,45 @@ pub mod amm_v3 {
             trade_fee_rate,
             protocol_fee_rate,
             fund_fee_rate,
+            0,
+        )
+    }
+
+    /// Creates an amm config like `create_amm_config` together with the pool creator fee rate
+    /// # Arguments
+    ///
+    /// * `ctx`- The accounts needed by instruction.
+    /// * `index` - The index of amm config, there may be multiple config.
+    /// * `tick_spacing` - The tickspacing binding with config, cannot be changed.
+    /// * `trade_fee_rate` - Trade fee rate, can be changed.
+    /// * `protocol_fee_rate` - The rate of protocol fee within trade fee.
+    /// * `fund_fee_rate` - The rate of fund fee within trade fee.
+    /// * `creator_fee_rate` - The rate of pool creator fee within trade fee.
+    ///
+    pub fn create_amm_config_with_creator_fee(
+        ctx: Context<CreateAmmConfig>,
+        index: u16,
+        tick_spacing: u16,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+        fund_fee_rate: u32,
+        creator_fee_rate: u32,
+    ) -> Result<()> {
+        assert!(trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
+        assert!(protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+        assert!(fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+        assert!(creator_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+        assert!(
+            fund_fee_rate + protocol_fee_rate + creator_fee_rate <= FEE_RATE_DENOMINATOR_VALUE
+        );
+        instructions::create_amm_config(
+            ctx,
+            index,
+            tick_spacing,
+            trade_fee_rate,
+            protocol_fee_rate,
+            fund_fee_rate,
+            creator_fee_rate,
         )
     }
 
@@ -86,7 +125,11 @@ pub mod amm_v3 {
     /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
     /// * `new_owner`- The config's new owner, be set when `param` is 3
     /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    /// * `dynamic_fee_max_rate`- The max volatility surcharge of trade fee, be set when `param` is 5
+    /// * `dynamic_fee_control`- The volatility surcharge per tick of deviation, at most 100%, be set when `param` is 6
+    /// * `dynamic_fee_decay_period`- The window in seconds of the average tick, between 1 second and 1 day, be set when `param` is 7
+    /// * `creator_fee_rate`- The new pool creator fee rate of amm config, be set when `param` is 8
+    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8, otherwise will report a error
     ///
     pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -> Result<()> {
         instructions::update_amm_config(ctx, param, value)
@@ -98,7 +141,8 @@ pub mod amm_v3 {
     ///
     /// * `ctx`- The context of accounts
     /// * `sqrt_price_x64` - the initial sqrt price (amount_token_1 / amount_token_0) of the pool as a Q64.64
-    /// Note: The open_time must be smaller than the current block_timestamp on chain.
+    /// * `open_time` - The timestamp swaps are allowed after, liquidity can be provided before.
+    /// Note: The pool opens at once if the open_time is smaller than the current block_timestamp on chain.
     pub fn create_pool(
         ctx: Context<CreatePool>,
         sqrt_price_x64: u128,
@@ -107,6 +151,18 @@ pub mod amm_v3 {
         instructions::create_pool(ctx, sqrt_price_x64, open_time)
     }
 
+    /// Changes the time a pool opens for swaps, by the pool owner or the admin before it opens.
+    /// Swaps disabled by the pool status stay disabled after the open time.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `open_time` - The timestamp swaps are allowed after
+    ///
+    pub fn set_pool_open_time(ctx: Context<SetPoolOpenTime>, open_time: u64) -> Result<()> {
+        instructions::set_pool_open_time(ctx, open_time)
+    }
+
     /// Update pool status for given value
     ///
     /// # Arguments
@@ -118,6 +174,77 @@ pub mod amm_v3 {
         instructions::update_pool_status(ctx, status)
     }
 
+    /// Update the minimum seconds between two oracle observations of a pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `update_duration` - The minimum seconds between two observations, at most one hour
+    ///
+    pub fn update_observation_duration(
+        ctx: Context<UpdateObservationDuration>,
+        update_duration: u32,
+    ) -> Result<()> {
+        instructions::update_observation_duration(ctx, update_duration)
+    }
+
+    /// Update the max ticks a swap can move the price of a pool away from the recent average tick
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `max_tick_deviation` - The max ticks away from the average tick, zero disables the guard
+    /// * `tick_deviation_period` - The window in seconds of the average tick
+    ///
+    pub fn update_pool_tick_deviation(
+        ctx: Context<UpdatePoolTickDeviation>,
+        max_tick_deviation: u32,
+        tick_deviation_period: u32,
+    ) -> Result<()> {
+        instructions::update_pool_tick_deviation(ctx, max_tick_deviation, tick_deviation_period)
+    }
+
+    /// Override the trade fee rate of a pool, independent of its amm config
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `trade_fee_rate` - The trade fee rate of the pool, none to use the amm config one again
+    ///
+    pub fn update_pool_trade_fee_rate(
+        ctx: Context<UpdatePoolTradeFeeRate>,
+        trade_fee_rate: Option<u32>,
+    ) -> Result<()> {
+        instructions::update_pool_trade_fee_rate(ctx, trade_fee_rate)
+    }
+
+    /// Move a pool to another amm config of the same tick spacing, to change its fee tier.
+    /// The protocol and fund fees of the pool must be collected before.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn update_pool_amm_config(ctx: Context<UpdatePoolAmmConfig>) -> Result<()> {
+        instructions::update_pool_amm_config(ctx)
+    }
+
+    /// Register the program called before and after each swap of a pool, which can reject the swap
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `swap_hook_program` - The swap hook program, none to remove the swap hook
+    /// * `swap_hook_account_count` - The number of accounts passed to the swap hook program after it
+    ///
+    pub fn update_pool_swap_hook(
+        ctx: Context<UpdatePoolSwapHook>,
+        swap_hook_program: Option<Pubkey>,
+        swap_hook_account_count: u8,
+    ) -> Result<()> {
+        instructions::update_pool_swap_hook(ctx, swap_hook_program, swap_hook_account_count)
+    }
+
     /// Creates an operation account for the program
     ///
     /// # Arguments
@@ -178,20 +305,79 @@ pub mod amm_v3 {
         instructions::initialize_reward(ctx, param)
     }
 
-    /// Collect remaining reward token for reward founder
+    /// Creates the reward extension of a pool, which holds the reward slots beyond the pool ones.
+    /// Once the pool reward slots are full, `initialize_reward` with the reward extension in the
+    /// remaining accounts initializes the reward in the extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn initialize_reward_extension(ctx: Context<InitializeRewardExtension>) -> Result<()> {
+        instructions::initialize_reward_extension(ctx)
+    }
+
+    /// Creates the companion of a tick array tracking the extension reward growths of its ticks,
+    /// required to add liquidity at its ticks and to cross them once the pool has extension rewards
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `tick_array_start_index` - The start index of the tick array
+    ///
+    pub fn initialize_tick_array_reward_extension(
+        ctx: Context<InitializeTickArrayRewardExtension>,
+        tick_array_start_index: i32,
+    ) -> Result<()> {
+        instructions::initialize_tick_array_reward_extension(ctx, tick_array_start_index)
+    }
+
+    /// Creates the companion of a position tracking its extension rewards, required to open or
+    /// modify the position once the pool has extension rewards. It can be created before the
+    /// position is opened.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn initialize_position_reward_extension(
+        ctx: Context<InitializePositionRewardExtension>,
+    ) -> Result<()> {
+        instructions::initialize_position_reward_extension(ctx)
+    }
+
+    /// Collect remaining reward token for reward founder, or refund a reward funder its share of
+    /// the tokens left unemitted
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
     /// * `reward_index` - the index to reward info
     ///
-    pub fn collect_remaining_rewards(
-        ctx: Context<CollectRemainingRewards>,
+    pub fn collect_remaining_rewards<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectRemainingRewards<'info>>,
         reward_index: u8,
     ) -> Result<()> {
         instructions::collect_remaining_rewards(ctx, reward_index)
     }
 
+    /// Settle a reward that has ended and refund the funders who have not collected their share
+    /// of the tokens left unemitted, so the reward can be funded again
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - the index to reward info
+    /// * `funder_count` - The number of funder accounts pairs ending the remaining accounts
+    ///
+    pub fn refund_reward_funders<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RefundRewardFunders<'info>>,
+        reward_index: u8,
+        funder_count: u8,
+    ) -> Result<()> {
+        instructions::refund_reward_funders(ctx, reward_index, funder_count)
+    }
+
     /// Update rewards info of the given pool, can be called for everyone
     ///
     /// # Arguments
@@ -231,6 +417,68 @@ pub mod amm_v3 {
         )
     }
 
+    /// Deposit into the vault of a reward still emitting, can be called for everyone. The deposit
+    /// is refunded pro rata on collect remaining rewards if the reward ends with tokens unemitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `reward_index` - The index of reward token in the pool.
+    /// * `amount` - The amount the reward vault receives
+    /// * `extend_end_time` - Extend the end time at the current emissions if true, otherwise
+    ///    raise the emissions until the end time
+    ///
+    pub fn fund_reward<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, FundReward<'info>>,
+        reward_index: u8,
+        amount: u64,
+        extend_end_time: bool,
+    ) -> Result<()> {
+        instructions::fund_reward(ctx, reward_index, amount, extend_end_time)
+    }
+
+    /// Restrict a reward to a tick range, it then only accrues while the current tick is within
+    /// the range. Only the pool reward slots can be restricted, the extension reward slots are
+    /// rejected with `RewardRangeNotSupported`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `reward_index` - The index of reward token in the pool.
+    /// * `enabled` - Restrict the reward to the range if true, otherwise remove its range
+    /// * `tick_lower` - The lower tick of the range, inclusive
+    /// * `tick_upper` - The upper tick of the range, exclusive
+    ///
+    pub fn set_reward_range(
+        ctx: Context<SetRewardRange>,
+        reward_index: u8,
+        enabled: bool,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<()> {
+        instructions::set_reward_range(ctx, reward_index, enabled, tick_lower, tick_upper)
+    }
+
+    /// Replace the constant emissions of a reward with a step schedule from its first point on.
+    /// The reward schedule account must then be passed to the instructions accruing rewards
+    /// until the reward ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `reward_index` - The index of reward token in the pool.
+    /// * `points` - The start timestamps and emissions of the schedule segments, sorted
+    /// * `end_time` - The reward end timestamp, after the last point
+    ///
+    pub fn set_reward_schedule<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetRewardSchedule<'info>>,
+        reward_index: u8,
+        points: Vec<RewardSchedulePoint>,
+        end_time: u64,
+    ) -> Result<()> {
+        instructions::set_reward_schedule(ctx, reward_index, points, end_time)
+    }
+
     /// Collect the protocol fee accrued to the pool
     ///
     /// # Arguments
@@ -263,6 +511,22 @@ pub mod amm_v3 {
         instructions::collect_fund_fee(ctx, amount_0_requested, amount_1_requested)
     }
 
+    /// Collect the creator fee accrued to the pool, signed by the pool owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
+    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    ///
+    pub fn collect_creator_fee(
+        ctx: Context<CollectCreatorFee>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_creator_fee(ctx, amount_0_requested, amount_1_requested)
+    }
+
     /// #[deprecated(note = "Use `open_position_with_token22_nft` instead.")]
     /// Creates a new position wrapped in a NFT
     ///
@@ -302,7 +566,7 @@ pub mod amm_v3 {
     }
 
     /// #[deprecated(note = "Use `open_position_with_token22_nft` instead.")]
-    /// Creates a new position wrapped in a NFT, support Token2022
+    /// Creates a new position wrapped in a NFT, support Token2022, the liquidity and amounts are returned as return data
     ///
     /// # Arguments
     ///
@@ -328,7 +592,7 @@ pub mod amm_v3 {
         amount_1_max: u64,
         with_metadata: bool,
         base_flag: Option<bool>,
-    ) -> Result<()> {
+    ) -> Result<LiquidityChangeResult> {
         instructions::open_position_v2(
             ctx,
             liquidity,
@@ -343,7 +607,7 @@ pub mod amm_v3 {
         )
     }
 
-    /// Creates a new position wrapped in a Token2022 NFT without relying on metadata_program and metadata_account, reduce the cost for user to create a personal position.
+    /// Creates a new position wrapped in a NFT like `open_position_v2`, fails once the deadline has passed
     ///
     /// # Arguments
     ///
@@ -357,9 +621,10 @@ pub mod amm_v3 {
     /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
     /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
     /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `deadline` - The unix timestamp after which the transaction fails
     ///
-    pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
+    pub fn open_position_v2_with_deadline<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionV2<'info>>,
         tick_lower_index: i32,
         tick_upper_index: i32,
         tick_array_lower_start_index: i32,
@@ -369,8 +634,10 @@ pub mod amm_v3 {
         amount_1_max: u64,
         with_metadata: bool,
         base_flag: Option<bool>,
-    ) -> Result<()> {
-        instructions::open_position_with_token22_nft(
+        deadline: i64,
+    ) -> Result<LiquidityChangeResult> {
+        util::check_deadline(Some(deadline))?;
+        instructions::open_position_v2(
             ctx,
             liquidity,
             amount_0_max,
@@ -384,81 +651,274 @@ pub mod amm_v3 {
         )
     }
 
-    /// Close the user's position and NFT account. If the NFT mint belongs to token2022, it will also be closed and the funds returned to the NFT owner.
-    ///
-    /// # Arguments
-    ///
-    /// * `ctx` - The context of accounts
-    ///
-    pub fn close_position<'a, 'b, 'c, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, ClosePosition<'info>>,
-    ) -> Result<()> {
-        instructions::close_position(ctx)
-    }
-
-    /// #[deprecated(note = "Use `increase_liquidity_v2` instead.")]
-    /// Increases liquidity for an existing position, with amount paid by `payer`
+    /// Creates a new position wrapped in a Token2022 NFT without relying on metadata_program and metadata_account, reduce the cost for user to create a personal position.
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
-    /// * `liquidity` - The desired liquidity to be added, can't be zero
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `liquidity` - The liquidity to be added, if zero, and the base_flag is specified, calculate liquidity base amount_0_max or amount_1_max according base_flag, otherwise open position with zero liquidity
     /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
     /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
     ///
-    pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidity<'info>>,
+    pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
         liquidity: u128,
         amount_0_max: u64,
         amount_1_max: u64,
+        with_metadata: bool,
+        base_flag: Option<bool>,
     ) -> Result<()> {
-        assert!(liquidity != 0);
-        instructions::increase_liquidity_v1(ctx, liquidity, amount_0_max, amount_1_max, None)
+        instructions::open_position_with_token22_nft(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            with_metadata,
+            base_flag,
+        )
     }
 
-    /// Increases liquidity for an existing position, with amount paid by `payer`, support Token2022
+    /// Creates a new position wrapped in a Token2022 NFT like `open_position_with_token22_nft`, fails once the deadline has passed
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
-    /// * `liquidity` - The desired liquidity to be added, if zero, calculate liquidity base amount_0 or amount_1 according base_flag
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `liquidity` - The liquidity to be added, if zero, and the base_flag is specified, calculate liquidity base amount_0_max or amount_1_max according base_flag, otherwise open position with zero liquidity
     /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
     /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
-    /// * `base_flag` - must be specified if liquidity is zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `deadline` - The unix timestamp after which the transaction fails
     ///
-    pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidityV2<'info>>,
+    pub fn open_position_with_token22_nft_with_deadline<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
         liquidity: u128,
         amount_0_max: u64,
         amount_1_max: u64,
+        with_metadata: bool,
         base_flag: Option<bool>,
+        deadline: i64,
     ) -> Result<()> {
-        if liquidity == 0 {
-            assert!(base_flag.is_some());
-        }
-        instructions::increase_liquidity_v2(ctx, liquidity, amount_0_max, amount_1_max, base_flag)
+        util::check_deadline(Some(deadline))?;
+        instructions::open_position_with_token22_nft(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            with_metadata,
+            base_flag,
+        )
     }
 
-    /// #[deprecated(note = "Use `decrease_liquidity_v2` instead.")]
-    /// Decreases liquidity for an existing position
+    /// Creates a new position wrapped in a Token2022 NFT from a single token. Part of the token is swapped
+    /// through the pool so that the rest and the swap output fit the range at the price after the swap,
+    /// what doesn't fit is paid back. The liquidity and amounts deposited are returned as return data.
     ///
     /// # Arguments
     ///
-    /// * `ctx` -  The context of accounts
-    /// * `liquidity` - The amount by which liquidity will be decreased
-    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
-    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `amount` - The amount of the single token to deposit
+    /// * `base_flag` - true: the amount is of token_0, otherwise token_1
+    /// * `liquidity_min` - The minimum liquidity of the position, for slippage protection
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
     ///
-    pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidity<'info>>,
-        liquidity: u128,
-        amount_0_min: u64,
+    pub fn open_position_single_sided<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionSingleSided<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        amount: u64,
+        base_flag: bool,
+        liquidity_min: u128,
+        with_metadata: bool,
+    ) -> Result<LiquidityChangeResult> {
+        instructions::open_position_single_sided(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            amount,
+            base_flag,
+            liquidity_min,
+            with_metadata,
+        )
+    }
+
+    /// Open a position from a single token like `open_position_single_sided`, fails once the
+    /// deadline has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `amount` - The amount of the single token to deposit
+    /// * `base_flag` - true: the amount is of token_0, otherwise token_1
+    /// * `liquidity_min` - The minimum liquidity of the position, for slippage protection
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    /// * `deadline` - The unix timestamp after which the transaction fails
+    ///
+    pub fn open_position_single_sided_with_deadline<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionSingleSided<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        amount: u64,
+        base_flag: bool,
+        liquidity_min: u128,
+        with_metadata: bool,
+        deadline: i64,
+    ) -> Result<LiquidityChangeResult> {
+        util::check_deadline(Some(deadline))?;
+        instructions::open_position_single_sided(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            amount,
+            base_flag,
+            liquidity_min,
+            with_metadata,
+        )
+    }
+
+    /// Close the user's position and NFT account. If the NFT mint belongs to token2022, it will also be closed and the funds returned to the NFT owner.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn close_position<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ClosePosition<'info>>,
+    ) -> Result<()> {
+        instructions::close_position(ctx)
+    }
+
+    /// #[deprecated(note = "Use `increase_liquidity_v2` instead.")]
+    /// Increases liquidity for an existing position, with amount paid by `payer`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The desired liquidity to be added, can't be zero
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    ///
+    pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidity<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<()> {
+        assert!(liquidity != 0);
+        instructions::increase_liquidity_v1(ctx, liquidity, amount_0_max, amount_1_max, None)
+    }
+
+    /// Increases liquidity for an existing position, with amount paid by `payer`, support Token2022, the liquidity and amounts are returned as return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The desired liquidity to be added, if zero, calculate liquidity base amount_0 or amount_1 according base_flag
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `base_flag` - must be specified if liquidity is zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    ///
+    pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidityV2<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        base_flag: Option<bool>,
+    ) -> Result<LiquidityChangeResult> {
+        if liquidity == 0 {
+            assert!(base_flag.is_some());
+        }
+        instructions::increase_liquidity_v2(ctx, liquidity, amount_0_max, amount_1_max, base_flag)
+    }
+
+    /// Increases liquidity for an existing position like `increase_liquidity_v2`, fails once the deadline has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The desired liquidity to be added, if zero, calculate liquidity base amount_0 or amount_1 according base_flag
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `base_flag` - must be specified if liquidity is zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `deadline` - The unix timestamp after which the transaction fails
+    ///
+    pub fn increase_liquidity_v2_with_deadline<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidityV2<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        base_flag: Option<bool>,
+        deadline: i64,
+    ) -> Result<LiquidityChangeResult> {
+        util::check_deadline(Some(deadline))?;
+        if liquidity == 0 {
+            assert!(base_flag.is_some());
+        }
+        instructions::increase_liquidity_v2(ctx, liquidity, amount_0_max, amount_1_max, base_flag)
+    }
+
+    /// #[deprecated(note = "Use `decrease_liquidity_v2` instead.")]
+    /// Decreases liquidity for an existing position
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `liquidity` - The amount by which liquidity will be decreased
+    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
+    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    ///
+    pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidity<'info>>,
+        liquidity: u128,
+        amount_0_min: u64,
         amount_1_min: u64,
     ) -> Result<()> {
         instructions::decrease_liquidity_v1(ctx, liquidity, amount_0_min, amount_1_min)
     }
 
-    /// Decreases liquidity for an existing position, support Token2022
+    /// Decreases liquidity for an existing position, support Token2022, the liquidity and amounts are returned as return data
     ///
     /// # Arguments
     ///
@@ -472,10 +932,161 @@ pub mod amm_v3 {
         liquidity: u128,
         amount_0_min: u64,
         amount_1_min: u64,
-    ) -> Result<()> {
+    ) -> Result<LiquidityChangeResult> {
         instructions::decrease_liquidity_v2(ctx, liquidity, amount_0_min, amount_1_min)
     }
 
+    /// Decreases liquidity for an existing position like `decrease_liquidity_v2`, fails once the deadline has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `liquidity` - The amount by which liquidity will be decreased
+    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
+    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    /// * `deadline` - The unix timestamp after which the transaction fails
+    ///
+    pub fn decrease_liquidity_v2_with_deadline<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityV2<'info>>,
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+        deadline: i64,
+    ) -> Result<LiquidityChangeResult> {
+        util::check_deadline(Some(deadline))?;
+        instructions::decrease_liquidity_v2(ctx, liquidity, amount_0_min, amount_1_min)
+    }
+
+    /// Collects the fees and rewards owed to many positions of a pool, one transfer per vault
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `position_count` - The number of position groups leading the remaining accounts
+    ///
+    pub fn collect_all<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectAll<'info>>,
+        position_count: u8,
+    ) -> Result<()> {
+        instructions::collect_all(ctx, position_count)
+    }
+
+    /// Reinvests the fees owed to a position as liquidity of the same position, as much as fits the
+    /// current price, the rest stays owed. No tokens are moved out of the vaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    ///
+    pub fn compound_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CompoundPosition<'info>>,
+    ) -> Result<()> {
+        instructions::compound_position(ctx)
+    }
+
+    /// Moves all liquidity of a position to a new tick range in one instruction, optionally
+    /// swapping part of the removed tokens through the same pool first. The position keeps its
+    /// NFT and the fees and rewards owed, the tokens that don't fit the new range are paid out.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `tick_lower_index` - The low boundary of the new range
+    /// * `tick_upper_index` - The upper boundary of the new range
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `swap_amount` - The amount of removed tokens to swap, 0 for no swap
+    /// * `zero_for_one` - The swap direction, swap token_0 for token_1 if true
+    /// * `liquidity_min` - The minimum liquidity of the new range, for slippage protection
+    ///
+    pub fn rebalance_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RebalancePosition<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        swap_amount: u64,
+        zero_for_one: bool,
+        liquidity_min: u128,
+    ) -> Result<()> {
+        instructions::rebalance_position(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            swap_amount,
+            zero_for_one,
+            liquidity_min,
+        )
+    }
+
+    /// Moves all liquidity of a position to a new tick range like `rebalance_position`, fails once the
+    /// deadline has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `tick_lower_index` - The low boundary of the new range
+    /// * `tick_upper_index` - The upper boundary of the new range
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `swap_amount` - The amount of removed tokens to swap, 0 for no swap
+    /// * `zero_for_one` - The swap direction, swap token_0 for token_1 if true
+    /// * `liquidity_min` - The minimum liquidity of the new range, for slippage protection
+    /// * `deadline` - The unix timestamp after which the transaction fails
+    ///
+    pub fn rebalance_position_with_deadline<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RebalancePosition<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        swap_amount: u64,
+        zero_for_one: bool,
+        liquidity_min: u128,
+        deadline: i64,
+    ) -> Result<()> {
+        util::check_deadline(Some(deadline))?;
+        instructions::rebalance_position(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            swap_amount,
+            zero_for_one,
+            liquidity_min,
+        )
+    }
+
+    /// Grants a delegate the right to act on a position without transferring the position NFT.
+    /// A delegate signs in place of the owner and passes the delegate account in the remaining accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `permissions` - Bitmask of the granted actions, bit 0 collect, bit 1 decrease liquidity, bit 2 increase liquidity, bit 3 compound
+    /// * `expiry` - The unix timestamp the grant expires at, 0 if it never expires
+    ///
+    pub fn grant_position_delegate(
+        ctx: Context<GrantPositionDelegate>,
+        permissions: u8,
+        expiry: u64,
+    ) -> Result<()> {
+        instructions::grant_position_delegate(ctx, permissions, expiry)
+    }
+
+    /// Revokes a position delegate and returns the account rent to the owner who granted it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    ///
+    pub fn revoke_position_delegate(ctx: Context<RevokePositionDelegate>) -> Result<()> {
+        instructions::revoke_position_delegate(ctx)
+    }
+
     /// #[deprecated(note = "Use `swap_v2` instead.")]
     /// Swaps one token for as much as possible of another token across a single pool
     ///
@@ -503,7 +1114,37 @@ pub mod amm_v3 {
         )
     }
 
-    /// Swaps one token for as much as possible of another token across a single pool, support token program 2022
+    /// Swaps one token for another across a single pool like `swap`, fails once the deadline has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `deadline` - The unix timestamp after which the transaction fails
+    ///
+    pub fn swap_with_deadline<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        deadline: i64,
+    ) -> Result<()> {
+        util::check_deadline(Some(deadline))?;
+        instructions::swap(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+        )
+    }
+
+    /// Swaps one token for as much as possible of another token across a single pool, support token program 2022,
+    /// the amounts, fee and price after the swap are returned as return data
     ///
     /// # Arguments
     ///
@@ -519,7 +1160,36 @@ pub mod amm_v3 {
         other_amount_threshold: u64,
         sqrt_price_limit_x64: u128,
         is_base_input: bool,
-    ) -> Result<()> {
+    ) -> Result<SwapResult> {
+        instructions::swap_v2(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+        )
+    }
+
+    /// Swaps one token for another across a single pool like `swap_v2`, fails once the deadline has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `deadline` - The unix timestamp after which the transaction fails
+    ///
+    pub fn swap_v2_with_deadline<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        deadline: i64,
+    ) -> Result<SwapResult> {
+        util::check_deadline(Some(deadline))?;
         instructions::swap_v2(
             ctx,
             amount,
@@ -544,4 +1214,176 @@ pub mod amm_v3 {
     ) -> Result<()> {
         instructions::swap_router_base_in(ctx, amount_in, amount_out_minimum)
     }
+
+    /// Swaps across the path provided like `swap_router_base_in`, fails once the deadline has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_in` - Token amount to be swapped in
+    /// * `amount_out_minimum` - Panic if output amount is below minimum amount. For slippage.
+    /// * `deadline` - The unix timestamp after which the transaction fails
+    ///
+    pub fn swap_router_base_in_with_deadline<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+        amount_in: u64,
+        amount_out_minimum: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        util::check_deadline(Some(deadline))?;
+        instructions::swap_router_base_in(ctx, amount_in, amount_out_minimum)
+    }
+
+    /// Swap as little as possible of one token for an exact amount of another token across the path provided, base output.
+    /// The input of every hop is found by walking the path backwards against copies of the pools,
+    /// long paths may need a larger heap frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_out` - Token amount to be received from the last hop
+    /// * `amount_in_maximum` - Panic if input amount is above maximum amount. For slippage.
+    ///
+    pub fn swap_router_base_out<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+        amount_out: u64,
+        amount_in_maximum: u64,
+    ) -> Result<()> {
+        instructions::swap_router_base_out(ctx, amount_out, amount_in_maximum)
+    }
+
+    /// Swaps across the path provided like `swap_router_base_out`, fails once the deadline has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_out` - Token amount to be received from the last hop
+    /// * `amount_in_maximum` - Panic if input amount is above maximum amount. For slippage.
+    /// * `deadline` - The unix timestamp after which the transaction fails
+    ///
+    pub fn swap_router_base_out_with_deadline<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+        amount_out: u64,
+        amount_in_maximum: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        util::check_deadline(Some(deadline))?;
+        instructions::swap_router_base_out(ctx, amount_out, amount_in_maximum)
+    }
+
+    /// Quotes a swap across a single pool against copies of the pool state without writing to any account,
+    /// the amounts including the transfer fees, the fee, the price after the swap and the tick arrays it runs
+    /// through are returned as return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - The amount_in of a base input swap or the amount_out of a base output swap
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    ///
+    pub fn quote_swap<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, QuoteSwap<'info>>,
+        amount: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+    ) -> Result<QuoteSwapResult> {
+        instructions::quote_swap(ctx, amount, sqrt_price_limit_x64, is_base_input)
+    }
+
+    /// Lends tokens from the pool vaults and calls back the borrower's program, the vaults must get back
+    /// the tokens plus a fee at the trade fee rate by the end of the instruction.
+    /// Whatever the callback did not pay back is taken from the payer's token accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0` - The amount of token_0 to borrow
+    /// * `amount_1` - The amount of token_1 to borrow
+    /// * `data` - The callback instruction data, the fees of token_0 and token_1 are appended as u64
+    ///
+    pub fn flash<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Flash<'info>>,
+        amount_0: u64,
+        amount_1: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::flash(ctx, amount_0, amount_1, data)
+    }
+
+    /// Places a limit order on a single tick, filled at the tick price when a swap reaches it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_index` - The tick the order rests on, above the current tick when selling token_0, otherwise at or below it
+    /// * `tick_array_start_index` - The start index of the tick array that contains the tick
+    /// * `zero_for_one` - true: sells token_0 for token_1, false: sells token_1 for token_0
+    /// * `amount` - The amount of the sold token, including transfer fee
+    ///
+    pub fn open_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenLimitOrder<'info>>,
+        tick_index: i32,
+        tick_array_start_index: i32,
+        zero_for_one: bool,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::open_limit_order(
+            ctx,
+            tick_index,
+            tick_array_start_index,
+            zero_for_one,
+            amount,
+        )
+    }
+
+    /// Cancels a limit order, returns the unfilled amount with the proceeds of the filled part and closes the order account
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn cancel_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CancelLimitOrder<'info>>,
+    ) -> Result<()> {
+        instructions::cancel_limit_order(ctx)
+    }
+
+    /// Claims the proceeds of the filled part of a limit order, the order account is closed once completely filled
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn claim_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ClaimLimitOrder<'info>>,
+    ) -> Result<()> {
+        instructions::claim_limit_order(ctx)
+    }
+
+    /// Reads the tick cumulatives of the pool oracle and the time-weighted average price, returned as return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `seconds_agos` - How many seconds ago to read the tick cumulative at, the average covers the window between the first and the last
+    ///
+    pub fn observe(ctx: Context<Observe>, seconds_agos: Vec<u32>) -> Result<ObserveResult> {
+        instructions::observe(ctx, seconds_agos)
+    }
+
+    /// Grows the observation account of the pool by realloc to hold more observations, the new
+    /// observations are taken into use once the current ones have been filled up
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `observation_cardinality_next` - The number of observations to hold, at most 232 more than the current one per instruction
+    ///
+    pub fn increase_observation_cardinality(
+        ctx: Context<IncreaseObservationCardinality>,
+        observation_cardinality_next: u16,
+    ) -> Result<()> {
+        instructions::increase_observation_cardinality(ctx, observation_cardinality_next)
+    }
 }
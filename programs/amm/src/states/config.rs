@@ -1,3 +1,4 @@
+use super::{Observation, ObservationState};
 use crate::error::ErrorCode;
 use anchor_lang::prelude::*;
 
@@ -5,6 +6,9 @@ pub const AMM_CONFIG_SEED: &str = "amm_config";
 
 pub const FEE_RATE_DENOMINATOR_VALUE: u32 = 1_000_000;
 
+/// The longest window in seconds of the average tick the dynamic fee is measured from
+pub const MAX_DYNAMIC_FEE_DECAY_PERIOD: u32 = 24 * 60 * 60;
+
 /// Holds the current owner of the factory
 #[account]
 #[derive(Default, Debug)]
@@ -22,10 +26,20 @@ pub struct AmmConfig {
     pub tick_spacing: u16,
     /// The fund fee, denominated in hundredths of a bip (10^-6)
     pub fund_fee_rate: u32,
-    // padding space for upgrade
-    pub padding_u32: u32,
+    /// The pool creator fee, paid to the pool owner out of the trade fee, denominated in
+    /// hundredths of a bip (10^-6)
+    pub creator_fee_rate: u32,
     pub fund_owner: Pubkey,
-    pub padding: [u64; 3],
+    /// The max volatility surcharge added on top of trade fee, denominated in hundredths of a bip (10^-6).
+    /// Zero disables the dynamic fee for all pools of the config.
+    pub dynamic_fee_max_rate: u32,
+    /// The surcharge added per tick of deviation between the current tick and the recent average tick,
+    /// denominated in hundredths of a bip (10^-6)
+    pub dynamic_fee_control: u32,
+    /// The window in seconds of the average tick, older tick movement decays out of the window
+    pub dynamic_fee_decay_period: u32,
+    // padding space for upgrade
+    pub padding: [u32; 3],
 }
 
 impl AmmConfig {
@@ -42,6 +56,29 @@ impl AmmConfig {
         );
         Ok(())
     }
+
+    /// Returns the volatility surcharge added to the trade fee rate, zero when the dynamic fee is
+    /// disabled.
+    pub fn get_dynamic_fee_rate(
+        &self,
+        observation_state: &ObservationState,
+        extended_observations: &[Observation],
+        tick_current: i32,
+        block_timestamp: u32,
+    ) -> u32 {
+        if self.dynamic_fee_max_rate == 0 || self.dynamic_fee_control == 0 {
+            return 0;
+        }
+        let tick_deviation = observation_state.get_tick_deviation(
+            extended_observations,
+            block_timestamp,
+            tick_current,
+            self.dynamic_fee_decay_period,
+        );
+        u64::from(tick_deviation)
+            .saturating_mul(self.dynamic_fee_control.into())
+            .min(self.dynamic_fee_max_rate.into()) as u32
+    }
 }
 
 /// Emitted when create or update a config
@@ -56,3 +93,21 @@ pub struct ConfigChangeEvent {
     pub fund_fee_rate: u32,
     pub fund_owner: Pubkey,
 }
+
+/// Emitted when the dynamic fee parameters of a config are set, apart from ConfigChangeEvent to keep its layout
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DynamicFeeChangeEvent {
+    pub index: u16,
+    pub dynamic_fee_max_rate: u32,
+    pub dynamic_fee_control: u32,
+    pub dynamic_fee_decay_period: u32,
+}
+
+/// Emitted when the creator fee rate of a config is set, apart from ConfigChangeEvent to keep its layout
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CreatorFeeRateChangeEvent {
+    pub index: u16,
+    pub creator_fee_rate: u32,
+}
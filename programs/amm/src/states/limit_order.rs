@@ -0,0 +1,288 @@
+use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv};
+use crate::util::get_recent_epoch;
+use anchor_lang::prelude::*;
+
+use super::TickState;
+
+/// Seed to derive account address and signature
+pub const LIMIT_ORDER_SEED: &str = "limit_order";
+
+/// A single tick limit order, selling one token of the pool for the other at the tick price
+#[account]
+#[derive(Default, Debug)]
+pub struct LimitOrderState {
+    /// Bump to identify PDA
+    pub bump: u8,
+
+    /// The ID of the pool the order is placed in
+    pub pool_id: Pubkey,
+
+    /// The owner of the order, who receives the proceeds
+    pub owner: Pubkey,
+
+    /// The tick the order rests on
+    pub tick_index: i32,
+
+    /// true: sells token_0 for token_1, false: sells token_1 for token_0
+    pub zero_for_one: bool,
+
+    /// The order phase of the tick when the order was last updated
+    pub order_phase: u64,
+
+    /// The amount of the sold token that is still waiting to be filled
+    pub unfilled_amount: u64,
+
+    /// The remaining ratio of the tick orders as of the last update, Q64.64
+    pub remaining_ratio_snapshot_x64: u128,
+
+    /// The amount of the sold token that was filled and not claimed yet
+    pub filled_amount: u64,
+
+    // account update recent epoch
+    pub recent_epoch: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl LimitOrderState {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 4 + 1 + 8 + 8 + 16 + 8 + 8 + 8 * 4;
+
+    /// Brings the order up to date with the fills the tick has gone through since the last update.
+    /// The unfilled amount is rounded up, so an order is never credited more than was filled
+    pub fn settle(&mut self, tick_state: &TickState) -> Result<()> {
+        let remaining_ratio_x64 = tick_state.order_remaining_ratio_x64();
+        if self.unfilled_amount != 0 {
+            let unfilled_amount = if self.order_phase == tick_state.order_phase {
+                U256::from(self.unfilled_amount)
+                    .mul_div_ceil(
+                        U256::from(remaining_ratio_x64),
+                        U256::from(self.remaining_ratio_snapshot_x64),
+                    )
+                    .unwrap()
+                    .as_u64()
+                    .min(self.unfilled_amount)
+                    .min(tick_state.order_amount)
+            } else {
+                // all orders of an earlier phase have been filled completely
+                0
+            };
+            self.filled_amount = self
+                .filled_amount
+                .checked_add(self.unfilled_amount - unfilled_amount)
+                .unwrap();
+            self.unfilled_amount = unfilled_amount;
+        }
+        self.order_phase = tick_state.order_phase;
+        self.remaining_ratio_snapshot_x64 = remaining_ratio_x64;
+        self.recent_epoch = get_recent_epoch()?;
+        Ok(())
+    }
+
+    /// The amount of the bought token the filled part of the order is worth
+    pub fn filled_proceeds(&self, sqrt_price_x64: u128) -> u64 {
+        if self.zero_for_one {
+            get_amount_1_for_amount_0(self.filled_amount, sqrt_price_x64, false)
+        } else {
+            get_amount_0_for_amount_1(self.filled_amount, sqrt_price_x64, false)
+        }
+    }
+}
+
+/// Converts an amount of token_0 into token_1 at the price `sqrt_price_x64`, saturating at u64::MAX
+pub fn get_amount_1_for_amount_0(amount_0: u64, sqrt_price_x64: u128, round_up: bool) -> u64 {
+    let price_x128 = U256::from(sqrt_price_x64) * U256::from(sqrt_price_x64);
+    let denominator = U256::from(fixed_point_64::Q64) * U256::from(fixed_point_64::Q64);
+    let amount_1 = if round_up {
+        U256::from(amount_0).mul_div_ceil(price_x128, denominator)
+    } else {
+        U256::from(amount_0).mul_div_floor(price_x128, denominator)
+    }
+    .unwrap();
+    amount_1.min(U256::from(u64::MAX)).as_u64()
+}
+
+/// Converts an amount of token_1 into token_0 at the price `sqrt_price_x64`, saturating at u64::MAX
+pub fn get_amount_0_for_amount_1(amount_1: u64, sqrt_price_x64: u128, round_up: bool) -> u64 {
+    let price_x128 = U256::from(sqrt_price_x64) * U256::from(sqrt_price_x64);
+    let numerator = U256::from(fixed_point_64::Q64) * U256::from(fixed_point_64::Q64);
+    let amount_0 = if round_up {
+        U256::from(amount_1).mul_div_ceil(numerator, price_x128)
+    } else {
+        U256::from(amount_1).mul_div_floor(numerator, price_x128)
+    }
+    .unwrap();
+    amount_0.min(U256::from(u64::MAX)).as_u64()
+}
+
+/// Emitted when a limit order is opened or increased
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct OpenLimitOrderEvent {
+    /// The pool the order is placed in
+    pub pool_state: Pubkey,
+    /// The owner of the order
+    pub owner: Pubkey,
+    /// The tick the order rests on
+    pub tick_index: i32,
+    /// true: sells token_0 for token_1, false: sells token_1 for token_0
+    pub zero_for_one: bool,
+    /// The amount added to the order, after transfer fee
+    pub amount: u64,
+    /// The token transfer fee for amount
+    pub transfer_fee: u64,
+}
+
+/// Emitted when a limit order is cancelled
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CancelLimitOrderEvent {
+    /// The pool the order is placed in
+    pub pool_state: Pubkey,
+    /// The owner of the order
+    pub owner: Pubkey,
+    /// The tick the order rests on
+    pub tick_index: i32,
+    /// true: sells token_0 for token_1, false: sells token_1 for token_0
+    pub zero_for_one: bool,
+    /// The unfilled amount of the sold token returned to the owner
+    pub unfilled_amount: u64,
+    /// The amount of the bought token paid for the filled part of the order
+    pub proceeds_amount: u64,
+}
+
+/// Emitted when the proceeds of a limit order are claimed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ClaimLimitOrderEvent {
+    /// The pool the order is placed in
+    pub pool_state: Pubkey,
+    /// The owner of the order
+    pub owner: Pubkey,
+    /// The tick the order rests on
+    pub tick_index: i32,
+    /// true: sells token_0 for token_1, false: sells token_1 for token_0
+    pub zero_for_one: bool,
+    /// The amount of the sold token that was filled
+    pub filled_amount: u64,
+    /// The amount of the bought token paid to the owner
+    pub proceeds_amount: u64,
+    /// Whether the order was completely filled and closed
+    pub closed: bool,
+}
+
+#[cfg(test)]
+mod limit_order_test {
+    use super::*;
+    use crate::libraries::tick_math;
+    use std::convert::identity;
+
+    #[test]
+    fn price_conversion_test() {
+        // price 1
+        let sqrt_price_x64 = fixed_point_64::Q64;
+        assert_eq!(get_amount_1_for_amount_0(1000, sqrt_price_x64, false), 1000);
+        assert_eq!(get_amount_0_for_amount_1(1000, sqrt_price_x64, true), 1000);
+
+        // price 4
+        let sqrt_price_x64 = 2 * fixed_point_64::Q64;
+        assert_eq!(get_amount_1_for_amount_0(1000, sqrt_price_x64, false), 4000);
+        assert_eq!(get_amount_0_for_amount_1(1001, sqrt_price_x64, false), 250);
+        assert_eq!(get_amount_0_for_amount_1(1001, sqrt_price_x64, true), 251);
+
+        // saturates instead of overflowing
+        assert_eq!(
+            get_amount_1_for_amount_0(u64::MAX, tick_math::MAX_SQRT_PRICE_X64, false),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn settle_partial_fill_test() {
+        let mut tick_state = TickState::default();
+        tick_state.order_amount = 1000;
+        let mut order = LimitOrderState::default();
+        order.settle(&tick_state).unwrap();
+        order.unfilled_amount = 400;
+        assert_eq!(order.remaining_ratio_snapshot_x64, fixed_point_64::Q64);
+
+        // a quarter of the tick orders are filled
+        tick_state.consume_orders(250, true);
+        order.settle(&tick_state).unwrap();
+        assert_eq!(order.unfilled_amount, 300);
+        assert_eq!(order.filled_amount, 100);
+
+        // settling again without fills changes nothing
+        order.settle(&tick_state).unwrap();
+        assert_eq!(order.unfilled_amount, 300);
+        assert_eq!(order.filled_amount, 100);
+    }
+
+    #[test]
+    fn settle_full_fill_test() {
+        let mut tick_state = TickState::default();
+        tick_state.order_amount = 1000;
+        let mut order = LimitOrderState::default();
+        order.settle(&tick_state).unwrap();
+        order.unfilled_amount = 1000;
+
+        tick_state.consume_orders(1000, true);
+        assert_eq!(identity(tick_state.order_amount), 0);
+        assert_eq!(identity(tick_state.order_phase), 1);
+
+        // new orders in the next phase do not revive the filled order
+        tick_state.order_amount = 500;
+        order.settle(&tick_state).unwrap();
+        assert_eq!(order.unfilled_amount, 0);
+        assert_eq!(order.filled_amount, 1000);
+        assert_eq!(order.order_phase, 1);
+    }
+
+    #[test]
+    fn settle_many_orders_test() {
+        // orders selling token_0 on a tick above the current one, filled by one_for_zero swaps
+        let tick = 1000;
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick).unwrap();
+        let mut tick_state = TickState::default();
+        tick_state.tick = tick;
+
+        let open_order = |tick_state: &mut TickState, amount: u64| {
+            let mut order = LimitOrderState::default();
+            order.zero_for_one = true;
+            order.settle(tick_state).unwrap();
+            order.unfilled_amount = amount;
+            tick_state.order_amount += amount;
+            tick_state.order_count += 1;
+            order
+        };
+        let mut orders: Vec<LimitOrderState> = [1, 3, 7, 13, 999, 1001, 12345, 77777]
+            .iter()
+            .map(|amount| open_order(&mut tick_state, *amount))
+            .collect();
+
+        let mut swap_amount_in = 0;
+        let mut swap_amount_out = 0;
+        for (i, amount) in [17u64, 333, 5, 4096, 1].iter().enumerate() {
+            let (amount_in, amount_out) = tick_state.fill_orders(*amount, false, true).unwrap();
+            swap_amount_in += amount_in;
+            swap_amount_out += amount_out;
+            // more orders join the phase while it is partly filled
+            if i % 2 == 0 {
+                orders.push(open_order(&mut tick_state, 3 + i as u64 * 1000));
+            }
+        }
+        assert_eq!(identity(tick_state.order_phase), 0);
+
+        let mut filled_amount = 0;
+        let mut proceeds_amount = 0;
+        let mut unfilled_amount = 0;
+        for order in orders.iter_mut() {
+            order.settle(&tick_state).unwrap();
+            filled_amount += order.filled_amount;
+            proceeds_amount += order.filled_proceeds(sqrt_price_x64);
+            unfilled_amount += order.unfilled_amount;
+        }
+        assert!(filled_amount <= swap_amount_out);
+        assert!(proceeds_amount <= swap_amount_in);
+        assert!(unfilled_amount >= identity(tick_state.order_amount));
+    }
+}
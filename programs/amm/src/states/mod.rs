@@ -1,19 +1,29 @@
 pub mod config;
+pub mod limit_order;
 pub mod operation_account;
 pub mod oracle;
 pub mod personal_position;
 pub mod pool;
+pub mod position_delegate;
 pub mod protocol_position;
+pub mod reward_extension;
+pub mod reward_funding;
+pub mod reward_schedule;
 pub mod support_mint_associated;
 pub mod tick_array;
 pub mod tickarray_bitmap_extension;
 
 pub use config::*;
+pub use limit_order::*;
 pub use operation_account::*;
 pub use oracle::*;
 pub use personal_position::*;
 pub use pool::*;
+pub use position_delegate::*;
 pub use protocol_position::*;
+pub use reward_extension::*;
+pub use reward_funding::*;
+pub use reward_schedule::*;
 pub use support_mint_associated::*;
 pub use tick_array::*;
 pub use tickarray_bitmap_extension::*;
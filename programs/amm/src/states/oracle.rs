@@ -2,13 +2,19 @@
 ///
 use anchor_lang::prelude::*;
 
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv, tick_math};
 use crate::util::get_recent_epoch;
+use std::cell::{Ref, RefMut};
+use std::mem;
 
 /// Seed to derive account address and signature
 pub const OBSERVATION_SEED: &str = "observation";
 // Number of ObservationState element
 pub const OBSERVATION_NUM: usize = 100;
 pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u32 = 15;
+// The longest the oracle can go without an observation, one hour
+pub const MAX_OBSERVATION_UPDATE_DURATION: u32 = 60 * 60;
 
 /// The element of observations in ObservationState
 #[zero_copy(unsafe)]
@@ -19,14 +25,21 @@ pub struct Observation {
     pub block_timestamp: u32,
     /// the cumulative of tick during the duration time
     pub tick_cumulative: i64,
+    /// the cumulative of seconds per in-range liquidity during the duration time, Q64.64
+    pub seconds_per_liquidity_cumulative_x64: u128,
     /// padding for feature update
-    pub padding: [u64; 4],
+    pub padding: [u64; 2],
 }
 
 impl Observation {
-    pub const LEN: usize = 4 + 8 + 8 * 4;
+    pub const LEN: usize = 4 + 8 + 16 + 8 * 2;
 }
 
+// the observations beyond OBSERVATION_NUM are cast from the bytes after the account struct, which is
+// sound as the struct is packed and consists of plain integers only
+unsafe impl bytemuck::Zeroable for Observation {}
+unsafe impl bytemuck::Pod for Observation {}
+
 #[account(zero_copy(unsafe))]
 #[repr(C, packed)]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -39,10 +52,16 @@ pub struct ObservationState {
     pub observation_index: u16,
     /// belongs to which pool
     pub pool_id: Pubkey,
-    /// observation array
+    /// observation array, the observations beyond OBSERVATION_NUM are stored after the account struct
     pub observations: [Observation; OBSERVATION_NUM],
+    /// the number of observations in use, OBSERVATION_NUM if not set
+    pub observation_cardinality: u16,
+    /// the number of observations the account has been grown to, taken into use once the index wraps around
+    pub observation_cardinality_next: u16,
+    /// the minimum seconds between two observations, OBSERVATION_UPDATE_DURATION_DEFAULT if not set
+    pub observation_update_duration: u32,
     /// padding for feature update
-    pub padding: [u64; 4],
+    pub padding: [u64; 3],
 }
 
 impl Default for ObservationState {
@@ -54,13 +73,23 @@ impl Default for ObservationState {
             observation_index: 0,
             pool_id: Pubkey::default(),
             observations: [Observation::default(); OBSERVATION_NUM],
-            padding: [0u64; 4],
+            observation_cardinality: 0,
+            observation_cardinality_next: 0,
+            observation_update_duration: 0,
+            padding: [0u64; 3],
         }
     }
 }
 
 impl ObservationState {
-    pub const LEN: usize = 8 + 1 + 8 + 2 + 32 + (Observation::LEN * OBSERVATION_NUM) + 8 * 4;
+    pub const LEN: usize =
+        8 + 1 + 8 + 2 + 32 + (Observation::LEN * OBSERVATION_NUM) + 2 + 2 + 4 + 8 * 3;
+
+    /// The account size for `observation_cardinality` observations
+    pub fn len_for_cardinality(observation_cardinality: u16) -> usize {
+        ObservationState::LEN
+            + (observation_cardinality as usize).saturating_sub(OBSERVATION_NUM) * Observation::LEN
+    }
 
     pub fn initialize(&mut self, pool_id: Pubkey) -> Result<()> {
         self.initialized = false;
@@ -68,43 +97,530 @@ impl ObservationState {
         self.observation_index = 0;
         self.pool_id = pool_id;
         self.observations = [Observation::default(); OBSERVATION_NUM];
-        self.padding = [0u64; 4];
+        self.observation_cardinality = OBSERVATION_NUM as u16;
+        self.observation_cardinality_next = OBSERVATION_NUM as u16;
+        self.observation_update_duration = OBSERVATION_UPDATE_DURATION_DEFAULT;
+        self.padding = [0u64; 3];
+        Ok(())
+    }
+
+    /// Borrows the observation account together with the observations stored after the account
+    /// struct once it has been grown beyond OBSERVATION_NUM
+    pub fn load_extended<'a>(
+        acc_info: &'a AccountInfo,
+    ) -> Result<(Ref<'a, ObservationState>, Ref<'a, [Observation]>)> {
+        ObservationState::check_account(acc_info)?;
+        let data = acc_info.try_borrow_data()?;
+        Ok(Ref::map_split(data, |data| {
+            let (state, extended) = data[8..].split_at(mem::size_of::<ObservationState>());
+            (
+                bytemuck::from_bytes(state),
+                bytemuck::cast_slice(
+                    &extended[..extended.len() / Observation::LEN * Observation::LEN],
+                ),
+            )
+        }))
+    }
+
+    /// Mutably borrows the observation account together with the observations stored after the
+    /// account struct once it has been grown beyond OBSERVATION_NUM
+    pub fn load_extended_mut<'a>(
+        acc_info: &'a AccountInfo,
+    ) -> Result<(RefMut<'a, ObservationState>, RefMut<'a, [Observation]>)> {
+        ObservationState::check_account(acc_info)?;
+        if !acc_info.is_writable {
+            return err!(anchor_lang::error::ErrorCode::AccountNotMutable);
+        }
+        let data = acc_info.try_borrow_mut_data()?;
+        Ok(RefMut::map_split(data, |data| {
+            let (state, extended) = data[8..].split_at_mut(mem::size_of::<ObservationState>());
+            let extended_len = extended.len() / Observation::LEN * Observation::LEN;
+            (
+                bytemuck::from_bytes_mut(state),
+                bytemuck::cast_slice_mut(&mut extended[..extended_len]),
+            )
+        }))
+    }
+
+    fn check_account(acc_info: &AccountInfo) -> Result<()> {
+        if acc_info.owner != &crate::id() {
+            return Err(
+                Error::from(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram)
+                    .with_pubkeys((*acc_info.owner, crate::id())),
+            );
+        }
+        let data = acc_info.try_borrow_data()?;
+        if data.len() < ObservationState::LEN {
+            return err!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize);
+        }
+        if &data[..8] != ObservationState::DISCRIMINATOR {
+            return err!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+        }
         Ok(())
     }
 
+    /// The number of observations in use
+    pub fn cardinality(&self) -> usize {
+        if self.observation_cardinality == 0 {
+            OBSERVATION_NUM
+        } else {
+            self.observation_cardinality as usize
+        }
+    }
+
+    /// The number of observations the account has room for
+    pub fn cardinality_next(&self) -> usize {
+        (self.observation_cardinality_next as usize).max(self.cardinality())
+    }
+
+    /// The minimum seconds between two observations
+    pub fn update_duration(&self) -> u32 {
+        if self.observation_update_duration == 0 {
+            OBSERVATION_UPDATE_DURATION_DEFAULT
+        } else {
+            self.observation_update_duration
+        }
+    }
+
+    fn observation(&self, extended_observations: &[Observation], index: usize) -> Observation {
+        if index < OBSERVATION_NUM {
+            self.observations[index]
+        } else {
+            extended_observations[index - OBSERVATION_NUM]
+        }
+    }
+
+    fn observation_mut<'a>(
+        &'a mut self,
+        extended_observations: &'a mut [Observation],
+        index: usize,
+    ) -> &'a mut Observation {
+        if index < OBSERVATION_NUM {
+            &mut self.observations[index]
+        } else {
+            &mut extended_observations[index - OBSERVATION_NUM]
+        }
+    }
+
     /// Writes an oracle observation to the account
     ///
     /// # Arguments
     ///
     /// * `self` - The ObservationState account to write in
+    /// * `extended_observations` - The observations stored after the account struct
     /// * `block_timestamp` - The current timestamp of to update
+    /// * `tick` - The tick of the pool since the last observation
+    /// * `liquidity` - The in-range liquidity of the pool since the last observation
     ///
-    pub fn update(&mut self, block_timestamp: u32, tick: i32) {
-        let observation_index = self.observation_index;
+    pub fn update(
+        &mut self,
+        extended_observations: &mut [Observation],
+        block_timestamp: u32,
+        tick: i32,
+        liquidity: u128,
+    ) {
+        let observation_index = self.observation_index as usize;
         if !self.initialized {
             self.initialized = true;
-            self.observations[observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[observation_index as usize].tick_cumulative = 0;
+            let observation = self.observation_mut(extended_observations, observation_index);
+            observation.block_timestamp = block_timestamp;
+            observation.tick_cumulative = 0;
+            observation.seconds_per_liquidity_cumulative_x64 = 0;
         } else {
-            let last_observation = self.observations[observation_index as usize];
+            let last_observation = self.observation(extended_observations, observation_index);
             let delta_time = block_timestamp.saturating_sub(last_observation.block_timestamp);
-            if delta_time < OBSERVATION_UPDATE_DURATION_DEFAULT {
+            if delta_time < self.update_duration() {
                 return;
             }
 
+            // the grown observations are only taken into use at the end of the array, so that the
+            // observations stay in chronological order from the oldest one
+            let mut cardinality = self.cardinality();
+            if observation_index == cardinality - 1 {
+                cardinality = self
+                    .cardinality_next()
+                    .min(OBSERVATION_NUM + extended_observations.len());
+                self.observation_cardinality = cardinality as u16;
+            }
+
             let delta_tick_cumulative = i64::from(tick).checked_mul(delta_time.into()).unwrap();
-            let next_observation_index = if observation_index as usize == OBSERVATION_NUM - 1 {
-                0
-            } else {
-                observation_index + 1
-            };
-            self.observations[next_observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[next_observation_index as usize].tick_cumulative = last_observation
+            let next_observation_index = (observation_index + 1) % cardinality;
+            let next_observation =
+                self.observation_mut(extended_observations, next_observation_index);
+            next_observation.block_timestamp = block_timestamp;
+            next_observation.tick_cumulative = last_observation
                 .tick_cumulative
                 .wrapping_add(delta_tick_cumulative);
-            self.observation_index = next_observation_index;
+            next_observation.seconds_per_liquidity_cumulative_x64 = last_observation
+                .seconds_per_liquidity_cumulative_x64
+                .wrapping_add(get_seconds_per_liquidity_x64(delta_time, liquidity));
+            self.observation_index = next_observation_index as u16;
+        }
+    }
+
+    /// Returns the seconds per liquidity cumulative as of `block_timestamp`, extrapolated from the
+    /// last observation with the in-range `liquidity` since, zero before the first observation
+    pub fn get_seconds_per_liquidity_cumulative_x64(
+        &self,
+        extended_observations: &[Observation],
+        block_timestamp: u32,
+        liquidity: u128,
+    ) -> u128 {
+        if !self.initialized {
+            return 0;
+        }
+        let last_observation =
+            self.observation(extended_observations, self.observation_index as usize);
+        last_observation
+            .seconds_per_liquidity_cumulative_x64
+            .wrapping_add(get_seconds_per_liquidity_x64(
+                block_timestamp.saturating_sub(last_observation.block_timestamp),
+                liquidity,
+            ))
+    }
+
+    /// Returns the absolute deviation between `tick_current` and the time-weighted average tick
+    /// over the recent `period` seconds, which is used as the volatility measure of the pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `extended_observations` - The observations stored after the account struct
+    /// * `block_timestamp` - The current timestamp
+    /// * `tick_current` - The current tick of the pool
+    /// * `period` - The window in seconds of the average tick
+    ///
+    pub fn get_tick_deviation(
+        &self,
+        extended_observations: &[Observation],
+        block_timestamp: u32,
+        tick_current: i32,
+        period: u32,
+    ) -> u32 {
+        self.get_recent_average_tick(extended_observations, block_timestamp, period)
+            .map_or(0, |average_tick| {
+                (i64::from(tick_current) - i64::from(average_tick)).unsigned_abs() as u32
+            })
+    }
+
+    /// Returns the time-weighted average tick between the written observations of the recent
+    /// `period` seconds, or of all the observations kept if they span less, rounded towards zero.
+    /// The current tick is not extrapolated, so a tick moved since the last observation does not
+    /// count towards its own average. None if fewer than two observations fall in the window.
+    ///
+    /// # Arguments
+    ///
+    /// * `extended_observations` - The observations stored after the account struct
+    /// * `block_timestamp` - The current timestamp
+    /// * `period` - The window in seconds of the average tick
+    ///
+    pub fn get_recent_average_tick(
+        &self,
+        extended_observations: &[Observation],
+        block_timestamp: u32,
+        period: u32,
+    ) -> Option<i32> {
+        if !self.initialized || period == 0 {
+            return None;
         }
+        self.get_average_tick_back_from(
+            extended_observations,
+            self.observation_index as usize,
+            block_timestamp.saturating_sub(period),
+        )
     }
+
+    /// Same as `get_recent_average_tick`, but leaves out the observation written at
+    /// `block_timestamp` if any, so that the average only covers observations settled before the
+    /// current block and can not be moved by an earlier transaction of the same block.
+    ///
+    /// # Arguments
+    ///
+    /// * `extended_observations` - The observations stored after the account struct
+    /// * `block_timestamp` - The current timestamp
+    /// * `period` - The window in seconds of the average tick
+    ///
+    pub fn get_settled_average_tick(
+        &self,
+        extended_observations: &[Observation],
+        block_timestamp: u32,
+        period: u32,
+    ) -> Option<i32> {
+        if !self.initialized || period == 0 {
+            return None;
+        }
+        let mut end_index = self.observation_index as usize;
+        let end_observation = self.observation(extended_observations, end_index);
+        if end_observation.block_timestamp >= block_timestamp {
+            // at most one observation is written per block, the previous one is settled
+            let cardinality = self.cardinality();
+            end_index = if end_index == 0 {
+                cardinality - 1
+            } else {
+                end_index - 1
+            };
+            let observation = self.observation(extended_observations, end_index);
+            if cardinality == 1
+                || observation.block_timestamp == 0
+                || observation.block_timestamp > end_observation.block_timestamp
+            {
+                return None;
+            }
+        }
+        self.get_average_tick_back_from(
+            extended_observations,
+            end_index,
+            block_timestamp.saturating_sub(period),
+        )
+    }
+
+    /// Returns the time-weighted average tick between the observation at `end_index` and the
+    /// newest observation before it not later than `target_timestamp`, or the oldest one if the
+    /// buffer is not long enough, rounded towards zero. None if they are the same observation.
+    fn get_average_tick_back_from(
+        &self,
+        extended_observations: &[Observation],
+        end_index: usize,
+        target_timestamp: u32,
+    ) -> Option<i32> {
+        let cardinality = self.cardinality();
+        let end_observation = self.observation(extended_observations, end_index);
+
+        let mut start_observation = end_observation;
+        let mut index = end_index;
+        for _ in 1..cardinality {
+            if start_observation.block_timestamp <= target_timestamp {
+                break;
+            }
+            index = if index == 0 {
+                cardinality - 1
+            } else {
+                index - 1
+            };
+            let observation = self.observation(extended_observations, index);
+            if observation.block_timestamp == 0
+                || observation.block_timestamp > start_observation.block_timestamp
+            {
+                break;
+            }
+            start_observation = observation;
+        }
+
+        let delta_time = end_observation
+            .block_timestamp
+            .saturating_sub(start_observation.block_timestamp);
+        if delta_time == 0 {
+            return None;
+        }
+        let average_tick = end_observation
+            .tick_cumulative
+            .wrapping_sub(start_observation.tick_cumulative)
+            .checked_div(i64::from(delta_time))
+            .unwrap();
+        Some(average_tick as i32)
+    }
+
+    /// Returns the tick and seconds per liquidity cumulatives as of each `seconds_agos` before
+    /// `block_timestamp`, together with the average tick and price over the window between the
+    /// first and the last of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `extended_observations` - The observations stored after the account struct
+    /// * `block_timestamp` - The current timestamp
+    /// * `tick_current` - The current tick of the pool
+    /// * `liquidity` - The current in-range liquidity of the pool
+    /// * `seconds_agos` - How many seconds ago to read the cumulatives at, e.g. [600, 0]
+    ///
+    pub fn observe(
+        &self,
+        extended_observations: &[Observation],
+        block_timestamp: u32,
+        tick_current: i32,
+        liquidity: u128,
+        seconds_agos: &[u32],
+    ) -> Result<ObserveResult> {
+        require!(self.initialized, ErrorCode::ObservationNotInitialized);
+        let (first_seconds_ago, last_seconds_ago) =
+            match (seconds_agos.first(), seconds_agos.last()) {
+                (Some(first), Some(last)) if first != last => (*first, *last),
+                _ => return err!(ErrorCode::InvalidSecondsAgos),
+            };
+
+        let mut tick_cumulatives = Vec::with_capacity(seconds_agos.len());
+        let mut seconds_per_liquidity_cumulatives_x64 = Vec::with_capacity(seconds_agos.len());
+        for seconds_ago in seconds_agos {
+            let target_timestamp = block_timestamp
+                .checked_sub(*seconds_ago)
+                .ok_or(ErrorCode::ObservationTooOld)?;
+            let (tick_cumulative, seconds_per_liquidity_cumulative_x64) = self.observe_single(
+                extended_observations,
+                target_timestamp,
+                tick_current,
+                liquidity,
+            )?;
+            tick_cumulatives.push(tick_cumulative);
+            seconds_per_liquidity_cumulatives_x64.push(seconds_per_liquidity_cumulative_x64);
+        }
+
+        let (tick_cumulative_start, tick_cumulative_end, delta_time) =
+            if first_seconds_ago > last_seconds_ago {
+                (
+                    tick_cumulatives[0],
+                    tick_cumulatives[seconds_agos.len() - 1],
+                    first_seconds_ago - last_seconds_ago,
+                )
+            } else {
+                (
+                    tick_cumulatives[seconds_agos.len() - 1],
+                    tick_cumulatives[0],
+                    last_seconds_ago - first_seconds_ago,
+                )
+            };
+        let average_tick =
+            get_average_tick(tick_cumulative_start, tick_cumulative_end, delta_time)?;
+        Ok(ObserveResult {
+            tick_cumulatives,
+            seconds_per_liquidity_cumulatives_x64,
+            average_tick,
+            average_sqrt_price_x64: tick_math::get_sqrt_price_at_tick(average_tick)?,
+        })
+    }
+
+    /// Returns the tick and seconds per liquidity cumulatives at `target_timestamp`, interpolated
+    /// between the two observations around it, or extrapolated with `tick_current` and `liquidity`
+    /// if it is later than the latest observation
+    fn observe_single(
+        &self,
+        extended_observations: &[Observation],
+        target_timestamp: u32,
+        tick_current: i32,
+        liquidity: u128,
+    ) -> Result<(i64, u128)> {
+        let cardinality = self.cardinality();
+        let observation_index = self.observation_index as usize;
+        let last_observation = self.observation(extended_observations, observation_index);
+        if target_timestamp >= last_observation.block_timestamp {
+            let delta_time = target_timestamp - last_observation.block_timestamp;
+            return Ok((
+                last_observation
+                    .tick_cumulative
+                    .wrapping_add(i64::from(tick_current) * i64::from(delta_time)),
+                last_observation
+                    .seconds_per_liquidity_cumulative_x64
+                    .wrapping_add(get_seconds_per_liquidity_x64(delta_time, liquidity)),
+            ));
+        }
+
+        // the oldest observation is next to the latest one once the buffer has wrapped around
+        let mut oldest_index = (observation_index + 1) % cardinality;
+        if self
+            .observation(extended_observations, oldest_index)
+            .block_timestamp
+            == 0
+        {
+            oldest_index = 0;
+        }
+        require_gte!(
+            target_timestamp,
+            self.observation(extended_observations, oldest_index)
+                .block_timestamp,
+            ErrorCode::ObservationTooOld
+        );
+        let observation_count = if oldest_index == 0 {
+            observation_index + 1
+        } else {
+            cardinality
+        };
+
+        // binary search the observations in chronological order for the two around the target
+        let observation_at = |position: usize| {
+            self.observation(
+                extended_observations,
+                (oldest_index + position) % cardinality,
+            )
+        };
+        let mut low = 0;
+        let mut high = observation_count - 1;
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if observation_at(middle).block_timestamp <= target_timestamp {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        let before = observation_at(low);
+        let after = observation_at(high);
+        let target_delta = target_timestamp - before.block_timestamp;
+        let observation_delta = after.block_timestamp - before.block_timestamp;
+        let delta_tick_cumulative =
+            i128::from(after.tick_cumulative.wrapping_sub(before.tick_cumulative));
+        let interpolated_tick_cumulative =
+            delta_tick_cumulative * i128::from(target_delta) / i128::from(observation_delta);
+        // the seconds per liquidity in between is assumed to accrue evenly
+        let interpolated_seconds_per_liquidity_x64 = U256::from(
+            after
+                .seconds_per_liquidity_cumulative_x64
+                .wrapping_sub(before.seconds_per_liquidity_cumulative_x64),
+        )
+        .mul_div_floor(U256::from(target_delta), U256::from(observation_delta))
+        .unwrap()
+        .as_u128();
+        Ok((
+            before
+                .tick_cumulative
+                .wrapping_add(interpolated_tick_cumulative as i64),
+            before
+                .seconds_per_liquidity_cumulative_x64
+                .wrapping_add(interpolated_seconds_per_liquidity_x64),
+        ))
+    }
+}
+
+/// The result of an oracle query
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObserveResult {
+    /// The tick cumulative as of each `seconds_agos`
+    pub tick_cumulatives: Vec<i64>,
+    /// The seconds per in-range liquidity cumulative as of each `seconds_agos`, Q64.64
+    pub seconds_per_liquidity_cumulatives_x64: Vec<u128>,
+    /// The time-weighted average tick over the window between the first and the last `seconds_agos`
+    pub average_tick: i32,
+    /// The sqrt price of the average tick, Q64.64
+    pub average_sqrt_price_x64: u128,
+}
+
+/// Emitted when the minimum seconds between two observations of a pool is set
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ObservationDurationChangeEvent {
+    /// The pool of the observation account
+    pub pool_state: Pubkey,
+
+    /// The minimum seconds between two observations
+    pub update_duration: u32,
+}
+
+/// Returns the time-weighted average tick between two tick cumulatives `delta_time` seconds apart,
+/// rounded towards negative infinity. Fails if they are not apart.
+pub fn get_average_tick(
+    tick_cumulative_start: i64,
+    tick_cumulative_end: i64,
+    delta_time: u32,
+) -> Result<i32> {
+    require!(delta_time != 0, ErrorCode::InvalidSecondsAgos);
+    let delta_tick_cumulative = tick_cumulative_end.wrapping_sub(tick_cumulative_start);
+    let mut average_tick = delta_tick_cumulative / i64::from(delta_time);
+    if delta_tick_cumulative < 0 && delta_tick_cumulative % i64::from(delta_time) != 0 {
+        average_tick -= 1;
+    }
+    Ok(average_tick as i32)
+}
+
+/// Returns the seconds per liquidity accrued over `delta_time` seconds, Q64.64. An empty pool is
+/// treated as having a liquidity of 1.
+pub fn get_seconds_per_liquidity_x64(delta_time: u32, liquidity: u128) -> u128 {
+    (u128::from(delta_time) << fixed_point_64::RESOLUTION) / liquidity.max(1)
 }
 
 /// Returns the block timestamp truncated to 32 bits, i.e. mod 2**32
@@ -122,6 +638,371 @@ pub fn block_timestamp_mock() -> u64 {
         .as_secs()
 }
 
+#[cfg(test)]
+pub mod tick_deviation_test {
+    use super::*;
+    use crate::states::{AmmConfig, PoolState};
+
+    fn build_observation_state(block_timestamp: u32, ticks: Vec<i32>) -> ObservationState {
+        let mut observation_state = ObservationState::default();
+        let mut timestamp = block_timestamp;
+        for tick in ticks {
+            observation_state.update(&mut [], timestamp, tick, 0);
+            timestamp += OBSERVATION_UPDATE_DURATION_DEFAULT;
+        }
+        observation_state
+    }
+
+    #[test]
+    fn stable_tick_has_no_deviation() {
+        let observation_state = build_observation_state(1000, vec![10, 10, 10, 10]);
+        assert_eq!(observation_state.get_tick_deviation(&[], 1060, 10, 60), 0);
+    }
+
+    #[test]
+    fn tick_deviation_from_average_test() {
+        // observations at 1000, 1015, 1030, 1045, the tick stayed at 0 until 1045
+        let observation_state = build_observation_state(1000, vec![0, 0, 0, 0]);
+        // the tick moves to 150 after 1045, the average tick only covers the written observations
+        assert_eq!(
+            observation_state.get_tick_deviation(&[], 1060, 150, 60),
+            150
+        );
+        // the window is longer than the history, use the oldest observation
+        assert_eq!(
+            observation_state.get_tick_deviation(&[], 1060, 150, 600),
+            150
+        );
+        // the window only covers the latest observation, no average
+        assert_eq!(observation_state.get_tick_deviation(&[], 1060, 150, 10), 0);
+        // disabled
+        assert_eq!(observation_state.get_tick_deviation(&[], 1060, 150, 0), 0);
+    }
+
+    #[test]
+    fn recent_average_tick_ignores_current_tick_test() {
+        // the tick was 0 over [1000, 1015] and 150 over [1015, 1045]
+        let observation_state = build_observation_state(1000, vec![0, 0, 150, 150]);
+        assert_eq!(
+            observation_state.get_recent_average_tick(&[], 1060, 60),
+            Some(150 * 30 / 45)
+        );
+        // the window starts at the observation of 1030
+        assert_eq!(
+            observation_state.get_recent_average_tick(&[], 1060, 30),
+            Some(150)
+        );
+        // the same as of any later timestamp, until the next observation is written
+        assert_eq!(
+            observation_state.get_recent_average_tick(&[], 1090, 60),
+            Some(150)
+        );
+        assert_eq!(
+            observation_state.get_recent_average_tick(&[], 1060, 10),
+            None
+        );
+        assert_eq!(
+            ObservationState::default().get_recent_average_tick(&[], 1060, 60),
+            None
+        );
+    }
+
+    #[test]
+    fn settled_average_tick_test() {
+        // the tick was 0 over [1000, 1015] and 150 over [1015, 1045]
+        let observation_state = build_observation_state(1000, vec![0, 0, 150, 150]);
+        // the observation of 1045 is settled in a later block
+        assert_eq!(
+            observation_state.get_settled_average_tick(&[], 1060, 60),
+            observation_state.get_recent_average_tick(&[], 1060, 60)
+        );
+        // the observation of 1045 is written in the current block, the average ends at 1030
+        assert_eq!(
+            observation_state.get_settled_average_tick(&[], 1045, 45),
+            Some(150 * 15 / 30)
+        );
+        assert_eq!(
+            observation_state.get_settled_average_tick(&[], 1045, 30),
+            Some(150)
+        );
+        assert_eq!(
+            observation_state.get_settled_average_tick(&[], 1045, 15),
+            None
+        );
+        // only the observation of the current block
+        let observation_state = build_observation_state(1000, vec![0]);
+        assert_eq!(
+            observation_state.get_settled_average_tick(&[], 1000, 60),
+            None
+        );
+        assert_eq!(
+            ObservationState::default().get_settled_average_tick(&[], 1000, 60),
+            None
+        );
+    }
+
+    #[test]
+    fn dynamic_trade_fee_rate_test() {
+        let observation_state = build_observation_state(1000, vec![0, 0, 0, 0]);
+        let mut amm_config = AmmConfig {
+            trade_fee_rate: 2500,
+            ..Default::default()
+        };
+        let mut pool_state = PoolState::default();
+        pool_state.tick_current = 150;
+        assert_eq!(
+            pool_state.get_trade_fee_rate(&amm_config, &observation_state, &[], 1060),
+            2500
+        );
+
+        amm_config.dynamic_fee_max_rate = 10000;
+        amm_config.dynamic_fee_control = 10;
+        amm_config.dynamic_fee_decay_period = 60;
+        assert_eq!(
+            pool_state.get_trade_fee_rate(&amm_config, &observation_state, &[], 1060),
+            2500 + 150 * 10
+        );
+        // capped by max rate
+        amm_config.dynamic_fee_max_rate = 1000;
+        assert_eq!(
+            pool_state.get_trade_fee_rate(&amm_config, &observation_state, &[], 1060),
+            2500 + 1000
+        );
+    }
+
+    #[test]
+    fn pool_trade_fee_rate_override_test() {
+        let observation_state = build_observation_state(1000, vec![0, 0, 0, 0]);
+        let amm_config = AmmConfig {
+            trade_fee_rate: 2500,
+            dynamic_fee_max_rate: 10000,
+            dynamic_fee_control: 10,
+            dynamic_fee_decay_period: 60,
+            ..Default::default()
+        };
+        let mut pool_state = PoolState::default();
+        pool_state.tick_current = 150;
+        assert_eq!(
+            pool_state.get_trade_fee_rate(&amm_config, &observation_state, &[], 1060),
+            2500 + 150 * 10
+        );
+
+        // the override replaces the amm config rate, the dynamic fee still applies
+        pool_state.set_trade_fee_rate_override(Some(0));
+        assert_eq!(pool_state.base_trade_fee_rate(&amm_config), 0);
+        assert_eq!(
+            pool_state.get_trade_fee_rate(&amm_config, &observation_state, &[], 1060),
+            150 * 10
+        );
+
+        pool_state.set_trade_fee_rate_override(None);
+        assert_eq!(pool_state.base_trade_fee_rate(&amm_config), 2500);
+    }
+}
+
+#[cfg(test)]
+pub mod observe_test {
+    use super::*;
+    use std::convert::identity;
+
+    fn build_observation_state(updates: Vec<(u32, i32)>) -> ObservationState {
+        let mut observation_state = ObservationState::default();
+        for (block_timestamp, tick) in updates {
+            observation_state.update(&mut [], block_timestamp, tick, 1);
+        }
+        observation_state
+    }
+
+    #[test]
+    fn observe_interpolate_test() {
+        // tick 10 during [1000, 1015], tick 20 during [1015, 1030], tick 30 since then
+        let observation_state = build_observation_state(vec![(1000, 0), (1015, 10), (1030, 20)]);
+        let result = observation_state
+            .observe(&[], 1040, 30, 1, &[40, 25, 20, 0])
+            .unwrap();
+        assert_eq!(result.tick_cumulatives, vec![0, 150, 250, 750]);
+        assert_eq!(result.average_tick, 18);
+        assert_eq!(
+            result.average_sqrt_price_x64,
+            tick_math::get_sqrt_price_at_tick(18).unwrap()
+        );
+
+        // the order of the window does not matter
+        let result = observation_state
+            .observe(&[], 1040, 30, 1, &[0, 40])
+            .unwrap();
+        assert_eq!(result.tick_cumulatives, vec![750, 0]);
+        assert_eq!(result.average_tick, 18);
+    }
+
+    #[test]
+    fn observe_invalid_test() {
+        let observation_state = ObservationState::default();
+        assert!(observation_state
+            .observe(&[], 1040, 30, 1, &[40, 0])
+            .is_err());
+
+        let observation_state = build_observation_state(vec![(1000, 0), (1015, 10)]);
+        // older than the oldest observation
+        assert!(observation_state
+            .observe(&[], 1040, 30, 1, &[41, 0])
+            .is_err());
+        // no window
+        assert!(observation_state.observe(&[], 1040, 30, 1, &[0]).is_err());
+        assert!(observation_state
+            .observe(&[], 1040, 30, 1, &[10, 10])
+            .is_err());
+        assert!(observation_state.observe(&[], 1040, 30, 1, &[]).is_err());
+    }
+
+    #[test]
+    fn observe_wrapped_buffer_test() {
+        let updates = (0..150)
+            .map(|i| (1000 + i * OBSERVATION_UPDATE_DURATION_DEFAULT, 1))
+            .collect();
+        let observation_state = build_observation_state(updates);
+        let block_timestamp = 1000 + 149 * OBSERVATION_UPDATE_DURATION_DEFAULT;
+        // only the latest 100 observations are kept
+        let result = observation_state
+            .observe(
+                &[],
+                block_timestamp,
+                1,
+                1,
+                &[99 * OBSERVATION_UPDATE_DURATION_DEFAULT, 0],
+            )
+            .unwrap();
+        assert_eq!(result.tick_cumulatives, vec![750, 2235]);
+        assert_eq!(result.average_tick, 1);
+        assert!(observation_state
+            .observe(
+                &[],
+                block_timestamp,
+                1,
+                1,
+                &[99 * OBSERVATION_UPDATE_DURATION_DEFAULT + 1, 0]
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn observe_seconds_per_liquidity_test() {
+        use fixed_point_64::Q64;
+        // liquidity 4 during [1000, 1015], liquidity 16 during [1015, 1030], liquidity 2 since then
+        let mut observation_state = ObservationState::default();
+        observation_state.update(&mut [], 1000, 0, 0);
+        observation_state.update(&mut [], 1015, 0, 4);
+        observation_state.update(&mut [], 1030, 0, 16);
+        let result = observation_state
+            .observe(&[], 1040, 0, 2, &[40, 25, 20, 0])
+            .unwrap();
+        assert_eq!(
+            result.seconds_per_liquidity_cumulatives_x64,
+            vec![
+                0,
+                15 * Q64 / 4,
+                15 * Q64 / 4 + 5 * Q64 / 16,
+                15 * Q64 / 4 + 15 * Q64 / 16 + 10 * Q64 / 2
+            ]
+        );
+
+        // an empty pool is treated as having a liquidity of 1
+        assert_eq!(get_seconds_per_liquidity_x64(15, 0), 15 * Q64);
+        assert_eq!(get_seconds_per_liquidity_x64(15, 1), 15 * Q64);
+    }
+
+    #[test]
+    fn observe_grown_buffer_test() {
+        let mut observation_state = ObservationState::default();
+        let mut extended_observations = [Observation::default(); 50];
+        observation_state.observation_cardinality_next = (OBSERVATION_NUM + 50) as u16;
+        observation_state.observation_update_duration = 60;
+        for i in 0..200 {
+            observation_state.update(&mut extended_observations, 1000 + i * 60, 1, 1);
+        }
+        // the grown observations were taken into use when the index first reached the end
+        assert_eq!(observation_state.cardinality(), OBSERVATION_NUM + 50);
+        assert_eq!(identity(observation_state.observation_index), 49);
+
+        // 150 observations a minute apart cover more than 2 hours
+        let block_timestamp = 1000 + 199 * 60;
+        let result = observation_state
+            .observe(
+                &extended_observations,
+                block_timestamp,
+                1,
+                1,
+                &[149 * 60, 3600, 0],
+            )
+            .unwrap();
+        assert_eq!(result.tick_cumulatives, vec![50 * 60, 139 * 60, 199 * 60]);
+        assert_eq!(result.average_tick, 1);
+        assert!(observation_state
+            .observe(
+                &extended_observations,
+                block_timestamp,
+                1,
+                1,
+                &[149 * 60 + 1, 0]
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn update_duration_test() {
+        let mut observation_state = ObservationState::default();
+        assert_eq!(
+            observation_state.update_duration(),
+            OBSERVATION_UPDATE_DURATION_DEFAULT
+        );
+        observation_state.observation_update_duration = 60;
+        observation_state.update(&mut [], 1000, 1, 1);
+        observation_state.update(&mut [], 1059, 1, 1);
+        assert_eq!(identity(observation_state.observation_index), 0);
+        observation_state.update(&mut [], 1060, 1, 1);
+        assert_eq!(identity(observation_state.observation_index), 1);
+    }
+
+    #[test]
+    fn seconds_per_liquidity_cumulative_test() {
+        let mut observation_state = ObservationState::default();
+        assert_eq!(
+            observation_state.get_seconds_per_liquidity_cumulative_x64(&[], 1000, 1),
+            0
+        );
+        observation_state.update(&mut [], 1000, 1, 2);
+        observation_state.update(&mut [], 1100, 1, 2);
+        // extrapolated from the last observation with the liquidity since
+        assert_eq!(
+            observation_state.get_seconds_per_liquidity_cumulative_x64(&[], 1110, 4),
+            get_seconds_per_liquidity_x64(100, 2) + get_seconds_per_liquidity_x64(10, 4)
+        );
+    }
+
+    #[test]
+    fn len_for_cardinality_test() {
+        assert_eq!(
+            ObservationState::len_for_cardinality(OBSERVATION_NUM as u16),
+            ObservationState::LEN
+        );
+        assert_eq!(
+            ObservationState::len_for_cardinality(OBSERVATION_NUM as u16 + 10),
+            ObservationState::LEN + 10 * Observation::LEN
+        );
+    }
+
+    #[test]
+    fn get_average_tick_test() {
+        assert_eq!(get_average_tick(0, 7, 2).unwrap(), 3);
+        assert_eq!(get_average_tick(0, -7, 2).unwrap(), -4);
+        assert_eq!(get_average_tick(-10, -16, 3).unwrap(), -2);
+        assert_eq!(
+            get_average_tick(0, 7, 0).unwrap_err(),
+            ErrorCode::InvalidSecondsAgos.into()
+        );
+    }
+}
+
 #[cfg(test)]
 pub mod oracle_layout_test {
     use super::*;
@@ -132,12 +1013,10 @@ pub mod oracle_layout_test {
         let recent_epoch: u64 = 0x123456789abcdef0;
         let observation_index: u16 = 0x1122;
         let pool_id: Pubkey = Pubkey::new_unique();
-        let padding: [u64; 4] = [
-            0x123456789abcde0f,
-            0x123456789abcd0ef,
-            0x123456789abc0def,
-            0x123456789ab0cdef,
-        ];
+        let observation_cardinality: u16 = 0x3344;
+        let observation_cardinality_next: u16 = 0x5566;
+        let observation_update_duration: u32 = 0x778899aa;
+        let padding: [u64; 3] = [0x123456789abcde0f, 0x123456789abcd0ef, 0x123456789abc0def];
 
         let mut observation_datas = [0u8; Observation::LEN * OBSERVATION_NUM];
         let mut observations = [Observation::default(); OBSERVATION_NUM];
@@ -146,27 +1025,24 @@ pub mod oracle_layout_test {
             let index = i + 1;
             let block_timestamp: u32 = u32::MAX - 3 * index as u32;
             let tick_cumulative: i64 = i64::MAX - 3 * index as i64;
-            let padding: [u64; 4] = [
-                u64::MAX - index as u64,
-                u64::MAX - 2 * index as u64,
-                u64::MAX - 3 * index as u64,
-                u64::MAX - 4 * index as u64,
-            ];
+            let seconds_per_liquidity_cumulative_x64: u128 = u128::MAX - 5 * index as u128;
+            let padding: [u64; 2] = [u64::MAX - index as u64, u64::MAX - 2 * index as u64];
             observations[i].block_timestamp = block_timestamp;
             observations[i].tick_cumulative = tick_cumulative;
+            observations[i].seconds_per_liquidity_cumulative_x64 =
+                seconds_per_liquidity_cumulative_x64;
             observations[i].padding = padding;
             observation_datas[offset..offset + 4].copy_from_slice(&block_timestamp.to_le_bytes());
             offset += 4;
             observation_datas[offset..offset + 8].copy_from_slice(&tick_cumulative.to_le_bytes());
             offset += 8;
+            observation_datas[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_cumulative_x64.to_le_bytes());
+            offset += 16;
             observation_datas[offset..offset + 8].copy_from_slice(&padding[0].to_le_bytes());
             offset += 8;
             observation_datas[offset..offset + 8].copy_from_slice(&padding[1].to_le_bytes());
             offset += 8;
-            observation_datas[offset..offset + 8].copy_from_slice(&padding[2].to_le_bytes());
-            offset += 8;
-            observation_datas[offset..offset + 8].copy_from_slice(&padding[3].to_le_bytes());
-            offset += 8;
         }
 
         // serialize original data
@@ -188,14 +1064,21 @@ pub mod oracle_layout_test {
         observation_state_data[offset..offset + Observation::LEN * OBSERVATION_NUM]
             .copy_from_slice(&observation_datas);
         offset += Observation::LEN * OBSERVATION_NUM;
+        observation_state_data[offset..offset + 2]
+            .copy_from_slice(&observation_cardinality.to_le_bytes());
+        offset += 2;
+        observation_state_data[offset..offset + 2]
+            .copy_from_slice(&observation_cardinality_next.to_le_bytes());
+        offset += 2;
+        observation_state_data[offset..offset + 4]
+            .copy_from_slice(&observation_update_duration.to_le_bytes());
+        offset += 4;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[0].to_le_bytes());
         offset += 8;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[1].to_le_bytes());
         offset += 8;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[2].to_le_bytes());
         offset += 8;
-        observation_state_data[offset..offset + 8].copy_from_slice(&padding[3].to_le_bytes());
-        offset += 8;
         // len check
         assert_eq!(offset, observation_state_data.len());
         assert_eq!(
@@ -217,6 +1100,18 @@ pub mod oracle_layout_test {
         assert_eq!(unpack_observation_index, observation_index);
         let unpack_pool_id = unpack_data.pool_id;
         assert_eq!(unpack_pool_id, pool_id);
+        let unpack_observation_cardinality = unpack_data.observation_cardinality;
+        assert_eq!(unpack_observation_cardinality, observation_cardinality);
+        let unpack_observation_cardinality_next = unpack_data.observation_cardinality_next;
+        assert_eq!(
+            unpack_observation_cardinality_next,
+            observation_cardinality_next
+        );
+        let unpack_observation_update_duration = unpack_data.observation_update_duration;
+        assert_eq!(
+            unpack_observation_update_duration,
+            observation_update_duration
+        );
         let unpack_padding = unpack_data.padding;
         assert_eq!(unpack_padding, padding);
         for (observation, unpack_observation) in
@@ -224,13 +1119,21 @@ pub mod oracle_layout_test {
         {
             let block_timestamp = observation.block_timestamp;
             let tick_cumulative = observation.tick_cumulative;
+            let seconds_per_liquidity_cumulative_x64 =
+                observation.seconds_per_liquidity_cumulative_x64;
             let padding = observation.padding;
 
             let unpack_block_timestamp = unpack_observation.block_timestamp;
             let unpack_tick_cumulative = unpack_observation.tick_cumulative;
+            let unpack_seconds_per_liquidity_cumulative_x64 =
+                unpack_observation.seconds_per_liquidity_cumulative_x64;
             let unpack_padding = unpack_observation.padding;
             assert_eq!(block_timestamp, unpack_block_timestamp);
             assert_eq!(tick_cumulative, unpack_tick_cumulative);
+            assert_eq!(
+                seconds_per_liquidity_cumulative_x64,
+                unpack_seconds_per_liquidity_cumulative_x64
+            );
             assert_eq!(padding, unpack_padding);
         }
     }
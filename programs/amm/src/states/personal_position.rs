@@ -164,6 +164,100 @@ pub struct IncreaseLiquidityEvent {
     pub amount_1_transfer_fee: u64,
 }
 
+/// Emitted when the fees of a position are reinvested as liquidity.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CompoundPositionEvent {
+    /// The ID of the token for which fees were reinvested
+    pub position_nft_mint: Pubkey,
+
+    /// The amount by which liquidity for the NFT position was increased
+    pub liquidity: u128,
+
+    /// The amount of token_0 fees that was reinvested
+    pub amount_0: u64,
+
+    /// The amount of token_1 fees that was reinvested
+    pub amount_1: u64,
+
+    /// The token_0 fees still owed to the position
+    pub token_fees_owed_0: u64,
+
+    /// The token_1 fees still owed to the position
+    pub token_fees_owed_1: u64,
+}
+
+/// Emitted when the liquidity of a position is moved to a new tick range.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RebalancePositionEvent {
+    /// The ID of the token for which liquidity was moved
+    pub position_nft_mint: Pubkey,
+
+    /// The lower tick of the range before
+    pub tick_lower_before: i32,
+
+    /// The upper tick of the range before
+    pub tick_upper_before: i32,
+
+    /// The liquidity removed from the range before
+    pub liquidity_before: u128,
+
+    /// The lower tick of the new range
+    pub tick_lower: i32,
+
+    /// The upper tick of the new range
+    pub tick_upper: i32,
+
+    /// The liquidity added to the new range
+    pub liquidity: u128,
+
+    /// The amount of token_0 removed from the range before
+    pub decrease_amount_0: u64,
+
+    /// The amount of token_1 removed from the range before
+    pub decrease_amount_1: u64,
+
+    /// The amount of token_0 swapped, paid if zero_for_one, otherwise received
+    pub swap_amount_0: u64,
+
+    /// The amount of token_1 swapped, received if zero_for_one, otherwise paid
+    pub swap_amount_1: u64,
+
+    /// The direction of the swap
+    pub zero_for_one: bool,
+
+    /// The amount of token_0 added to the new range
+    pub deposit_amount_0: u64,
+
+    /// The amount of token_1 added to the new range
+    pub deposit_amount_1: u64,
+
+    /// The amount of token_0 paid out to the recipient
+    pub refund_amount_0: u64,
+
+    /// The amount of token_1 paid out to the recipient
+    pub refund_amount_1: u64,
+}
+
+/// The result of a liquidity change of a position, returned as return data by the v2 liquidity
+/// instructions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LiquidityChangeResult {
+    /// The liquidity added to or removed from the position
+    pub liquidity: u128,
+    /// The amount of token_0 deposited or withdrawn, the transfer fee is paid on top of a
+    /// deposit and deducted from a withdrawal
+    pub amount_0: u64,
+    /// The amount of token_1 deposited or withdrawn, the transfer fee is paid on top of a
+    /// deposit and deducted from a withdrawal
+    pub amount_1: u64,
+    /// The token transfer fee for amount_0
+    pub amount_0_transfer_fee: u64,
+    /// The token transfer fee for amount_1
+    pub amount_1_transfer_fee: u64,
+}
+
 /// Emitted when liquidity is decreased.
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
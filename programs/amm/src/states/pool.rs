@@ -113,8 +113,20 @@ pub struct PoolState {
     /// bit3, 1: disable collect reward, 0: normal
     /// bit4, 1: disable swap, 0: normal
     pub status: u8,
+    /// The number of rewards initialized in the reward extension, once it is non zero the reward
+    /// extension accounts must be passed to the instructions accruing rewards
+    pub extension_reward_count: u8,
+    /// Bitmask of the reward indexes deposited into by funders other than the reward authority,
+    /// the reward funding account must then be passed to collect the remaining rewards
+    pub funded_reward_flags: u8,
+    /// Bitmask of the reward indexes restricted to a tick range, such a reward only accrues while
+    /// the current tick is within its range
+    pub reward_range_flags: u8,
+    /// Bitmask of the reward indexes following a reward schedule, the reward schedule account
+    /// must then be passed to the instructions accruing rewards until the reward ends
+    pub scheduled_reward_flags: u8,
     /// Leave blank for future use
-    pub padding: [u8; 7],
+    pub padding: [u8; 3],
 
     pub reward_infos: [RewardInfo; REWARD_NUM],
 
@@ -131,14 +143,45 @@ pub struct PoolState {
     pub fund_fees_token_0: u64,
     pub fund_fees_token_1: u64,
 
-    // The timestamp allowed for swap in the pool.
-    // Note: The open_time is disabled for now.
+    /// The timestamp swaps are allowed after, the liquidity can be provided before. The pool
+    /// owner or the admin can change it until it passes.
     pub open_time: u64,
     // account recent update epoch
     pub recent_epoch: u64,
 
+    /// The tick ranges of the rewards flagged in reward_range_flags, a reward accrues while
+    /// tick_lower <= tick_current < tick_upper
+    pub reward_range_tick_lower: [i32; REWARD_NUM],
+    pub reward_range_tick_upper: [i32; REWARD_NUM],
+
+    /// The max ticks a swap can move the price away from the recent average tick, the swap stops
+    /// at the bound. Zero disables the guard.
+    pub max_tick_deviation: u32,
+    /// The window in seconds of the average tick the deviation is measured from
+    pub tick_deviation_period: u32,
+
+    /// The trade fee rate the pool uses instead of the amm config one while the override is set
+    pub trade_fee_rate_override: u32,
+    /// 1 if trade_fee_rate_override is set, 0 to use the amm config trade fee rate
+    pub trade_fee_rate_override_enabled: u8,
+    pub padding5: [u8; 3],
+
+    /// The amounts of token_0 and token_1 that are owed to the pool creator.
+    pub creator_fees_token_0: u64,
+    pub creator_fees_token_1: u64,
+
+    /// The amm config the pool address is derived from once the pool moved to another amm config,
+    /// the default pubkey while the pool still belongs to it
+    pub seed_amm_config: Pubkey,
+
+    /// The program called before and after each swap of the pool, the default pubkey if none
+    pub swap_hook_program: Pubkey,
+    /// The number of accounts passed to the swap hook program after it
+    pub swap_hook_account_count: u8,
+    pub padding6: [u8; 7],
+
     // Unused bytes for future upgrades.
-    pub padding1: [u64; 24],
+    pub padding1: [u64; 8],
     pub padding2: [u64; 32],
 }
 
@@ -168,9 +211,14 @@ impl PoolState {
         + 512;
 
     pub fn seeds(&self) -> [&[u8]; 5] {
+        let amm_config = if self.seed_amm_config == Pubkey::default() {
+            &self.amm_config
+        } else {
+            &self.seed_amm_config
+        };
         [
             &POOL_SEED.as_bytes(),
-            self.amm_config.as_ref(),
+            amm_config.as_ref(),
             self.token_mint_0.as_ref(),
             self.token_mint_1.as_ref(),
             self.bump.as_ref(),
@@ -181,6 +229,18 @@ impl PoolState {
         Pubkey::create_program_address(&self.seeds(), &crate::id()).unwrap()
     }
 
+    /// Moves the pool to another amm config, the pool address stays derived from the amm config
+    /// it was created with.
+    pub fn set_amm_config(&mut self, amm_config: Pubkey) {
+        if self.seed_amm_config == Pubkey::default() {
+            self.seed_amm_config = self.amm_config;
+        }
+        if self.seed_amm_config == amm_config {
+            self.seed_amm_config = Pubkey::default();
+        }
+        self.amm_config = amm_config;
+    }
+
     pub fn initialize(
         &mut self,
         bump: u8,
@@ -220,7 +280,11 @@ impl PoolState {
         self.swap_in_amount_token_1 = 0;
         self.swap_out_amount_token_0 = 0;
         self.status = 0;
-        self.padding = [0; 7];
+        self.extension_reward_count = 0;
+        self.funded_reward_flags = 0;
+        self.reward_range_flags = 0;
+        self.scheduled_reward_flags = 0;
+        self.padding = [0; 3];
         self.tick_array_bitmap = [0; 16];
         self.total_fees_token_0 = 0;
         self.total_fees_claimed_token_0 = 0;
@@ -230,7 +294,20 @@ impl PoolState {
         self.fund_fees_token_1 = 0;
         self.open_time = open_time;
         self.recent_epoch = get_recent_epoch()?;
-        self.padding1 = [0; 24];
+        self.reward_range_tick_lower = [0; REWARD_NUM];
+        self.reward_range_tick_upper = [0; REWARD_NUM];
+        self.max_tick_deviation = 0;
+        self.tick_deviation_period = 0;
+        self.trade_fee_rate_override = 0;
+        self.trade_fee_rate_override_enabled = 0;
+        self.padding5 = [0; 3];
+        self.creator_fees_token_0 = 0;
+        self.creator_fees_token_1 = 0;
+        self.seed_amm_config = Pubkey::default();
+        self.swap_hook_program = Pubkey::default();
+        self.swap_hook_account_count = 0;
+        self.padding6 = [0; 7];
+        self.padding1 = [0; 8];
         self.padding2 = [0; 32];
         self.observation_key = observation_state_key;
 
@@ -340,68 +417,14 @@ impl PoolState {
 
         let mut next_reward_infos = self.reward_infos;
 
-        for i in 0..REWARD_NUM {
-            let reward_info = &mut next_reward_infos[i];
-            if !reward_info.initialized() {
-                continue;
-            }
-            if curr_timestamp <= reward_info.open_time {
-                continue;
-            }
-            let latest_update_timestamp = curr_timestamp.min(reward_info.end_time);
-
-            if self.liquidity != 0 {
-                require_gte!(latest_update_timestamp, reward_info.last_update_time);
-                let time_delta = latest_update_timestamp
-                    .checked_sub(reward_info.last_update_time)
-                    .unwrap();
-
-                let reward_growth_delta = U256::from(time_delta)
-                    .mul_div_floor(
-                        U256::from(reward_info.emissions_per_second_x64),
-                        U256::from(self.liquidity),
-                    )
-                    .unwrap();
-
-                reward_info.reward_growth_global_x64 = reward_info
-                    .reward_growth_global_x64
-                    .checked_add(reward_growth_delta.as_u128())
-                    .unwrap();
-
-                reward_info.reward_total_emissioned = reward_info
-                    .reward_total_emissioned
-                    .checked_add(
-                        U128::from(time_delta)
-                            .mul_div_ceil(
-                                U128::from(reward_info.emissions_per_second_x64),
-                                U128::from(fixed_point_64::Q64),
-                            )
-                            .unwrap()
-                            .as_u64(),
-                    )
-                    .unwrap();
-                #[cfg(feature = "enable-log")]
-                msg!(
-                    "reward_index:{},latest_update_timestamp:{},reward_info.reward_last_update_time:{},time_delta:{},reward_emission_per_second_x64:{},reward_growth_delta:{},reward_info.reward_growth_global_x64:{}, reward_info.reward_claim:{}",
-                    i,
-                    latest_update_timestamp,
-                    identity(reward_info.last_update_time),
-                    time_delta,
-                    identity(reward_info.emissions_per_second_x64),
-                    reward_growth_delta,
-                    identity(reward_info.reward_growth_global_x64),
-                    identity(reward_info.reward_claimed)
-                );
-            }
-            reward_info.last_update_time = latest_update_timestamp;
-            // update reward state
-            if latest_update_timestamp >= reward_info.open_time
-                && latest_update_timestamp < reward_info.end_time
-            {
-                reward_info.reward_state = RewardState::Opening as u8;
-            } else if latest_update_timestamp == next_reward_infos[i].end_time {
-                next_reward_infos[i].reward_state = RewardState::Ended as u8;
-            }
+        for (i, reward_info) in next_reward_infos.iter_mut().enumerate() {
+            // the time out of its tick range is not emitted, as if the pool had no liquidity
+            let liquidity = if self.is_in_reward_range(i) {
+                self.liquidity
+            } else {
+                0
+            };
+            reward_info.update(curr_timestamp, liquidity)?;
         }
         self.reward_infos = next_reward_infos;
         #[cfg(feature = "enable-log")]
@@ -411,6 +434,107 @@ impl PoolState {
         Ok(next_reward_infos)
     }
 
+    /// Whether the reward accrues at the current tick, always true if it has no tick range
+    pub fn is_in_reward_range(&self, index: usize) -> bool {
+        self.reward_range_flags & (1 << index) == 0
+            || (self.reward_range_tick_lower[index] <= self.tick_current
+                && self.tick_current < self.reward_range_tick_upper[index])
+    }
+
+    /// Restricts the reward to the tick range, or lifts the restriction if `range` is none. The
+    /// rewards must be updated before.
+    pub fn set_reward_range(&mut self, index: usize, range: Option<(i32, i32)>) {
+        assert!(index < REWARD_NUM);
+        match range {
+            Some((tick_lower, tick_upper)) => {
+                self.reward_range_flags |= 1 << index;
+                self.reward_range_tick_lower[index] = tick_lower;
+                self.reward_range_tick_upper[index] = tick_upper;
+            }
+            None => {
+                self.reward_range_flags &= !(1 << index);
+                self.reward_range_tick_lower[index] = 0;
+                self.reward_range_tick_upper[index] = 0;
+            }
+        }
+    }
+
+    /// Overrides the amm config trade fee rate for the pool, or clears the override if
+    /// `trade_fee_rate` is none.
+    pub fn set_trade_fee_rate_override(&mut self, trade_fee_rate: Option<u32>) {
+        match trade_fee_rate {
+            Some(trade_fee_rate) => {
+                self.trade_fee_rate_override = trade_fee_rate;
+                self.trade_fee_rate_override_enabled = 1;
+            }
+            None => {
+                self.trade_fee_rate_override = 0;
+                self.trade_fee_rate_override_enabled = 0;
+            }
+        }
+    }
+
+    /// Returns the trade fee rate of the pool before the dynamic fee, the override if set or
+    /// else the amm config one.
+    pub fn base_trade_fee_rate(&self, amm_config: &AmmConfig) -> u32 {
+        if self.trade_fee_rate_override_enabled == 1 {
+            self.trade_fee_rate_override
+        } else {
+            amm_config.trade_fee_rate
+        }
+    }
+
+    /// Returns the trade fee rate used by a swap, the base trade fee rate of the pool plus the
+    /// volatility surcharge of the amm config.
+    pub fn get_trade_fee_rate(
+        &self,
+        amm_config: &AmmConfig,
+        observation_state: &ObservationState,
+        extended_observations: &[Observation],
+        block_timestamp: u32,
+    ) -> u32 {
+        let dynamic_fee_rate = amm_config.get_dynamic_fee_rate(
+            observation_state,
+            extended_observations,
+            self.tick_current,
+            block_timestamp,
+        );
+        // the total fee rate must be less than the denominator
+        self.base_trade_fee_rate(amm_config)
+            .saturating_add(dynamic_fee_rate)
+            .min(FEE_RATE_DENOMINATOR_VALUE - 1)
+    }
+
+    /// Returns the sqrt price a swap in the direction can move the price to at most, the price at
+    /// `max_tick_deviation` ticks from the average tick of the observations settled before the
+    /// current block. None if the guard is disabled or the oracle has no history yet.
+    pub fn get_deviation_sqrt_price_bound(
+        &self,
+        observation_state: &ObservationState,
+        extended_observations: &[Observation],
+        block_timestamp: u32,
+        zero_for_one: bool,
+    ) -> Result<Option<u128>> {
+        if self.max_tick_deviation == 0 {
+            return Ok(None);
+        }
+        let average_tick = match observation_state.get_settled_average_tick(
+            extended_observations,
+            block_timestamp,
+            self.tick_deviation_period,
+        ) {
+            Some(average_tick) => i64::from(average_tick),
+            None => return Ok(None),
+        };
+        let max_tick_deviation = i64::from(self.max_tick_deviation);
+        let tick_bound = if zero_for_one {
+            (average_tick - max_tick_deviation).max(tick_math::MIN_TICK.into())
+        } else {
+            (average_tick + max_tick_deviation).min(tick_math::MAX_TICK.into())
+        };
+        Ok(Some(tick_math::get_sqrt_price_at_tick(tick_bound as i32)?))
+    }
+
     pub fn check_unclaimed_reward(&self, index: usize, reward_amount_owed: u64) -> Result<()> {
         assert!(index < REWARD_NUM);
         let unclaimed_reward = self.reward_infos[index]
@@ -430,6 +554,83 @@ impl PoolState {
         Ok(())
     }
 
+    /// Splits a fee paid outside of a swap into the protocol, fund, creator and liquidity provider
+    /// parts, the same way a swap fee is split
+    pub fn accrue_fee(&mut self, amm_config: &AmmConfig, fee_amount: u64, is_token_0: bool) {
+        let protocol_fee = U128::from(fee_amount)
+            .checked_mul(amm_config.protocol_fee_rate.into())
+            .unwrap()
+            .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
+            .unwrap()
+            .as_u64();
+        let fund_fee = U128::from(fee_amount)
+            .checked_mul(amm_config.fund_fee_rate.into())
+            .unwrap()
+            .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
+            .unwrap()
+            .as_u64();
+        let creator_fee = U128::from(fee_amount)
+            .checked_mul(amm_config.creator_fee_rate.into())
+            .unwrap()
+            .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
+            .unwrap()
+            .as_u64();
+        // like a swap, the liquidity provider part stays in the vault unclaimed without liquidity
+        let (lp_fee, fee_growth_global_x64_delta) = if self.liquidity > 0 {
+            let lp_fee = fee_amount - protocol_fee - fund_fee - creator_fee;
+            (
+                lp_fee,
+                U128::from(lp_fee)
+                    .mul_div_floor(U128::from(fixed_point_64::Q64), U128::from(self.liquidity))
+                    .unwrap()
+                    .as_u128(),
+            )
+        } else {
+            (0, 0)
+        };
+        if is_token_0 {
+            self.fee_growth_global_0_x64 = self
+                .fee_growth_global_0_x64
+                .checked_add(fee_growth_global_x64_delta)
+                .unwrap();
+            self.total_fees_token_0 = self.total_fees_token_0.checked_add(lp_fee).unwrap();
+            self.protocol_fees_token_0 = self
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .unwrap();
+            self.fund_fees_token_0 = self.fund_fees_token_0.checked_add(fund_fee).unwrap();
+            self.creator_fees_token_0 = self.creator_fees_token_0.checked_add(creator_fee).unwrap();
+        } else {
+            self.fee_growth_global_1_x64 = self
+                .fee_growth_global_1_x64
+                .checked_add(fee_growth_global_x64_delta)
+                .unwrap();
+            self.total_fees_token_1 = self.total_fees_token_1.checked_add(lp_fee).unwrap();
+            self.protocol_fees_token_1 = self
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .unwrap();
+            self.fund_fees_token_1 = self.fund_fees_token_1.checked_add(fund_fee).unwrap();
+            self.creator_fees_token_1 = self.creator_fees_token_1.checked_add(creator_fee).unwrap();
+        }
+    }
+
+    /// Returns the fees of the token accrued to the liquidity providers plus the protocol, fund
+    /// and creator fees not yet collected
+    pub fn get_accrued_fees(&self, is_token_0: bool) -> u64 {
+        if is_token_0 {
+            self.total_fees_token_0
+                + self.protocol_fees_token_0
+                + self.fund_fees_token_0
+                + self.creator_fees_token_0
+        } else {
+            self.total_fees_token_1
+                + self.protocol_fees_token_1
+                + self.fund_fees_token_1
+                + self.creator_fees_token_1
+        }
+    }
+
     pub fn get_tick_array_offset(&self, tick_array_start_index: i32) -> Result<usize> {
         require!(
             TickArrayState::check_is_valid_start_index(tick_array_start_index, self.tick_spacing),
@@ -571,6 +772,22 @@ impl PoolState {
         self.status.bitand(status) == 0
     }
 
+    /// Checks the pool allows swaps at `block_timestamp`. The `Swap` status bit takes precedence
+    /// over `open_time`: a pool with swaps disabled stays closed after its open time, and enabling
+    /// swaps does not open the pool before its open time.
+    pub fn check_swap_allowed(&self, block_timestamp: u32) -> Result<()> {
+        require!(
+            self.get_status_by_bit(PoolStatusBitIndex::Swap),
+            ErrorCode::NotApproved
+        );
+        require_gt!(
+            u64::from(block_timestamp),
+            self.open_time,
+            ErrorCode::PoolNotOpen
+        );
+        Ok(())
+    }
+
     pub fn is_overflow_default_tickarray_bitmap(&self, tick_indexs: Vec<i32>) -> bool {
         let (min_tick_array_start_index_boundary, max_tick_array_index_boundary) =
             self.tick_array_start_index_range();
@@ -666,6 +883,70 @@ impl RewardInfo {
         self.token_mint.ne(&Pubkey::default())
     }
 
+    /// Accrues the reward growth from the last update time up to `curr_timestamp`, shared by the
+    /// in-range `liquidity` of the pool.
+    /// The provided timestamp must be greater than or equal to the last updated timestamp.
+    pub fn update(&mut self, curr_timestamp: u64, liquidity: u128) -> Result<()> {
+        if !self.initialized() {
+            return Ok(());
+        }
+        if curr_timestamp <= self.open_time {
+            return Ok(());
+        }
+        let latest_update_timestamp = curr_timestamp.min(self.end_time);
+
+        if liquidity != 0 {
+            require_gte!(latest_update_timestamp, self.last_update_time);
+            let time_delta = latest_update_timestamp
+                .checked_sub(self.last_update_time)
+                .unwrap();
+
+            let reward_growth_delta = U256::from(time_delta)
+                .mul_div_floor(
+                    U256::from(self.emissions_per_second_x64),
+                    U256::from(liquidity),
+                )
+                .unwrap();
+
+            self.reward_growth_global_x64 = self
+                .reward_growth_global_x64
+                .checked_add(reward_growth_delta.as_u128())
+                .unwrap();
+
+            self.reward_total_emissioned = self
+                .reward_total_emissioned
+                .checked_add(
+                    U128::from(time_delta)
+                        .mul_div_ceil(
+                            U128::from(self.emissions_per_second_x64),
+                            U128::from(fixed_point_64::Q64),
+                        )
+                        .unwrap()
+                        .as_u64(),
+                )
+                .unwrap();
+            #[cfg(feature = "enable-log")]
+            msg!(
+                "latest_update_timestamp:{},reward_info.reward_last_update_time:{},time_delta:{},reward_emission_per_second_x64:{},reward_growth_delta:{},reward_info.reward_growth_global_x64:{}, reward_info.reward_claim:{}",
+                latest_update_timestamp,
+                identity(self.last_update_time),
+                time_delta,
+                identity(self.emissions_per_second_x64),
+                reward_growth_delta,
+                identity(self.reward_growth_global_x64),
+                identity(self.reward_claimed)
+            );
+        }
+        self.last_update_time = latest_update_timestamp;
+        // update reward state
+        if latest_update_timestamp >= self.open_time && latest_update_timestamp < self.end_time {
+            self.reward_state = RewardState::Opening as u8;
+        } else if latest_update_timestamp == self.end_time {
+            self.reward_state = RewardState::Ended as u8;
+        }
+        Ok(())
+    }
+
     pub fn get_reward_growths(reward_infos: &[RewardInfo; REWARD_NUM]) -> [u128; REWARD_NUM] {
         let mut reward_growths = [0u128; REWARD_NUM];
         for i in 0..REWARD_NUM {
@@ -704,6 +985,97 @@ pub struct PoolCreatedEvent {
     pub token_vault_1: Pubkey,
 }
 
+/// Emitted when a pool is moved to another amm config
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolAmmConfigChangeEvent {
+    /// The pool moved
+    pub pool_state: Pubkey,
+
+    /// The amm config the pool belonged to
+    pub old_amm_config: Pubkey,
+
+    /// The amm config the pool belongs to now
+    pub amm_config: Pubkey,
+
+    /// The rates of the new amm config
+    pub trade_fee_rate: u32,
+    pub protocol_fee_rate: u32,
+    pub fund_fee_rate: u32,
+    pub creator_fee_rate: u32,
+}
+
+/// Emitted when a reward of a pool is restricted to a tick range, or the restriction is lifted
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RewardRangeChangeEvent {
+    /// The pool updated
+    pub pool_state: Pubkey,
+
+    /// The index of the reward in the pool
+    pub reward_index: u8,
+
+    /// True if the reward only accrues within the range
+    pub enabled: bool,
+
+    /// The range of ticks the reward accrues in, zero if not restricted
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+}
+
+/// Emitted when the time a pool opens for swaps is moved
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolOpenTimeChangeEvent {
+    /// The pool updated
+    pub pool_state: Pubkey,
+
+    /// The timestamp swaps were allowed after
+    pub old_open_time: u64,
+
+    /// The timestamp swaps are allowed after
+    pub open_time: u64,
+}
+
+/// Emitted when the swap hook program of a pool is registered or removed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolSwapHookChangeEvent {
+    /// The pool updated
+    pub pool_state: Pubkey,
+
+    /// The program called before and after each swap, the default pubkey if removed
+    pub swap_hook_program: Pubkey,
+
+    /// The number of accounts passed to the swap hook program after it
+    pub swap_hook_account_count: u8,
+}
+
+/// Emitted when the trade fee rate override of a pool is set or removed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolTradeFeeRateChangeEvent {
+    /// The pool updated
+    pub pool_state: Pubkey,
+
+    /// The trade fee rate the pool uses instead of the amm config one, none to use the amm config
+    pub trade_fee_rate: Option<u32>,
+}
+
+/// Emitted when the tick deviation guard of a pool is set
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolTickDeviationChangeEvent {
+    /// The pool updated
+    pub pool_state: Pubkey,
+
+    /// The max ticks a swap can move the price away from the average tick, zero if disabled
+    pub max_tick_deviation: u32,
+
+    /// The window in seconds of the average tick
+    pub tick_deviation_period: u32,
+}
+
 /// Emitted when the collected protocol fees are withdrawn by the factory owner
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -724,6 +1096,26 @@ pub struct CollectProtocolFeeEvent {
     pub amount_1: u64,
 }
 
+/// Emitted when the pool creator fees are withdrawn by the pool owner
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CollectCreatorFeeEvent {
+    /// The pool whose creator fee is collected
+    pub pool_state: Pubkey,
+
+    /// The address that receives the collected token_0 creator fees
+    pub recipient_token_account_0: Pubkey,
+
+    /// The address that receives the collected token_1 creator fees
+    pub recipient_token_account_1: Pubkey,
+
+    /// The amount of token_0 creator fees that is withdrawn
+    pub amount_0: u64,
+
+    /// The amount of token_1 creator fees that is withdrawn
+    pub amount_1: u64,
+}
+
 /// Emitted by when a swap is performed for a pool
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -765,6 +1157,70 @@ pub struct SwapEvent {
 
     /// The log base 1.0001 of price of the pool after the swap
     pub tick: i32,
+
+    /// The trade fee rate the swap was charged, including the dynamic fee
+    pub trade_fee_rate: u32,
+}
+
+/// The result of a swap, returned as return data by swap_v2
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapResult {
+    /// The amount of input token paid by the user, including the transfer fee
+    pub amount_in: u64,
+    /// The amount of output token received by the user, net of the transfer fee
+    pub amount_out: u64,
+    /// The trade fee paid in input token
+    pub fee_amount: u64,
+    /// The sqrt(price) of the pool after the swap, as a Q64.64
+    pub sqrt_price_x64: u128,
+    /// The log base 1.0001 of price of the pool after the swap
+    pub tick: i32,
+}
+
+/// The result of a swap run against copies of the pool, returned as return data by quote_swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuoteSwapResult {
+    /// The amount of input token to be paid
+    pub amount_in: u64,
+    /// The amount of output token to be received
+    pub amount_out: u64,
+    /// The trade fee paid in input token
+    pub fee_amount: u64,
+    /// The sqrt(price) of the pool after the swap, as a Q64.64
+    pub sqrt_price_x64: u128,
+    /// The log base 1.0001 of price of the pool after the swap
+    pub tick: i32,
+    /// The start indexes of the tick arrays passed that the swap runs through, in swap order
+    pub tick_array_start_indexes: Vec<i32>,
+}
+
+/// Emitted by when a flash loan is repaid to a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FlashEvent {
+    /// The pool the tokens were borrowed from
+    pub pool_state: Pubkey,
+
+    /// The address that borrowed the tokens
+    pub sender: Pubkey,
+
+    /// The amount of token_0 borrowed
+    pub amount_0: u64,
+
+    /// The amount of token_1 borrowed
+    pub amount_1: u64,
+
+    /// The fee charged on the token_0 borrowed
+    pub fee_0: u64,
+
+    /// The fee charged on the token_1 borrowed
+    pub fee_1: u64,
+
+    /// The amount of token_0 the vault got back
+    pub paid_0: u64,
+
+    /// The amount of token_1 the vault got back
+    pub paid_1: u64,
 }
 
 /// Emitted pool liquidity change when increase and decrease liquidity
@@ -958,6 +1414,33 @@ pub mod pool_test {
         }
     }
 
+    mod set_amm_config_test {
+        use super::*;
+
+        #[test]
+        fn pool_key_unchanged_by_amm_config_test() {
+            let pool_state = build_pool(0, 10, 1 << 64, 0);
+            let mut pool_state = pool_state.borrow_mut();
+            let pool_key = pool_state.key();
+            let amm_config = pool_state.amm_config;
+
+            let new_amm_config = Pubkey::new_unique();
+            pool_state.set_amm_config(new_amm_config);
+            assert_eq!(pool_state.amm_config, new_amm_config);
+            assert_eq!(pool_state.seed_amm_config, amm_config);
+            assert_eq!(pool_state.key(), pool_key);
+
+            pool_state.set_amm_config(Pubkey::new_unique());
+            assert_eq!(pool_state.seed_amm_config, amm_config);
+            assert_eq!(pool_state.key(), pool_key);
+
+            // back to the amm config it was created with
+            pool_state.set_amm_config(amm_config);
+            assert_eq!(pool_state.seed_amm_config, Pubkey::default());
+            assert_eq!(pool_state.key(), pool_key);
+        }
+    }
+
     mod pool_status_test {
         use super::*;
 
@@ -1018,6 +1501,115 @@ pub mod pool_test {
         }
     }
 
+    mod check_swap_allowed_test {
+        use super::*;
+
+        #[test]
+        fn open_time_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.open_time = 1000;
+            assert_eq!(
+                pool_state.check_swap_allowed(1000).unwrap_err(),
+                ErrorCode::PoolNotOpen.into()
+            );
+            assert!(pool_state.check_swap_allowed(1001).is_ok());
+        }
+
+        #[test]
+        fn swap_status_takes_precedence_over_open_time_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.open_time = 1000;
+            pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
+            // disabled before and after the open time
+            assert_eq!(
+                pool_state.check_swap_allowed(999).unwrap_err(),
+                ErrorCode::NotApproved.into()
+            );
+            assert_eq!(
+                pool_state.check_swap_allowed(1001).unwrap_err(),
+                ErrorCode::NotApproved.into()
+            );
+
+            // enabling swaps does not open the pool early
+            pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Enable);
+            assert_eq!(
+                pool_state.check_swap_allowed(999).unwrap_err(),
+                ErrorCode::PoolNotOpen.into()
+            );
+            assert!(pool_state.check_swap_allowed(1001).is_ok());
+
+            // other status bits do not close the pool
+            pool_state.set_status_by_bit(
+                PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity,
+                PoolStatusBitFlag::Disable,
+            );
+            assert!(pool_state.check_swap_allowed(1001).is_ok());
+        }
+    }
+
+    mod accrue_fee_test {
+        use super::*;
+
+        #[test]
+        fn accrue_fee_test() {
+            let amm_config = AmmConfig {
+                protocol_fee_rate: 120000,
+                fund_fee_rate: 40000,
+                ..Default::default()
+            };
+            let pool_state = &mut PoolState::default();
+            pool_state.liquidity = 1 << 20;
+            pool_state.accrue_fee(&amm_config, 10000, true);
+            assert!(pool_state.protocol_fees_token_0 == 1200);
+            assert!(pool_state.fund_fees_token_0 == 400);
+            assert!(pool_state.total_fees_token_0 == 8400);
+            assert!(pool_state.fee_growth_global_0_x64 == (8400u128 << 64) >> 20);
+            assert!(pool_state.total_fees_token_1 == 0);
+            assert!(pool_state.fee_growth_global_1_x64 == 0);
+
+            pool_state.accrue_fee(&amm_config, 10000, false);
+            assert!(pool_state.protocol_fees_token_1 == 1200);
+            assert!(pool_state.fund_fees_token_1 == 400);
+            assert!(pool_state.total_fees_token_1 == 8400);
+        }
+
+        #[test]
+        fn accrue_fee_without_liquidity_test() {
+            let amm_config = AmmConfig {
+                protocol_fee_rate: 120000,
+                ..Default::default()
+            };
+            let pool_state = &mut PoolState::default();
+            pool_state.accrue_fee(&amm_config, 10000, true);
+            assert!(pool_state.protocol_fees_token_0 == 1200);
+            assert!(pool_state.total_fees_token_0 == 0);
+            assert!(pool_state.fee_growth_global_0_x64 == 0);
+        }
+
+        #[test]
+        fn accrue_fee_with_creator_fee_test() {
+            let amm_config = AmmConfig {
+                protocol_fee_rate: 120000,
+                fund_fee_rate: 40000,
+                creator_fee_rate: 50000,
+                ..Default::default()
+            };
+            let pool_state = &mut PoolState::default();
+            pool_state.liquidity = 1 << 20;
+            pool_state.accrue_fee(&amm_config, 10000, true);
+            assert!(pool_state.protocol_fees_token_0 == 1200);
+            assert!(pool_state.fund_fees_token_0 == 400);
+            assert!(pool_state.creator_fees_token_0 == 500);
+            assert!(pool_state.total_fees_token_0 == 7900);
+
+            pool_state.accrue_fee(&amm_config, 10000, false);
+            assert!(pool_state.creator_fees_token_1 == 500);
+            assert!(pool_state.total_fees_token_1 == 7900);
+            assert!(pool_state.get_accrued_fees(true) == 10000);
+            assert!(pool_state.get_accrued_fees(false) == 10000);
+        }
+    }
+
     mod update_reward_infos_test {
         use super::*;
         use anchor_lang::prelude::Pubkey;
@@ -1074,6 +1666,62 @@ pub mod pool_test {
                 1666069200
             );
         }
+
+        #[test]
+        fn reward_range_test() {
+            let pool_state = &mut PoolState::default();
+            let operation_state = OperationState {
+                bump: 0,
+                operation_owners: [Pubkey::default(); OPERATION_SIZE_USIZE],
+                whitelist_mints: [Pubkey::default(); WHITE_MINT_SIZE_USIZE],
+            };
+            pool_state
+                .initialize_reward(
+                    1665982800,
+                    1666069200,
+                    10,
+                    &Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap(),
+                    COption::None,
+                    &Pubkey::default(),
+                    &Pubkey::default(),
+                    &operation_state,
+                )
+                .unwrap();
+            pool_state.liquidity = 100;
+            pool_state.tick_current = 10;
+            pool_state.set_reward_range(0, Some((-10, 10)));
+            assert!(!pool_state.is_in_reward_range(0));
+            assert!(pool_state.is_in_reward_range(1));
+
+            // out of range, the time passes without emission
+            let mut updated_reward_infos = pool_state.update_reward_infos(1665982900).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].last_update_time),
+                1665982900
+            );
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_growth_global_x64),
+                0
+            );
+            assert_eq!(identity(updated_reward_infos[0].reward_total_emissioned), 0);
+
+            pool_state.tick_current = -10;
+            updated_reward_infos = pool_state.update_reward_infos(1665983000).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_growth_global_x64),
+                10
+            );
+
+            // lifting the range accrues at any tick
+            pool_state.tick_current = 100;
+            pool_state.set_reward_range(0, None);
+            assert!(pool_state.is_in_reward_range(0));
+            updated_reward_infos = pool_state.update_reward_infos(1665983100).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_growth_global_x64),
+                20
+            );
+        }
     }
 
     mod use_tickarray_bitmap_extension_test {
@@ -1610,7 +2258,11 @@ pub mod pool_test {
             let swap_in_amount_token_1: u128 = 0x11223344556677008899aabbccddeeff;
             let swap_out_amount_token_0: u128 = 0x11223344556677880099aabbccddeeff;
             let status: u8 = 0x1b;
-            let padding: [u8; 7] = [0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18];
+            let extension_reward_count: u8 = 0x12;
+            let funded_reward_flags: u8 = 0x13;
+            let reward_range_flags: u8 = 0x14;
+            let scheduled_reward_flags: u8 = 0x15;
+            let padding: [u8; 3] = [0x16, 0x17, 0x18];
             // RewardInfo
             let reward_state: u8 = 0x1c;
             let open_time: u64 = 0x123456789abc0def;
@@ -1681,10 +2333,23 @@ pub mod pool_test {
             let fund_fees_token_1: u64 = 0x1230456789abcdef;
             let pool_open_time: u64 = 0x1203456789abcdef;
             let recent_epoch: u64 = 0x1023456789abcdef;
-            let mut padding1: [u64; 24] = [0u64; 24];
-            let mut padding1_data = [0u8; 8 * 24];
+            let reward_range_tick_lower: [i32; REWARD_NUM] = [-0x1234, -0x123, -0x12];
+            let reward_range_tick_upper: [i32; REWARD_NUM] = [0x1234, 0x123, 0x12];
+            let max_tick_deviation: u32 = 0x12345678;
+            let tick_deviation_period: u32 = 0x87654321;
+            let trade_fee_rate_override: u32 = 0x0001e240;
+            let trade_fee_rate_override_enabled: u8 = 1;
+            let padding5: [u8; 3] = [0x1b, 0x1c, 0x1d];
+            let creator_fees_token_0: u64 = 0x1234567089abcdef;
+            let creator_fees_token_1: u64 = 0x1234560789abcdef;
+            let seed_amm_config = Pubkey::new_unique();
+            let swap_hook_program = Pubkey::new_unique();
+            let swap_hook_account_count: u8 = 0x1e;
+            let padding6: [u8; 7] = [0x1f, 0x20, 0x21, 0x22, 0x23, 0x24, 0x25];
+            let mut padding1: [u64; 8] = [0u64; 8];
+            let mut padding1_data = [0u8; 8 * 8];
             let mut offset = 0;
-            for i in 0..24 {
+            for i in 0..8 {
                 padding1[i] = u64::MAX - i as u64;
                 padding1_data[offset..offset + 8].copy_from_slice(&padding1[i].to_le_bytes());
                 offset += 8;
@@ -1752,8 +2417,16 @@ pub mod pool_test {
             offset += 16;
             pool_data[offset..offset + 1].copy_from_slice(&status.to_le_bytes());
             offset += 1;
-            pool_data[offset..offset + 7].copy_from_slice(&padding);
-            offset += 7;
+            pool_data[offset..offset + 1].copy_from_slice(&extension_reward_count.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 1].copy_from_slice(&funded_reward_flags.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 1].copy_from_slice(&reward_range_flags.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 1].copy_from_slice(&scheduled_reward_flags.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 3].copy_from_slice(&padding);
+            offset += 3;
             pool_data[offset..offset + RewardInfo::LEN * REWARD_NUM]
                 .copy_from_slice(&reward_info_datas);
             offset += RewardInfo::LEN * REWARD_NUM;
@@ -1777,8 +2450,39 @@ pub mod pool_test {
             offset += 8;
             pool_data[offset..offset + 8].copy_from_slice(&recent_epoch.to_le_bytes());
             offset += 8;
-            pool_data[offset..offset + 8 * 24].copy_from_slice(&padding1_data);
-            offset += 8 * 24;
+            for tick in reward_range_tick_lower {
+                pool_data[offset..offset + 4].copy_from_slice(&tick.to_le_bytes());
+                offset += 4;
+            }
+            for tick in reward_range_tick_upper {
+                pool_data[offset..offset + 4].copy_from_slice(&tick.to_le_bytes());
+                offset += 4;
+            }
+            pool_data[offset..offset + 4].copy_from_slice(&max_tick_deviation.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&tick_deviation_period.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&trade_fee_rate_override.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 1]
+                .copy_from_slice(&trade_fee_rate_override_enabled.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 3].copy_from_slice(&padding5);
+            offset += 3;
+            pool_data[offset..offset + 8].copy_from_slice(&creator_fees_token_0.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&creator_fees_token_1.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 32].copy_from_slice(&seed_amm_config.to_bytes());
+            offset += 32;
+            pool_data[offset..offset + 32].copy_from_slice(&swap_hook_program.to_bytes());
+            offset += 32;
+            pool_data[offset..offset + 1].copy_from_slice(&swap_hook_account_count.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 7].copy_from_slice(&padding6);
+            offset += 7;
+            pool_data[offset..offset + 8 * 8].copy_from_slice(&padding1_data);
+            offset += 8 * 8;
             pool_data[offset..offset + 8 * 32].copy_from_slice(&padding2_data);
             offset += 8 * 32;
 
@@ -1841,6 +2545,14 @@ pub mod pool_test {
             assert_eq!(unpack_swap_out_amount_token_0, swap_out_amount_token_0);
             let unpack_status = unpack_data.status;
             assert_eq!(unpack_status, status);
+            let unpack_extension_reward_count = unpack_data.extension_reward_count;
+            assert_eq!(unpack_extension_reward_count, extension_reward_count);
+            let unpack_funded_reward_flags = unpack_data.funded_reward_flags;
+            assert_eq!(unpack_funded_reward_flags, funded_reward_flags);
+            let unpack_reward_range_flags = unpack_data.reward_range_flags;
+            assert_eq!(unpack_reward_range_flags, reward_range_flags);
+            let unpack_scheduled_reward_flags = unpack_data.scheduled_reward_flags;
+            assert_eq!(unpack_scheduled_reward_flags, scheduled_reward_flags);
             let unpack_padding = unpack_data.padding;
             assert_eq!(unpack_padding, padding);
 
@@ -1891,6 +2603,36 @@ pub mod pool_test {
             assert_eq!(unpack_open_time, pool_open_time);
             let unpack_recent_epoch = unpack_data.recent_epoch;
             assert_eq!(unpack_recent_epoch, recent_epoch);
+            let unpack_reward_range_tick_lower = unpack_data.reward_range_tick_lower;
+            assert_eq!(unpack_reward_range_tick_lower, reward_range_tick_lower);
+            let unpack_reward_range_tick_upper = unpack_data.reward_range_tick_upper;
+            assert_eq!(unpack_reward_range_tick_upper, reward_range_tick_upper);
+            let unpack_max_tick_deviation = unpack_data.max_tick_deviation;
+            assert_eq!(unpack_max_tick_deviation, max_tick_deviation);
+            let unpack_tick_deviation_period = unpack_data.tick_deviation_period;
+            assert_eq!(unpack_tick_deviation_period, tick_deviation_period);
+            let unpack_trade_fee_rate_override = unpack_data.trade_fee_rate_override;
+            assert_eq!(unpack_trade_fee_rate_override, trade_fee_rate_override);
+            let unpack_trade_fee_rate_override_enabled =
+                unpack_data.trade_fee_rate_override_enabled;
+            assert_eq!(
+                unpack_trade_fee_rate_override_enabled,
+                trade_fee_rate_override_enabled
+            );
+            let unpack_padding5 = unpack_data.padding5;
+            assert_eq!(unpack_padding5, padding5);
+            let unpack_creator_fees_token_0 = unpack_data.creator_fees_token_0;
+            assert_eq!(unpack_creator_fees_token_0, creator_fees_token_0);
+            let unpack_creator_fees_token_1 = unpack_data.creator_fees_token_1;
+            assert_eq!(unpack_creator_fees_token_1, creator_fees_token_1);
+            let unpack_seed_amm_config = unpack_data.seed_amm_config;
+            assert_eq!(unpack_seed_amm_config, seed_amm_config);
+            let unpack_swap_hook_program = unpack_data.swap_hook_program;
+            assert_eq!(unpack_swap_hook_program, swap_hook_program);
+            let unpack_swap_hook_account_count = unpack_data.swap_hook_account_count;
+            assert_eq!(unpack_swap_hook_account_count, swap_hook_account_count);
+            let unpack_padding6 = unpack_data.padding6;
+            assert_eq!(unpack_padding6, padding6);
             let unpack_padding1 = unpack_data.padding1;
             assert_eq!(unpack_padding1, padding1);
             let unpack_padding2 = unpack_data.padding2;
@@ -0,0 +1,161 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const POSITION_DELEGATE_SEED: &str = "position_delegate";
+
+/// The actions a delegate can be granted on a position
+pub enum PositionDelegatePermission {
+    /// Collect the fees and rewards owed, i.e. decrease liquidity by zero
+    Collect,
+    /// Decrease liquidity, which collects the fees and rewards owed as well
+    DecreaseLiquidity,
+    /// Increase liquidity with the delegate's tokens
+    IncreaseLiquidity,
+    /// Reinvest the fees owed as liquidity
+    Compound,
+}
+
+impl PositionDelegatePermission {
+    pub const FULL: u8 = (1 << 4) - 1;
+
+    pub fn bit(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+/// Grants a delegate the right to act on a position on behalf of the position NFT holder
+#[account]
+#[derive(Default, Debug)]
+pub struct PositionDelegateState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// Mint address of the tokenized position
+    pub nft_mint: Pubkey,
+    /// The position NFT holder who granted the delegate, the grant lapses once the NFT changes hands
+    pub owner: Pubkey,
+    /// The key allowed to act on the position
+    pub delegate: Pubkey,
+    /// Bitmask of the granted `PositionDelegatePermission`
+    pub permissions: u8,
+    /// The unix timestamp the grant expires at, 0 if it never expires
+    pub expiry: u64,
+    /// Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl PositionDelegateState {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 32 + 1 + 8 + 8 * 4;
+
+    pub fn key(nft_mint: Pubkey, delegate: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                POSITION_DELEGATE_SEED.as_bytes(),
+                nft_mint.as_ref(),
+                delegate.as_ref(),
+            ],
+            &crate::id(),
+        )
+        .0
+    }
+
+    /// Whether the grant is valid for `permission` at `timestamp`
+    pub fn is_permitted(&self, permission: PositionDelegatePermission, timestamp: u64) -> bool {
+        self.permissions & permission.bit() != 0 && (self.expiry == 0 || timestamp < self.expiry)
+    }
+}
+
+/// A delegate acting on a position, the payouts of which must go to the position NFT holder
+#[derive(Clone, Copy, Debug)]
+pub struct PositionDelegateAuthority {
+    /// The delegate account passed in the remaining accounts
+    pub position_delegate: Pubkey,
+    /// The position NFT holder
+    pub nft_holder: Pubkey,
+}
+
+/// Checks that `signer` is the position NFT holder, or a delegate granted `permission` by the
+/// holder. The delegate account is looked up in `remaining_accounts` by its address.
+///
+/// Returns the delegate authority if the signer is a delegate
+pub fn check_position_authority<'info>(
+    signer: Pubkey,
+    nft_holder: Pubkey,
+    nft_mint: Pubkey,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    permission: PositionDelegatePermission,
+) -> Result<Option<PositionDelegateAuthority>> {
+    if signer == nft_holder {
+        return Ok(None);
+    }
+    let position_delegate_key = PositionDelegateState::key(nft_mint, signer);
+    let position_delegate_info = remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == position_delegate_key)
+        .ok_or(ErrorCode::NotApproved)?;
+    let position_delegate = Account::<PositionDelegateState>::try_from(position_delegate_info)?;
+    require_keys_eq!(position_delegate.owner, nft_holder, ErrorCode::NotApproved);
+    require!(
+        position_delegate.is_permitted(permission, Clock::get()?.unix_timestamp as u64),
+        ErrorCode::NotApproved
+    );
+    Ok(Some(PositionDelegateAuthority {
+        position_delegate: position_delegate_key,
+        nft_holder,
+    }))
+}
+
+/// Emitted when a position delegate is granted or updated
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct GrantPositionDelegateEvent {
+    /// Mint address of the tokenized position
+    pub position_nft_mint: Pubkey,
+    /// The position NFT holder
+    pub owner: Pubkey,
+    /// The key allowed to act on the position
+    pub delegate: Pubkey,
+    /// Bitmask of the granted permissions
+    pub permissions: u8,
+    /// The unix timestamp the grant expires at, 0 if it never expires
+    pub expiry: u64,
+}
+
+/// Emitted when a position delegate is revoked
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RevokePositionDelegateEvent {
+    /// Mint address of the tokenized position
+    pub position_nft_mint: Pubkey,
+    /// The position NFT holder who granted the delegate
+    pub owner: Pubkey,
+    /// The revoked delegate
+    pub delegate: Pubkey,
+}
+
+#[cfg(test)]
+mod position_delegate_test {
+    use super::*;
+
+    #[test]
+    fn is_permitted_test() {
+        let mut position_delegate = PositionDelegateState {
+            permissions: PositionDelegatePermission::Collect.bit(),
+            ..Default::default()
+        };
+        assert!(position_delegate.is_permitted(PositionDelegatePermission::Collect, 1000));
+        assert!(
+            !position_delegate.is_permitted(PositionDelegatePermission::DecreaseLiquidity, 1000)
+        );
+
+        position_delegate.expiry = 1000;
+        assert!(position_delegate.is_permitted(PositionDelegatePermission::Collect, 999));
+        assert!(!position_delegate.is_permitted(PositionDelegatePermission::Collect, 1000));
+
+        position_delegate.expiry = 0;
+        position_delegate.permissions = PositionDelegatePermission::FULL;
+        assert!(position_delegate.is_permitted(PositionDelegatePermission::Collect, 1000));
+        assert!(position_delegate.is_permitted(PositionDelegatePermission::DecreaseLiquidity, 1000));
+        assert!(position_delegate.is_permitted(PositionDelegatePermission::IncreaseLiquidity, 1000));
+        assert!(position_delegate.is_permitted(PositionDelegatePermission::Compound, 1000));
+    }
+}
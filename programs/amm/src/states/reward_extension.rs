@@ -0,0 +1,700 @@
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv};
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::{prelude::*, system_program};
+#[cfg(feature = "enable-log")]
+use std::convert::identity;
+
+pub const POOL_REWARD_EXTENSION_SEED: &str = "pool_reward_extension";
+pub const TICK_ARRAY_REWARD_EXTENSION_SEED: &str = "tick_array_reward_extension";
+pub const POSITION_REWARD_EXTENSION_SEED: &str = "position_reward_extension";
+
+/// The number of reward slots of a reward extension, the extension rewards are addressed by the
+/// reward indexes following the pool rewards, from `REWARD_NUM` up to
+/// `REWARD_NUM + EXTENSION_REWARD_NUM`
+pub const EXTENSION_REWARD_NUM: usize = 5;
+
+/// Holds the reward slots of a pool beyond its `REWARD_NUM` slots.
+///
+/// PDA of `[POOL_REWARD_EXTENSION_SEED, pool_id]`
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct RewardExtensionState {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// The pool the rewards are emitted to
+    pub pool_id: Pubkey,
+    /// The extension reward infos, accrued with the liquidity of the pool like the pool rewards
+    pub reward_infos: [RewardInfo; EXTENSION_REWARD_NUM],
+    // account update recent epoch
+    pub recent_epoch: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 16],
+}
+
+impl RewardExtensionState {
+    pub const LEN: usize = 8 + 1 + 32 + RewardInfo::LEN * EXTENSION_REWARD_NUM + 8 + 8 * 16;
+
+    pub fn key(pool_id: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[POOL_REWARD_EXTENSION_SEED.as_bytes(), pool_id.as_ref()],
+            &crate::id(),
+        )
+        .0
+    }
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey, pool_creator: Pubkey) -> Result<()> {
+        self.bump = [bump];
+        self.pool_id = pool_id;
+        self.reward_infos = [RewardInfo::new(pool_creator); EXTENSION_REWARD_NUM];
+        self.recent_epoch = get_recent_epoch()?;
+        Ok(())
+    }
+
+    /// Initializes the lowest free extension reward slot, returns its index in the extension.
+    /// The reward mint must not be in use by the pool or the extension, and must not have a
+    /// freeze authority unless it is a pool token or a whitelisted mint.
+    pub fn initialize_reward(
+        &mut self,
+        pool_state: &PoolState,
+        open_time: u64,
+        end_time: u64,
+        reward_per_second_x64: u128,
+        token_mint: &Pubkey,
+        token_mint_freeze_authority: COption<Pubkey>,
+        token_vault: &Pubkey,
+        authority: &Pubkey,
+        operation_state: &OperationState,
+    ) -> Result<usize> {
+        let reward_infos = self.reward_infos;
+        let lowest_index = match reward_infos.iter().position(|r| !r.initialized()) {
+            Some(lowest_index) => lowest_index,
+            None => return Err(ErrorCode::FullRewardInfo.into()),
+        };
+        // check init token_mint is not already in use
+        let pool_reward_infos = pool_state.reward_infos;
+        require!(
+            !pool_reward_infos
+                .iter()
+                .chain(reward_infos.iter())
+                .any(|item| item.token_mint == *token_mint),
+            ErrorCode::RewardTokenAlreadyInUse
+        );
+        if *token_mint != pool_state.token_mint_0
+            && *token_mint != pool_state.token_mint_1
+            && !operation_state.whitelist_mints.contains(token_mint)
+        {
+            require!(
+                token_mint_freeze_authority.is_none(),
+                ErrorCode::ExceptRewardMint
+            );
+        }
+
+        self.reward_infos[lowest_index].last_update_time = open_time;
+        self.reward_infos[lowest_index].open_time = open_time;
+        self.reward_infos[lowest_index].end_time = end_time;
+        self.reward_infos[lowest_index].emissions_per_second_x64 = reward_per_second_x64;
+        self.reward_infos[lowest_index].token_mint = *token_mint;
+        self.reward_infos[lowest_index].token_vault = *token_vault;
+        self.reward_infos[lowest_index].authority = *authority;
+        #[cfg(feature = "enable-log")]
+        msg!(
+            "extension reward_index:{}, reward_infos:{:?}",
+            lowest_index,
+            self.reward_infos[lowest_index],
+        );
+        self.recent_epoch = get_recent_epoch()?;
+        Ok(lowest_index)
+    }
+
+    /// Accrues the extension rewards up to `curr_timestamp` with the in-range `liquidity` of the
+    /// pool, must be called with the pool liquidity before it changes.
+    pub fn update_reward_infos(
+        &mut self,
+        curr_timestamp: u64,
+        liquidity: u128,
+    ) -> Result<[RewardInfo; EXTENSION_REWARD_NUM]> {
+        let mut next_reward_infos = self.reward_infos;
+        for reward_info in next_reward_infos.iter_mut() {
+            reward_info.update(curr_timestamp, liquidity)?;
+        }
+        self.reward_infos = next_reward_infos;
+        self.recent_epoch = get_recent_epoch()?;
+        Ok(next_reward_infos)
+    }
+
+    pub fn check_unclaimed_reward(&self, index: usize, reward_amount_owed: u64) -> Result<()> {
+        assert!(index < EXTENSION_REWARD_NUM);
+        let unclaimed_reward = self.reward_infos[index]
+            .reward_total_emissioned
+            .checked_sub(self.reward_infos[index].reward_claimed)
+            .unwrap();
+        require_gte!(unclaimed_reward, reward_amount_owed);
+        Ok(())
+    }
+
+    pub fn add_reward_clamed(&mut self, index: usize, amount: u64) -> Result<()> {
+        assert!(index < EXTENSION_REWARD_NUM);
+        self.reward_infos[index].reward_claimed = self.reward_infos[index]
+            .reward_claimed
+            .checked_add(amount)
+            .unwrap();
+        Ok(())
+    }
+}
+
+/// Tracks the extension reward growths outside of the ticks of a tick array, the companion of
+/// `TickState.reward_growths_outside_x64`. The tick array itself need not exist.
+///
+/// PDA of `[TICK_ARRAY_REWARD_EXTENSION_SEED, pool_id, start_tick_index]`
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+pub struct TickArrayRewardExtension {
+    pub pool_id: Pubkey,
+    pub start_tick_index: i32,
+    /// Extension reward growths per unit of liquidity on the _other_ side of each tick, array of Q64.64
+    pub reward_growths_outside_x64: [[u128; EXTENSION_REWARD_NUM]; TICK_ARRAY_SIZE_USIZE],
+    // account update recent epoch
+    pub recent_epoch: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl Default for TickArrayRewardExtension {
+    #[inline]
+    fn default() -> TickArrayRewardExtension {
+        TickArrayRewardExtension {
+            pool_id: Pubkey::default(),
+            start_tick_index: 0,
+            reward_growths_outside_x64: [[0; EXTENSION_REWARD_NUM]; TICK_ARRAY_SIZE_USIZE],
+            recent_epoch: 0,
+            padding: [0; 8],
+        }
+    }
+}
+
+impl TickArrayRewardExtension {
+    pub const LEN: usize =
+        8 + 32 + 4 + 16 * EXTENSION_REWARD_NUM * TICK_ARRAY_SIZE_USIZE + 8 + 8 * 8;
+
+    pub fn key(pool_id: Pubkey, start_tick_index: i32) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                TICK_ARRAY_REWARD_EXTENSION_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &start_tick_index.to_be_bytes(),
+            ],
+            &crate::id(),
+        )
+        .0
+    }
+
+    /// Initializes the companion of a tick array, the ticks that are initialized already get their
+    /// growths like a tick initialized now
+    pub fn initialize(
+        &mut self,
+        pool_id: Pubkey,
+        start_tick_index: i32,
+        tick_array: Option<&TickArrayState>,
+        tick_spacing: u16,
+        tick_current: i32,
+        reward_infos: &[RewardInfo; EXTENSION_REWARD_NUM],
+    ) -> Result<()> {
+        self.pool_id = pool_id;
+        self.start_tick_index = start_tick_index;
+        if let Some(tick_array) = tick_array {
+            for offset in 0..TICK_ARRAY_SIZE_USIZE {
+                let tick_state = tick_array.ticks[offset];
+                if tick_state.is_initialized() {
+                    let tick = start_tick_index + (offset as i32) * i32::from(tick_spacing);
+                    self.initialize_tick(offset, tick, tick_current, reward_infos);
+                }
+            }
+        }
+        self.recent_epoch = get_recent_epoch()?;
+        Ok(())
+    }
+
+    /// Resets the growths outside of a tick getting liquidity for the first time, like
+    /// `TickState::update` does
+    pub fn initialize_tick(
+        &mut self,
+        offset: usize,
+        tick: i32,
+        tick_current: i32,
+        reward_infos: &[RewardInfo; EXTENSION_REWARD_NUM],
+    ) {
+        // by convention, we assume that all growth before a tick was initialized happened _below_ the tick
+        for i in 0..EXTENSION_REWARD_NUM {
+            self.reward_growths_outside_x64[offset][i] = if tick <= tick_current {
+                reward_infos[i].reward_growth_global_x64
+            } else {
+                0
+            };
+        }
+    }
+
+    /// Flips the growths outside of a tick crossed by a swap, like `TickState::cross` does
+    pub fn cross_tick(&mut self, offset: usize, reward_infos: &[RewardInfo; EXTENSION_REWARD_NUM]) {
+        for i in 0..EXTENSION_REWARD_NUM {
+            if !reward_infos[i].initialized() {
+                continue;
+            }
+            self.reward_growths_outside_x64[offset][i] = reward_infos[i]
+                .reward_growth_global_x64
+                .checked_sub(self.reward_growths_outside_x64[offset][i])
+                .unwrap();
+        }
+    }
+}
+
+/// Tracks the extension rewards of a position, the companion of `PersonalPositionState.reward_infos`.
+///
+/// PDA of `[POSITION_REWARD_EXTENSION_SEED, nft_mint]`
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct PositionRewardExtension {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// Mint address of the tokenized position
+    pub nft_mint: Pubkey,
+    /// The position liquidity as of the last settlement, a position starts earning the extension
+    /// rewards from its first settlement after the companion was created
+    pub liquidity: u128,
+    /// The extension reward growths inside the position range as of the last settlement, Q64.64
+    pub reward_growths_inside_last_x64: [u128; EXTENSION_REWARD_NUM],
+    /// The extension rewards owed to the position owner, as of the last settlement
+    pub reward_amounts_owed: [u64; EXTENSION_REWARD_NUM],
+    // account update recent epoch
+    pub recent_epoch: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl PositionRewardExtension {
+    pub const LEN: usize =
+        8 + 1 + 32 + 16 + 16 * EXTENSION_REWARD_NUM + 8 * EXTENSION_REWARD_NUM + 8 + 8 * 8;
+
+    pub fn key(nft_mint: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[POSITION_REWARD_EXTENSION_SEED.as_bytes(), nft_mint.as_ref()],
+            &crate::id(),
+        )
+        .0
+    }
+
+    pub fn initialize(&mut self, bump: u8, nft_mint: Pubkey) -> Result<()> {
+        self.bump = [bump];
+        self.nft_mint = nft_mint;
+        self.recent_epoch = get_recent_epoch()?;
+        Ok(())
+    }
+
+    /// Accrues the extension rewards earned by the liquidity of the last settlement, then records
+    /// `liquidity_after` for the next one
+    pub fn update_rewards(
+        &mut self,
+        reward_growths_inside: [u128; EXTENSION_REWARD_NUM],
+        liquidity_after: u128,
+    ) -> Result<()> {
+        for i in 0..EXTENSION_REWARD_NUM {
+            let reward_growth_delta =
+                reward_growths_inside[i].wrapping_sub(self.reward_growths_inside_last_x64[i]);
+            let amount_owed_delta = U256::from(reward_growth_delta)
+                .mul_div_floor(U256::from(self.liquidity), U256::from(fixed_point_64::Q64))
+                .unwrap()
+                .to_underflow_u64();
+            // Overflows not allowed. Must collect rewards owed before overflow.
+            self.reward_amounts_owed[i] = self.reward_amounts_owed[i]
+                .checked_add(amount_owed_delta)
+                .unwrap();
+            self.reward_growths_inside_last_x64[i] = reward_growths_inside[i];
+        }
+        #[cfg(feature = "enable-log")]
+        msg!(
+            "update position extension rewards, liquidity:{}, liquidity_after:{}, owed:{:?}",
+            identity(self.liquidity),
+            liquidity_after,
+            identity(self.reward_amounts_owed)
+        );
+        self.liquidity = liquidity_after;
+        self.recent_epoch = get_recent_epoch()?;
+        Ok(())
+    }
+}
+
+/// The reward extension accounts of a pool passed in the remaining accounts of an instruction
+pub struct RewardExtensionAccounts<'info> {
+    pub reward_extension: AccountLoad<'info, RewardExtensionState>,
+    /// The extension reward infos accrued up to the instruction
+    pub reward_infos: [RewardInfo; EXTENSION_REWARD_NUM],
+    pub tick_array_extensions: Vec<AccountLoad<'info, TickArrayRewardExtension>>,
+    pub position_extension: Option<AccountLoad<'info, PositionRewardExtension>>,
+}
+
+impl<'info> RewardExtensionAccounts<'info> {
+    pub fn has_discriminator(account_info: &AccountInfo, discriminator: &[u8]) -> bool {
+        account_info.owner == &crate::id()
+            && account_info
+                .try_borrow_data()
+                .map_or(false, |data| data.starts_with(discriminator))
+    }
+
+    /// Whether the account is one of the reward extension accounts or a reward schedule, or the
+    /// address of an uninitialized one, which other remaining account lists skip
+    pub fn is_extension_account(account_info: &AccountInfo) -> bool {
+        (account_info.owner == &system_program::ID && account_info.data_is_empty())
+            || Self::has_discriminator(account_info, RewardExtensionState::DISCRIMINATOR)
+            || Self::has_discriminator(account_info, RewardScheduleState::DISCRIMINATOR)
+            || Self::has_discriminator(account_info, TickArrayRewardExtension::DISCRIMINATOR)
+            || Self::has_discriminator(account_info, PositionRewardExtension::DISCRIMINATOR)
+    }
+
+    /// Finds the reward extension of the pool in the remaining accounts
+    pub fn find_reward_extension(
+        pool_id: Pubkey,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<Option<AccountLoad<'info, RewardExtensionState>>> {
+        for account_info in remaining_accounts {
+            if !Self::has_discriminator(account_info, RewardExtensionState::DISCRIMINATOR) {
+                continue;
+            }
+            let reward_extension = AccountLoad::<RewardExtensionState>::try_from(account_info)?;
+            if reward_extension.load()?.pool_id == pool_id {
+                return Ok(Some(reward_extension));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Finds the position companion of `nft_mint` in the remaining accounts
+    pub fn find_position_extension(
+        nft_mint: Pubkey,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<Option<AccountLoad<'info, PositionRewardExtension>>> {
+        for account_info in remaining_accounts {
+            if !Self::has_discriminator(account_info, PositionRewardExtension::DISCRIMINATOR) {
+                continue;
+            }
+            let position_extension =
+                AccountLoad::<PositionRewardExtension>::try_from(account_info)?;
+            if position_extension.load()?.nft_mint == nft_mint {
+                return Ok(Some(position_extension));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Loads the reward extension accounts of the pool from the remaining accounts and accrues the
+    /// extension rewards, must be called before the pool liquidity changes. Returns none if the
+    /// pool has no extension rewards, otherwise the reward extension is required, as is the
+    /// position companion of `nft_mint` if given.
+    pub fn load(
+        pool_state: &PoolState,
+        pool_id: Pubkey,
+        nft_mint: Option<Pubkey>,
+        remaining_accounts: &[AccountInfo<'info>],
+        curr_timestamp: u64,
+    ) -> Result<Option<Self>> {
+        if pool_state.extension_reward_count == 0 {
+            return Ok(None);
+        }
+        let reward_extension = Self::find_reward_extension(pool_id, remaining_accounts)?
+            .ok_or(ErrorCode::MissingRewardExtensionAccount)?;
+        let reward_infos = reward_extension
+            .load_mut()?
+            .update_reward_infos(curr_timestamp, pool_state.liquidity)?;
+
+        let mut tick_array_extensions = Vec::new();
+        for account_info in remaining_accounts {
+            if Self::has_discriminator(account_info, TickArrayRewardExtension::DISCRIMINATOR) {
+                let tick_array_extension =
+                    AccountLoad::<TickArrayRewardExtension>::try_from(account_info)?;
+                if tick_array_extension.load()?.pool_id == pool_id {
+                    tick_array_extensions.push(tick_array_extension);
+                }
+            }
+        }
+        let position_extension = match nft_mint {
+            Some(nft_mint) => Some(
+                Self::find_position_extension(nft_mint, remaining_accounts)?
+                    .ok_or(ErrorCode::MissingRewardExtensionAccount)?,
+            ),
+            None => None,
+        };
+        Ok(Some(Self {
+            reward_extension,
+            reward_infos,
+            tick_array_extensions,
+            position_extension,
+        }))
+    }
+
+    fn tick_array_extension(
+        &self,
+        start_tick_index: i32,
+    ) -> Option<&AccountLoad<'info, TickArrayRewardExtension>> {
+        self.tick_array_extensions
+            .iter()
+            .find(|tick_array_extension| {
+                tick_array_extension.load().map_or(false, |extension| {
+                    extension.start_tick_index == start_tick_index
+                })
+            })
+    }
+
+    /// Flips the extension growths of the ticks a swap crossed from `tick_before` to `tick_after`,
+    /// which are the initialized ticks of the swap tick arrays in between, the same ones
+    /// `TickState::cross` flipped. A tick array without companion needs the uninitialized
+    /// companion address passed, to prove none exists yet.
+    pub fn cross_ticks(
+        &self,
+        pool_id: Pubkey,
+        tick_array_infos: &[AccountInfo<'info>],
+        tick_before: i32,
+        tick_after: i32,
+    ) -> Result<()> {
+        if tick_before == tick_after {
+            return Ok(());
+        }
+        let (tick_min, tick_max) = (tick_before.min(tick_after), tick_before.max(tick_after));
+        let mut visited_start_indexes = Vec::new();
+        for account_info in tick_array_infos {
+            if !Self::has_discriminator(account_info, TickArrayState::DISCRIMINATOR) {
+                continue;
+            }
+            let tick_array_loader = AccountLoad::<TickArrayState>::try_from(account_info)?;
+            let tick_array = tick_array_loader.load()?;
+            let start_tick_index = tick_array.start_tick_index;
+            if tick_array.pool_id != pool_id || visited_start_indexes.contains(&start_tick_index) {
+                continue;
+            }
+            visited_start_indexes.push(start_tick_index);
+
+            let crossed_offsets: Vec<usize> = (0..TICK_ARRAY_SIZE_USIZE)
+                .filter(|&offset| {
+                    let tick_state = tick_array.ticks[offset];
+                    let tick = tick_state.tick;
+                    tick_state.is_initialized() && tick > tick_min && tick <= tick_max
+                })
+                .collect();
+            if crossed_offsets.is_empty() {
+                continue;
+            }
+            match self.tick_array_extension(start_tick_index) {
+                Some(tick_array_extension) => {
+                    let mut tick_array_extension = tick_array_extension.load_mut()?;
+                    for offset in crossed_offsets {
+                        tick_array_extension.cross_tick(offset, &self.reward_infos);
+                    }
+                    tick_array_extension.recent_epoch = get_recent_epoch()?;
+                }
+                None => {
+                    let key = TickArrayRewardExtension::key(pool_id, start_tick_index);
+                    require!(
+                        tick_array_infos
+                            .iter()
+                            .any(|account_info| account_info.key() == key
+                                && account_info.owner == &system_program::ID),
+                        ErrorCode::MissingRewardExtensionAccount
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Settles the extension rewards of a position whose liquidity changed by `liquidity_delta` to
+    /// `liquidity_after`, must be called after the ticks were updated. The tick companions of
+    /// the position range are required.
+    pub fn update_position(
+        &self,
+        tick_current: i32,
+        tick_spacing: u16,
+        tick_array_lower: &TickArrayState,
+        tick_array_upper: &TickArrayState,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        liquidity_delta: i128,
+        liquidity_after: u128,
+    ) -> Result<()> {
+        let mut reward_growths_outside = [[0u128; EXTENSION_REWARD_NUM]; 2];
+        for (i, (tick_array, tick_index)) in [
+            (tick_array_lower, tick_lower_index),
+            (tick_array_upper, tick_upper_index),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let offset = tick_array.get_tick_offset_in_array(tick_index, tick_spacing)?;
+            let tick_state = tick_array.ticks[offset];
+            let mut tick_array_extension = self
+                .tick_array_extension(tick_array.start_tick_index)
+                .ok_or(ErrorCode::MissingRewardExtensionAccount)?
+                .load_mut()?;
+            // the tick got liquidity for the first time
+            if liquidity_delta > 0 && tick_state.liquidity_gross == liquidity_delta as u128 {
+                tick_array_extension.initialize_tick(
+                    offset,
+                    tick_index,
+                    tick_current,
+                    &self.reward_infos,
+                );
+            }
+            reward_growths_outside[i] = tick_array_extension.reward_growths_outside_x64[offset];
+            tick_array_extension.recent_epoch = get_recent_epoch()?;
+        }
+
+        let mut reward_growths_inside = [0u128; EXTENSION_REWARD_NUM];
+        for i in 0..EXTENSION_REWARD_NUM {
+            if !self.reward_infos[i].initialized() {
+                continue;
+            }
+            reward_growths_inside[i] = get_reward_growth_inside(
+                tick_lower_index,
+                reward_growths_outside[0][i],
+                tick_upper_index,
+                reward_growths_outside[1][i],
+                tick_current,
+                self.reward_infos[i].reward_growth_global_x64,
+            );
+        }
+        self.position_extension
+            .as_ref()
+            .ok_or(ErrorCode::MissingRewardExtensionAccount)?
+            .load_mut()?
+            .update_rewards(reward_growths_inside, liquidity_after)
+    }
+}
+
+/// Emitted when the extension rewards of a position are collected
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CollectExtensionRewardEvent {
+    /// The ID of the token for which the rewards were collected
+    pub position_nft_mint: Pubkey,
+    /// The amounts of the extension rewards collected
+    pub reward_amounts: [u64; EXTENSION_REWARD_NUM],
+}
+
+#[cfg(test)]
+mod reward_extension_test {
+    use super::*;
+
+    fn build_reward_infos(
+        reward_growths_global_x64: [u128; EXTENSION_REWARD_NUM],
+    ) -> [RewardInfo; EXTENSION_REWARD_NUM] {
+        let mut reward_infos = [RewardInfo::default(); EXTENSION_REWARD_NUM];
+        for i in 0..EXTENSION_REWARD_NUM {
+            reward_infos[i].token_mint = Pubkey::new_unique();
+            reward_infos[i].reward_growth_global_x64 = reward_growths_global_x64[i];
+        }
+        reward_infos
+    }
+
+    #[test]
+    fn update_rewards_accrues_at_last_liquidity_test() {
+        let mut position_extension = PositionRewardExtension::default();
+        let q64 = fixed_point_64::Q64;
+        // the first settlement of an existing position only records the snapshot
+        position_extension
+            .update_rewards([q64, 0, 0, 0, 0], 1000)
+            .unwrap();
+        assert_eq!(
+            { position_extension.reward_amounts_owed },
+            [0; EXTENSION_REWARD_NUM]
+        );
+
+        position_extension
+            .update_rewards([q64 * 3, 0, 0, 0, q64 / 2], 0)
+            .unwrap();
+        assert_eq!(
+            { position_extension.reward_amounts_owed },
+            [2000, 0, 0, 0, 500]
+        );
+        assert_eq!({ position_extension.liquidity }, 0);
+
+        // no liquidity, nothing earned
+        position_extension
+            .update_rewards([q64 * 5, 0, 0, 0, q64], 10)
+            .unwrap();
+        assert_eq!(
+            { position_extension.reward_amounts_owed },
+            [2000, 0, 0, 0, 500]
+        );
+    }
+
+    #[test]
+    fn initialize_and_cross_tick_test() {
+        let mut tick_array_extension = TickArrayRewardExtension::default();
+        let mut reward_infos = build_reward_infos([100, 200, 300, 400, 500]);
+        reward_infos[4].token_mint = Pubkey::default();
+
+        tick_array_extension.initialize_tick(0, -60, 0, &reward_infos);
+        tick_array_extension.initialize_tick(1, 60, 0, &reward_infos);
+        assert_eq!(
+            { tick_array_extension.reward_growths_outside_x64 }[0],
+            [100, 200, 300, 400, 500]
+        );
+        assert_eq!(
+            { tick_array_extension.reward_growths_outside_x64 }[1],
+            [0; 5]
+        );
+
+        let reward_infos = {
+            let mut next = build_reward_infos([150, 250, 350, 450, 550]);
+            next[4].token_mint = Pubkey::default();
+            next
+        };
+        tick_array_extension.cross_tick(1, &reward_infos);
+        // uninitialized rewards are not flipped
+        assert_eq!(
+            { tick_array_extension.reward_growths_outside_x64 }[1],
+            [150, 250, 350, 450, 0]
+        );
+    }
+
+    #[test]
+    fn tick_array_extension_tracks_tick_state_test() {
+        let tick_spacing = 10;
+        let mut reward_infos = [RewardInfo::default(); EXTENSION_REWARD_NUM];
+        reward_infos[0].token_mint = Pubkey::new_unique();
+        let mut base_reward_infos = [RewardInfo::default(); REWARD_NUM];
+        base_reward_infos[0].token_mint = Pubkey::new_unique();
+
+        let mut tick_state = TickState::default();
+        tick_state.tick = 100;
+        let mut tick_array_extension = TickArrayRewardExtension::default();
+        let offset = 10;
+
+        // the same accrual drives both, the companion must follow the tick state
+        let mut tick_current = 0;
+        let mut growth = 0u128;
+        for step in 1..20u128 {
+            growth += step * 7;
+            reward_infos[0].reward_growth_global_x64 = growth;
+            base_reward_infos[0].reward_growth_global_x64 = growth;
+            if step == 3 {
+                tick_state
+                    .update(tick_current, 1000, 0, 0, false, &base_reward_infos, 0)
+                    .unwrap();
+                tick_array_extension.initialize_tick(offset, 100, tick_current, &reward_infos);
+            } else if step > 3 && step % 4 == 0 {
+                // cross the tick up or down
+                tick_current = if tick_current < 100 { 100 } else { 99 };
+                tick_state.cross(0, 0, &base_reward_infos, 0);
+                tick_array_extension.cross_tick(offset, &reward_infos);
+            }
+            if step >= 3 {
+                assert_eq!(
+                    { tick_state.reward_growths_outside_x64 }[0],
+                    { tick_array_extension.reward_growths_outside_x64 }[offset][0]
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use super::pool::RewardInfo;
+use crate::libraries::{big_num::U128, full_math::MulDiv};
+use anchor_lang::prelude::*;
+
+pub const REWARD_FUNDING_SEED: &str = "reward_funding";
+pub const REWARD_FUNDER_SEED: &str = "reward_funder";
+
+/// Tracks the tokens deposited into a reward vault by funders other than the reward authority,
+/// so the amount left unemitted once the reward ends can be refunded pro rata
+#[account]
+#[derive(Default, Debug)]
+pub struct RewardFundingState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the reward belongs to
+    pub pool_id: Pubkey,
+    /// The reward index, the indexes from REWARD_NUM are of the reward extension
+    pub reward_index: u8,
+    /// The tokens in the vault not owed to positions when the first funder deposited, plus every
+    /// deposit since, the base the unemitted amount is split on
+    pub budget: u64,
+    /// The funder deposits not refunded yet
+    pub total_funded: u64,
+    /// The tokens left unemitted in the vault once the reward ended, set when settled
+    pub remaining_amount: u64,
+    /// Whether the unemitted amount has been recorded, no funder can deposit until every funder
+    /// has been refunded
+    pub settled: bool,
+    /// Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl RewardFundingState {
+    pub const LEN: usize = 8 + 1 + 32 + 1 + 8 + 8 + 8 + 1 + 8 * 4;
+
+    pub fn key(pool_id: Pubkey, reward_index: u8) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                REWARD_FUNDING_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &[reward_index],
+            ],
+            &crate::id(),
+        )
+        .0
+    }
+
+    /// Starts tracking a new round of deposits, `unowed_amount` being the tokens in the vault not
+    /// owed to positions
+    pub fn reset(&mut self, unowed_amount: u64) {
+        self.budget = unowed_amount;
+        self.total_funded = 0;
+        self.remaining_amount = 0;
+        self.settled = false;
+    }
+
+    pub fn fund(&mut self, amount: u64) {
+        self.budget = self.budget.checked_add(amount).unwrap();
+        self.total_funded = self.total_funded.checked_add(amount).unwrap();
+    }
+
+    /// Records the tokens left unemitted once the reward ended
+    pub fn settle(&mut self, unowed_amount: u64) {
+        if !self.settled {
+            self.remaining_amount = unowed_amount;
+            self.settled = true;
+        }
+    }
+
+    /// The share of the unemitted amount refunded for a `contribution`, rounded down
+    pub fn refund_amount(&self, contribution: u64) -> u64 {
+        if self.budget == 0 {
+            return 0;
+        }
+        U128::from(self.remaining_amount)
+            .mul_div_floor(U128::from(contribution), U128::from(self.budget))
+            .unwrap()
+            .as_u64()
+    }
+
+    /// The share of the unemitted amount kept aside for the funders not refunded yet, rounded up
+    pub fn reserved_amount(&self) -> u64 {
+        if self.budget == 0 {
+            return 0;
+        }
+        U128::from(self.remaining_amount)
+            .mul_div_ceil(U128::from(self.total_funded), U128::from(self.budget))
+            .unwrap()
+            .as_u64()
+    }
+
+    /// Refunds a funder, returns the amount to transfer
+    pub fn refund(&mut self, funder: &mut RewardFunderState) -> u64 {
+        let amount = self.refund_amount(funder.amount);
+        self.total_funded = self.total_funded.checked_sub(funder.amount).unwrap();
+        funder.amount = 0;
+        amount
+    }
+}
+
+/// The deposits of a funder into a reward
+#[account]
+#[derive(Default, Debug)]
+pub struct RewardFunderState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The reward funding account
+    pub reward_funding: Pubkey,
+    /// The funder refunded on collect remaining rewards
+    pub funder: Pubkey,
+    /// The deposits not refunded yet
+    pub amount: u64,
+    /// Unused bytes for future upgrades.
+    pub padding: [u64; 2],
+}
+
+impl RewardFunderState {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 8 + 8 * 2;
+
+    pub fn key(reward_funding: Pubkey, funder: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                REWARD_FUNDER_SEED.as_bytes(),
+                reward_funding.as_ref(),
+                funder.as_ref(),
+            ],
+            &crate::id(),
+        )
+        .0
+    }
+}
+
+/// The tokens in the reward vault not owed to positions, i.e. not emitted yet or never emitted
+pub fn get_unowed_reward_amount(reward_info: &RewardInfo, vault_amount: u64) -> u64 {
+    vault_amount.saturating_sub(
+        reward_info
+            .reward_total_emissioned
+            .saturating_sub(reward_info.reward_claimed),
+    )
+}
+
+/// Emitted when a funder deposits into a reward
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FundRewardEvent {
+    /// The pool the reward belongs to
+    pub pool_state: Pubkey,
+    /// The funder
+    pub funder: Pubkey,
+    /// The reward index
+    pub reward_index: u8,
+    /// The amount received by the reward vault
+    pub amount: u64,
+    /// The reward end time after the deposit
+    pub end_time: u64,
+    /// The reward emissions per second after the deposit
+    pub emissions_per_second_x64: u128,
+}
+
+/// Emitted when the pool owner refunds a funder its share of the unemitted tokens
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RefundRewardFunderEvent {
+    /// The pool the reward belongs to
+    pub pool_state: Pubkey,
+    /// The funder refunded
+    pub funder: Pubkey,
+    /// The reward index
+    pub reward_index: u8,
+    /// The amount refunded
+    pub amount: u64,
+}
+
+#[cfg(test)]
+mod reward_funding_test {
+    use super::*;
+
+    #[test]
+    fn refund_pro_rata_test() {
+        let mut reward_funding = RewardFundingState::default();
+        // 1000 left from the authority, two funders deposit 500 and 1500
+        reward_funding.reset(1000);
+        reward_funding.fund(500);
+        reward_funding.fund(1500);
+        assert_eq!(reward_funding.budget, 3000);
+        assert_eq!(reward_funding.total_funded, 2000);
+
+        // a third of the budget was not emitted
+        reward_funding.settle(1000);
+        // settling again keeps the first record
+        reward_funding.settle(10);
+        assert_eq!(reward_funding.remaining_amount, 1000);
+        assert_eq!(reward_funding.reserved_amount(), 667);
+
+        let mut funder_0 = RewardFunderState {
+            amount: 500,
+            ..Default::default()
+        };
+        assert_eq!(reward_funding.refund(&mut funder_0), 166);
+        assert_eq!(funder_0.amount, 0);
+        assert_eq!(reward_funding.total_funded, 1500);
+        assert_eq!(reward_funding.reserved_amount(), 500);
+
+        let mut funder_1 = RewardFunderState {
+            amount: 1500,
+            ..Default::default()
+        };
+        assert_eq!(reward_funding.refund(&mut funder_1), 500);
+        assert_eq!(reward_funding.reserved_amount(), 0);
+    }
+
+    #[test]
+    fn get_unowed_reward_amount_test() {
+        let info = RewardInfo {
+            reward_total_emissioned: 4000,
+            reward_claimed: 1000,
+            ..Default::default()
+        };
+        assert_eq!(get_unowed_reward_amount(&info, 10000), 7000);
+        assert_eq!(get_unowed_reward_amount(&info, 2000), 0);
+    }
+}
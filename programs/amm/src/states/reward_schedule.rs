@@ -0,0 +1,289 @@
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv};
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+pub const REWARD_SCHEDULE_SEED: &str = "reward_schedule";
+
+/// The maximum number of points of a reward schedule
+pub const REWARD_SCHEDULE_POINT_NUM: usize = 16;
+
+/// The start of a segment of a reward schedule, the emissions hold until the next point
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct RewardSchedulePoint {
+    /// The unix timestamp the segment starts at
+    pub timestamp: u64,
+    /// Q64.64 number of reward tokens emitted per second from the timestamp
+    pub emissions_per_second_x64: u128,
+}
+
+impl RewardSchedulePoint {
+    pub const LEN: usize = 8 + 16;
+}
+
+/// The emissions of a pool reward over time, in steps. While the schedule runs the reward must be
+/// accrued with it, the schedule account is then required by the instructions updating rewards.
+#[account]
+#[derive(Default, Debug)]
+pub struct RewardScheduleState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the reward belongs to
+    pub pool_id: Pubkey,
+    /// The reward index in the pool
+    pub reward_index: u8,
+    /// The number of points used
+    pub point_count: u8,
+    /// The points sorted by timestamp, the last one starts the final segment up to the reward
+    /// end time
+    pub points: [RewardSchedulePoint; REWARD_SCHEDULE_POINT_NUM],
+    /// Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl RewardScheduleState {
+    pub const LEN: usize =
+        8 + 1 + 32 + 1 + 1 + RewardSchedulePoint::LEN * REWARD_SCHEDULE_POINT_NUM + 8 * 4;
+
+    pub fn key(pool_id: Pubkey, reward_index: u8) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                REWARD_SCHEDULE_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &[reward_index],
+            ],
+            &crate::id(),
+        )
+        .0
+    }
+
+    pub fn points(&self) -> &[RewardSchedulePoint] {
+        &self.points[..self.point_count as usize]
+    }
+
+    /// Checks the points are sorted, start no earlier than `start_time` and end before `end_time`
+    pub fn check_points(
+        points: &[RewardSchedulePoint],
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<()> {
+        require!(
+            !points.is_empty() && points.len() <= REWARD_SCHEDULE_POINT_NUM,
+            ErrorCode::InvalidRewardSchedule
+        );
+        require_gte!(
+            points[0].timestamp,
+            start_time,
+            ErrorCode::InvalidRewardSchedule
+        );
+        for (point, next_point) in points.iter().zip(points.iter().skip(1)) {
+            require_gt!(
+                next_point.timestamp,
+                point.timestamp,
+                ErrorCode::InvalidRewardSchedule
+            );
+        }
+        require_gt!(
+            end_time,
+            points[points.len() - 1].timestamp,
+            ErrorCode::InvalidRewardSchedule
+        );
+        Ok(())
+    }
+
+    /// The amount emitted by the points up to `end_time`, rounded up
+    pub fn get_reward_amount(points: &[RewardSchedulePoint], end_time: u64) -> u64 {
+        let mut reward_amount = 0u64;
+        for (i, point) in points.iter().enumerate() {
+            let segment_end_time = points.get(i + 1).map_or(end_time, |next| next.timestamp);
+            reward_amount = reward_amount
+                .checked_add(
+                    U256::from(segment_end_time - point.timestamp)
+                        .mul_div_ceil(
+                            U256::from(point.emissions_per_second_x64),
+                            U256::from(fixed_point_64::Q64),
+                        )
+                        .unwrap()
+                        .as_u64(),
+                )
+                .unwrap();
+        }
+        reward_amount
+    }
+
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        pool_id: Pubkey,
+        reward_index: u8,
+        points: &[RewardSchedulePoint],
+    ) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.reward_index = reward_index;
+        self.point_count = points.len() as u8;
+        self.points = [RewardSchedulePoint::default(); REWARD_SCHEDULE_POINT_NUM];
+        self.points[..points.len()].copy_from_slice(points);
+    }
+
+    /// Accrues the reward up to `curr_timestamp`, switching its emissions at each point passed
+    pub fn update_reward_info(
+        &self,
+        reward_info: &mut RewardInfo,
+        curr_timestamp: u64,
+        liquidity: u128,
+    ) -> Result<()> {
+        for point in self.points() {
+            // a point at the last update time starts the segment in progress, set it again
+            if point.timestamp < reward_info.last_update_time {
+                continue;
+            }
+            if point.timestamp > curr_timestamp.min(reward_info.end_time) {
+                break;
+            }
+            reward_info.update(point.timestamp, liquidity)?;
+            reward_info.emissions_per_second_x64 = point.emissions_per_second_x64;
+        }
+        reward_info.update(curr_timestamp, liquidity)
+    }
+}
+
+/// Accrues the pool rewards following a schedule up to `curr_timestamp`, must be called before
+/// `PoolState::update_reward_infos` which accrues at constant emissions. The schedules are looked
+/// up in `remaining_accounts` and required for the rewards flagged in `scheduled_reward_flags`
+/// until they end.
+pub fn update_scheduled_reward_infos(
+    pool_state: &mut PoolState,
+    pool_id: Pubkey,
+    remaining_accounts: &[AccountInfo],
+    curr_timestamp: u64,
+) -> Result<()> {
+    if pool_state.scheduled_reward_flags == 0 {
+        return Ok(());
+    }
+    for reward_index in 0..REWARD_NUM {
+        let mut reward_info = pool_state.reward_infos[reward_index];
+        // an ended reward accrues no more, its schedule is no longer needed
+        if pool_state.scheduled_reward_flags & (1 << reward_index) == 0
+            || reward_info.last_update_time == reward_info.end_time
+        {
+            continue;
+        }
+        let reward_schedule =
+            find_reward_schedule(pool_id, reward_index as u8, remaining_accounts)?
+                .ok_or(ErrorCode::MissingRewardScheduleAccount)?;
+        let liquidity = if pool_state.is_in_reward_range(reward_index) {
+            pool_state.liquidity
+        } else {
+            0
+        };
+        reward_schedule.update_reward_info(&mut reward_info, curr_timestamp, liquidity)?;
+        pool_state.reward_infos[reward_index] = reward_info;
+    }
+    Ok(())
+}
+
+/// Finds the schedule of a pool reward in the remaining accounts
+pub fn find_reward_schedule(
+    pool_id: Pubkey,
+    reward_index: u8,
+    remaining_accounts: &[AccountInfo],
+) -> Result<Option<RewardScheduleState>> {
+    for account_info in remaining_accounts {
+        if !RewardExtensionAccounts::has_discriminator(
+            account_info,
+            RewardScheduleState::DISCRIMINATOR,
+        ) {
+            continue;
+        }
+        let reward_schedule =
+            RewardScheduleState::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        if reward_schedule.pool_id == pool_id && reward_schedule.reward_index == reward_index {
+            return Ok(Some(reward_schedule));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod reward_schedule_test {
+    use super::*;
+    use std::convert::identity;
+
+    fn point(timestamp: u64, emissions_per_second: u128) -> RewardSchedulePoint {
+        RewardSchedulePoint {
+            timestamp,
+            emissions_per_second_x64: emissions_per_second << 64,
+        }
+    }
+
+    #[test]
+    fn check_points_test() {
+        let points = [point(100, 1), point(200, 2)];
+        assert!(RewardScheduleState::check_points(&points, 100, 300).is_ok());
+        // starts too early
+        assert!(RewardScheduleState::check_points(&points, 101, 300).is_err());
+        // ends before the last point
+        assert!(RewardScheduleState::check_points(&points, 100, 200).is_err());
+        // not sorted
+        assert!(
+            RewardScheduleState::check_points(&[point(200, 1), point(200, 2)], 100, 300).is_err()
+        );
+        assert!(RewardScheduleState::check_points(&[], 100, 300).is_err());
+    }
+
+    #[test]
+    fn get_reward_amount_test() {
+        let points = [point(100, 1), point(200, 3), point(300, 0)];
+        assert_eq!(RewardScheduleState::get_reward_amount(&points, 400), 400);
+        assert_eq!(
+            RewardScheduleState::get_reward_amount(&points[..2], 250),
+            250
+        );
+    }
+
+    #[test]
+    fn update_reward_info_across_points_test() {
+        let mut reward_schedule = RewardScheduleState::default();
+        reward_schedule.initialize(0, Pubkey::default(), 0, &[point(200, 3), point(300, 0)]);
+        let mut reward_info = RewardInfo {
+            token_mint: Pubkey::new_unique(),
+            open_time: 100,
+            end_time: 400,
+            last_update_time: 100,
+            emissions_per_second_x64: 1 << 64,
+            ..Default::default()
+        };
+
+        // one update across both points integrates each segment at its rate
+        reward_schedule
+            .update_reward_info(&mut reward_info, 350, 1 << 64)
+            .unwrap();
+        assert_eq!(identity(reward_info.reward_total_emissioned), 100 + 300);
+        assert_eq!(identity(reward_info.last_update_time), 350);
+        assert_eq!(identity(reward_info.emissions_per_second_x64), 0);
+
+        // the same as updating at each point
+        let mut reward_info_stepped = RewardInfo {
+            token_mint: Pubkey::new_unique(),
+            open_time: 100,
+            end_time: 400,
+            last_update_time: 100,
+            emissions_per_second_x64: 1 << 64,
+            ..Default::default()
+        };
+        for timestamp in [150, 200, 250, 300, 350] {
+            reward_schedule
+                .update_reward_info(&mut reward_info_stepped, timestamp, 1 << 64)
+                .unwrap();
+        }
+        assert_eq!(
+            identity(reward_info_stepped.reward_total_emissioned),
+            identity(reward_info.reward_total_emissioned)
+        );
+        assert_eq!(
+            identity(reward_info_stepped.reward_growth_global_x64),
+            identity(reward_info.reward_growth_global_x64)
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use super::pool::PoolState;
+use super::{get_amount_0_for_amount_1, get_amount_1_for_amount_0};
 use crate::error::ErrorCode;
-use crate::libraries::{liquidity_math, tick_math};
+use crate::libraries::{
+    big_num::U128, fixed_point_64, full_math::MulDiv, liquidity_math, tick_math,
+};
 use crate::pool::{RewardInfo, REWARD_NUM};
 use crate::util::*;
 use crate::Result;
@@ -143,7 +146,7 @@ impl TickArrayState {
     }
 
     /// Get tick's offset in current tick array, tick must be include in tick array， otherwise throw an error
-    fn get_tick_offset_in_array(self, tick_index: i32, tick_spacing: u16) -> Result<usize> {
+    pub fn get_tick_offset_in_array(&self, tick_index: i32, tick_spacing: u16) -> Result<usize> {
         let start_tick_index = TickArrayState::get_array_start_index(tick_index, tick_spacing);
         require_eq!(
             start_tick_index,
@@ -281,12 +284,23 @@ pub struct TickState {
 
     // Reward growth per unit of liquidity like fee, array of Q64.64
     pub reward_growths_outside_x64: [u128; REWARD_NUM],
-    // Unused bytes for future upgrades.
-    pub padding: [u32; 13],
+
+    /// Incremented every time the limit orders resting on this tick are completely filled
+    pub order_phase: u64,
+    /// The unfilled amount of limit orders resting on this tick, tracked apart from liquidity_net.
+    /// Orders above the current tick sell token_0, orders at or below it sell token_1
+    pub order_amount: u64,
+    /// The fraction of the current phase orders that is still unfilled, Q64.64, zero means one
+    pub order_remaining_x64: u128,
+    /// The number of open limit order accounts that reference this tick
+    pub order_count: u32,
+    /// Seconds per unit of in-range liquidity on the _other_ side of this tick, Q64.64, only has
+    /// relative meaning like the fee growth outside
+    pub seconds_per_liquidity_outside_x64: u128,
 }
 
 impl TickState {
-    pub const LEN: usize = 4 + 16 + 16 + 16 + 16 + 16 * REWARD_NUM + 16 + 16 + 8 + 8 + 4;
+    pub const LEN: usize = 4 + 16 + 16 + 16 + 16 + 16 * REWARD_NUM + 8 + 8 + 16 + 4 + 16;
 
     pub fn initialize(&mut self, tick: i32, tick_spacing: u16) -> Result<()> {
         if TickState::check_is_out_of_boundary(tick) {
@@ -308,20 +322,29 @@ impl TickState {
         fee_growth_global_1_x64: u128,
         upper: bool,
         reward_infos: &[RewardInfo; REWARD_NUM],
+        seconds_per_liquidity_cumulative_x64: u128,
     ) -> Result<bool> {
         let liquidity_gross_before = self.liquidity_gross;
         let liquidity_gross_after =
             liquidity_math::add_delta(liquidity_gross_before, liquidity_delta)?;
 
         // Either liquidity_gross_after becomes 0 (uninitialized) XOR liquidity_gross_before
-        // was zero (initialized)
-        let flipped = (liquidity_gross_after == 0) != (liquidity_gross_before == 0);
+        // was zero (initialized), resting limit orders keep the tick initialized
+        let flipped = self.order_count == 0
+            && ((liquidity_gross_after == 0) != (liquidity_gross_before == 0));
         if liquidity_gross_before == 0 {
             // by convention, we assume that all growth before a tick was initialized happened _below_ the tick
             if self.tick <= tick_current {
                 self.fee_growth_outside_0_x64 = fee_growth_global_0_x64;
                 self.fee_growth_outside_1_x64 = fee_growth_global_1_x64;
                 self.reward_growths_outside_x64 = RewardInfo::get_reward_growths(reward_infos);
+                self.seconds_per_liquidity_outside_x64 = seconds_per_liquidity_cumulative_x64;
+            } else {
+                // the growth may have been flipped by crossings while only orders rested on the tick
+                self.fee_growth_outside_0_x64 = 0;
+                self.fee_growth_outside_1_x64 = 0;
+                self.reward_growths_outside_x64 = [0; REWARD_NUM];
+                self.seconds_per_liquidity_outside_x64 = 0;
             }
         }
 
@@ -345,6 +368,7 @@ impl TickState {
         fee_growth_global_0_x64: u128,
         fee_growth_global_1_x64: u128,
         reward_infos: &[RewardInfo; REWARD_NUM],
+        seconds_per_liquidity_cumulative_x64: u128,
     ) -> i128 {
         self.fee_growth_outside_0_x64 = fee_growth_global_0_x64
             .checked_sub(self.fee_growth_outside_0_x64)
@@ -363,20 +387,104 @@ impl TickState {
                 .checked_sub(self.reward_growths_outside_x64[i])
                 .unwrap();
         }
+        // the cumulative is allowed to overflow like the observations it is read from
+        self.seconds_per_liquidity_outside_x64 = seconds_per_liquidity_cumulative_x64
+            .wrapping_sub(self.seconds_per_liquidity_outside_x64);
 
         self.liquidity_net
     }
 
+    /// Fills the limit orders resting on this tick with up to `amount_remaining` of a swap that
+    /// reached the tick price, must be called before the tick is crossed. Orders are filled at the
+    /// tick price without trade fee. Returns the swap input and output amounts of the fill.
+    pub fn fill_orders(
+        &mut self,
+        amount_remaining: u64,
+        zero_for_one: bool,
+        is_base_input: bool,
+    ) -> Result<(u64, u64)> {
+        if self.order_amount == 0 || amount_remaining == 0 {
+            return Ok((0, 0));
+        }
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(self.tick)?;
+        // the orders sell the output token of the swap
+        let get_amount_out = |amount_in: u64| {
+            if zero_for_one {
+                get_amount_1_for_amount_0(amount_in, sqrt_price_x64, false)
+            } else {
+                get_amount_0_for_amount_1(amount_in, sqrt_price_x64, false)
+            }
+        };
+        let get_amount_in = |amount_out: u64| {
+            if zero_for_one {
+                get_amount_0_for_amount_1(amount_out, sqrt_price_x64, true)
+            } else {
+                get_amount_1_for_amount_0(amount_out, sqrt_price_x64, true)
+            }
+        };
+
+        let (amount_in, amount_out) = if is_base_input {
+            let amount_out = get_amount_out(amount_remaining);
+            if amount_out >= self.order_amount {
+                (
+                    get_amount_in(self.order_amount).min(amount_remaining),
+                    self.order_amount,
+                )
+            } else {
+                // the swap ends on this tick, an input too small to buy anything is kept by the pool
+                (amount_remaining, amount_out)
+            }
+        } else {
+            let amount_out = amount_remaining.min(self.order_amount);
+            (get_amount_in(amount_out), amount_out)
+        };
+        require_neq!(amount_in, u64::MAX, ErrorCode::CalculateOverflow);
+
+        self.consume_orders(amount_out, true);
+        Ok((amount_in, amount_out))
+    }
+
+    /// Removes `amount` from the unfilled orders of the tick, either filled by a swap or
+    /// withdrawn by a cancellation. Starts a new phase once no unfilled order is left.
+    pub fn consume_orders(&mut self, amount: u64, filled: bool) {
+        let order_amount_after = self.order_amount.checked_sub(amount).unwrap();
+        if order_amount_after == 0 {
+            self.order_phase = self.order_phase.checked_add(1).unwrap();
+            self.order_remaining_x64 = 0;
+        } else if filled && amount != 0 {
+            // rounded up, the ratio of a tick with unfilled orders never reaches zero and the
+            // orders are never credited more than was filled
+            self.order_remaining_x64 = U128::from(self.order_remaining_ratio_x64())
+                .mul_div_ceil(
+                    U128::from(order_amount_after),
+                    U128::from(self.order_amount),
+                )
+                .unwrap()
+                .as_u128();
+        }
+        self.order_amount = order_amount_after;
+    }
+
+    /// The fraction of the current phase orders that is still unfilled, Q64.64
+    pub fn order_remaining_ratio_x64(&self) -> u128 {
+        if self.order_remaining_x64 == 0 {
+            fixed_point_64::Q64
+        } else {
+            self.order_remaining_x64
+        }
+    }
+
     pub fn clear(&mut self) {
         self.liquidity_net = 0;
         self.liquidity_gross = 0;
         self.fee_growth_outside_0_x64 = 0;
         self.fee_growth_outside_1_x64 = 0;
         self.reward_growths_outside_x64 = [0; REWARD_NUM];
+        self.seconds_per_liquidity_outside_x64 = 0;
     }
 
     pub fn is_initialized(self) -> bool {
-        self.liquidity_gross != 0
+        self.liquidity_gross != 0 || self.order_count != 0
     }
 
     /// Common checks for a valid tick input.
@@ -453,34 +561,19 @@ pub fn get_reward_growths_inside(
             continue;
         }
 
-        let reward_growths_below = if tick_current_index >= tick_lower.tick {
-            tick_lower.reward_growths_outside_x64[i]
-        } else {
-            reward_infos[i]
-                .reward_growth_global_x64
-                .checked_sub(tick_lower.reward_growths_outside_x64[i])
-                .unwrap()
-        };
-
-        let reward_growths_above = if tick_current_index < tick_upper.tick {
-            tick_upper.reward_growths_outside_x64[i]
-        } else {
-            reward_infos[i]
-                .reward_growth_global_x64
-                .checked_sub(tick_upper.reward_growths_outside_x64[i])
-                .unwrap()
-        };
-        reward_growths_inside[i] = reward_infos[i]
-            .reward_growth_global_x64
-            .wrapping_sub(reward_growths_below)
-            .wrapping_sub(reward_growths_above);
+        reward_growths_inside[i] = get_reward_growth_inside(
+            tick_lower.tick,
+            tick_lower.reward_growths_outside_x64[i],
+            tick_upper.tick,
+            tick_upper.reward_growths_outside_x64[i],
+            tick_current_index,
+            reward_infos[i].reward_growth_global_x64,
+        );
         #[cfg(feature = "enable-log")]
         msg!(
-            "get_reward_growths_inside,i:{},reward_growth_global:{},reward_growth_below:{},reward_growth_above:{}, reward_growth_inside:{}",
+            "get_reward_growths_inside,i:{},reward_growth_global:{}, reward_growth_inside:{}",
             i,
             identity(reward_infos[i].reward_growth_global_x64),
-            reward_growths_below,
-            reward_growths_above,
             reward_growths_inside[i]
         );
     }
@@ -488,6 +581,62 @@ pub fn get_reward_growths_inside(
     reward_growths_inside
 }
 
+/// Calculates the growth of a single reward inside of tick_lower and tick_upper from the reward
+/// growths outside of the two ticks
+pub fn get_reward_growth_inside(
+    tick_lower: i32,
+    reward_growth_outside_lower_x64: u128,
+    tick_upper: i32,
+    reward_growth_outside_upper_x64: u128,
+    tick_current_index: i32,
+    reward_growth_global_x64: u128,
+) -> u128 {
+    let reward_growth_below = if tick_current_index >= tick_lower {
+        reward_growth_outside_lower_x64
+    } else {
+        reward_growth_global_x64
+            .checked_sub(reward_growth_outside_lower_x64)
+            .unwrap()
+    };
+
+    let reward_growth_above = if tick_current_index < tick_upper {
+        reward_growth_outside_upper_x64
+    } else {
+        reward_growth_global_x64
+            .checked_sub(reward_growth_outside_upper_x64)
+            .unwrap()
+    };
+    reward_growth_global_x64
+        .wrapping_sub(reward_growth_below)
+        .wrapping_sub(reward_growth_above)
+}
+
+/// Calculates the seconds per unit of in-range liquidity spent inside of tick_lower and
+/// tick_upper, Q64.64. Only the difference between two reads is meaningful, a position was in range
+/// for `liquidity * delta / 2**64` seconds.
+pub fn get_seconds_per_liquidity_inside(
+    tick_lower: &TickState,
+    tick_upper: &TickState,
+    tick_current: i32,
+    seconds_per_liquidity_cumulative_x64: u128,
+) -> u128 {
+    let seconds_per_liquidity_below_x64 = if tick_current >= tick_lower.tick {
+        tick_lower.seconds_per_liquidity_outside_x64
+    } else {
+        seconds_per_liquidity_cumulative_x64
+            .wrapping_sub(tick_lower.seconds_per_liquidity_outside_x64)
+    };
+    let seconds_per_liquidity_above_x64 = if tick_current < tick_upper.tick {
+        tick_upper.seconds_per_liquidity_outside_x64
+    } else {
+        seconds_per_liquidity_cumulative_x64
+            .wrapping_sub(tick_upper.seconds_per_liquidity_outside_x64)
+    };
+    seconds_per_liquidity_cumulative_x64
+        .wrapping_sub(seconds_per_liquidity_below_x64)
+        .wrapping_sub(seconds_per_liquidity_above_x64)
+}
+
 pub fn check_tick_array_start_index(
     tick_array_start_index: i32,
     tick_index: i32,
@@ -823,12 +972,14 @@ pub mod tick_array_test {
                     fee_growth_global_0_x64,
                     fee_growth_global_1_x64,
                     &[RewardInfo::default(); 3],
+                    0,
                 );
             } else {
                 tick_upper.cross(
                     fee_growth_global_0_x64,
                     fee_growth_global_1_x64,
                     &[RewardInfo::default(); 3],
+                    0,
                 );
             }
 
@@ -1159,9 +1310,9 @@ pub mod tick_array_test {
 
             reward_growth_global_x64 = reward_growth_global_x64 + reward_growth_global_delta;
             if cross_tick_lower {
-                tick_lower.cross(0, 0, &build_reward_infos(reward_growth_global_x64));
+                tick_lower.cross(0, 0, &build_reward_infos(reward_growth_global_x64), 0);
             } else {
-                tick_upper.cross(0, 0, &build_reward_infos(reward_growth_global_x64));
+                tick_upper.cross(0, 0, &build_reward_infos(reward_growth_global_x64), 0);
             }
 
             tick_current = target_tick_current;
@@ -1307,6 +1458,179 @@ pub mod tick_array_test {
             assert_eq!(reward_frowth_inside_delta, 500);
         }
     }
+
+    mod get_seconds_per_liquidity_inside_test {
+        use super::*;
+        use std::convert::identity;
+
+        fn build_tick(
+            tick: i32,
+            tick_current: i32,
+            seconds_per_liquidity_cumulative_x64: u128,
+        ) -> TickState {
+            let mut new_tick = TickState::default();
+            new_tick.tick = tick;
+            new_tick
+                .update(
+                    tick_current,
+                    100,
+                    0,
+                    0,
+                    false,
+                    &[RewardInfo::default(); REWARD_NUM],
+                    seconds_per_liquidity_cumulative_x64,
+                )
+                .unwrap();
+            new_tick
+        }
+
+        #[test]
+        fn in_range_then_cross_upper_test() {
+            let mut tick_lower = build_tick(-10, 0, 100);
+            let mut tick_upper = build_tick(10, 0, 100);
+            assert_eq!(identity(tick_lower.seconds_per_liquidity_outside_x64), 100);
+            assert_eq!(identity(tick_upper.seconds_per_liquidity_outside_x64), 0);
+            assert_eq!(
+                get_seconds_per_liquidity_inside(&tick_lower, &tick_upper, 0, 150),
+                50
+            );
+
+            // the price leaves the range at 200 and nothing accrues inside since
+            tick_upper.cross(0, 0, &[RewardInfo::default(); REWARD_NUM], 200);
+            assert_eq!(
+                get_seconds_per_liquidity_inside(&tick_lower, &tick_upper, 20, 260),
+                100
+            );
+
+            // and comes back at 300
+            tick_upper.cross(0, 0, &[RewardInfo::default(); REWARD_NUM], 300);
+            assert_eq!(
+                get_seconds_per_liquidity_inside(&tick_lower, &tick_upper, 0, 320),
+                120
+            );
+            tick_lower.cross(0, 0, &[RewardInfo::default(); REWARD_NUM], 320);
+            assert_eq!(
+                get_seconds_per_liquidity_inside(&tick_lower, &tick_upper, -20, 400),
+                120
+            );
+        }
+
+        #[test]
+        fn initialized_without_observation_test() {
+            // ticks initialized with zero only shift the value, the difference is still right
+            let mut tick_lower = build_tick(-10, 0, 0);
+            let mut tick_upper = build_tick(10, 0, 0);
+            let inside_before = get_seconds_per_liquidity_inside(&tick_lower, &tick_upper, 0, 100);
+            tick_lower.cross(0, 0, &[RewardInfo::default(); REWARD_NUM], 150);
+            let inside_after = get_seconds_per_liquidity_inside(&tick_lower, &tick_upper, -20, 400);
+            assert_eq!(inside_after.wrapping_sub(inside_before), 50);
+            tick_lower.cross(0, 0, &[RewardInfo::default(); REWARD_NUM], 500);
+            tick_upper.cross(0, 0, &[RewardInfo::default(); REWARD_NUM], u128::MAX);
+            let inside_after = get_seconds_per_liquidity_inside(&tick_lower, &tick_upper, 20, 10);
+            assert_eq!(
+                inside_after.wrapping_sub(inside_before),
+                50 + (u128::MAX - 500)
+            );
+        }
+    }
+
+    mod fill_orders_test {
+        use super::*;
+        use std::convert::identity;
+
+        fn build_tick_with_orders(tick: i32, order_amount: u64) -> TickState {
+            let mut new_tick = TickState::default();
+            new_tick.tick = tick;
+            new_tick.order_amount = order_amount;
+            new_tick.order_count = 1;
+            new_tick
+        }
+
+        #[test]
+        fn order_tick_is_initialized_test() {
+            let mut tick = build_tick_with_orders(0, 0);
+            assert!(tick.is_initialized());
+            // removing the last liquidity does not flip a tick that still has orders
+            let flipped = tick
+                .update(0, 100, 0, 0, false, &[RewardInfo::default(); REWARD_NUM], 0)
+                .unwrap();
+            assert!(!flipped);
+            let flipped = tick
+                .update(
+                    0,
+                    -100,
+                    0,
+                    0,
+                    false,
+                    &[RewardInfo::default(); REWARD_NUM],
+                    0,
+                )
+                .unwrap();
+            assert!(!flipped);
+            assert!(tick.is_initialized());
+        }
+
+        #[test]
+        fn partial_fill_base_input_test() {
+            // price of tick 0 is 1
+            let mut tick = build_tick_with_orders(0, 1000);
+            let (amount_in, amount_out) = tick.fill_orders(400, true, true).unwrap();
+            assert_eq!(amount_in, 400);
+            assert_eq!(amount_out, 400);
+            assert_eq!(identity(tick.order_amount), 600);
+            assert_eq!(identity(tick.order_phase), 0);
+            // rounded up
+            assert_eq!(
+                tick.order_remaining_ratio_x64(),
+                fixed_point_64::Q64 * 6 / 10 + 1
+            );
+        }
+
+        #[test]
+        fn full_fill_base_input_test() {
+            let mut tick = build_tick_with_orders(0, 1000);
+            let (amount_in, amount_out) = tick.fill_orders(5000, false, true).unwrap();
+            assert_eq!(amount_in, 1000);
+            assert_eq!(amount_out, 1000);
+            assert_eq!(identity(tick.order_amount), 0);
+            assert_eq!(identity(tick.order_phase), 1);
+            assert_eq!(tick.order_remaining_ratio_x64(), fixed_point_64::Q64);
+        }
+
+        #[test]
+        fn fill_base_output_test() {
+            let mut tick = build_tick_with_orders(0, 1000);
+            let (amount_in, amount_out) = tick.fill_orders(300, true, false).unwrap();
+            assert_eq!(amount_in, 300);
+            assert_eq!(amount_out, 300);
+            let (amount_in, amount_out) = tick.fill_orders(5000, true, false).unwrap();
+            assert_eq!(amount_in, 700);
+            assert_eq!(amount_out, 700);
+            assert_eq!(identity(tick.order_phase), 1);
+        }
+
+        #[test]
+        fn fill_dust_input_test() {
+            // price of tick -69082 is about 0.001, one unit of token_0 buys no token_1
+            let mut tick = build_tick_with_orders(-69082, 1000);
+            let (amount_in, amount_out) = tick.fill_orders(1, true, true).unwrap();
+            assert_eq!(amount_in, 1);
+            assert_eq!(amount_out, 0);
+            assert_eq!(identity(tick.order_amount), 1000);
+            assert_eq!(tick.order_remaining_ratio_x64(), fixed_point_64::Q64);
+        }
+
+        #[test]
+        fn cancel_keeps_ratio_test() {
+            let mut tick = build_tick_with_orders(0, 1000);
+            tick.consume_orders(500, false);
+            assert_eq!(identity(tick.order_amount), 500);
+            assert_eq!(tick.order_remaining_ratio_x64(), fixed_point_64::Q64);
+            tick.consume_orders(500, false);
+            assert_eq!(identity(tick.order_phase), 1);
+        }
+    }
+
     mod tick_array_layout_test {
         use super::*;
         use anchor_lang::Discriminator;
@@ -1333,15 +1657,11 @@ pub mod tick_array_test {
                 0x11223344556600778899aabbccddeeff,
                 0x11223344556677008899aabbccddeeff,
             ];
-            let mut tick_padding: [u32; 13] = [0u32; 13];
-            let mut tick_padding_data = [0u8; 4 * 13];
-            let mut offset = 0;
-            for i in 0..13 {
-                tick_padding[i] = u32::MAX - 3 * i as u32;
-                tick_padding_data[offset..offset + 4]
-                    .copy_from_slice(&tick_padding[i].to_le_bytes());
-                offset += 4;
-            }
+            let order_phase: u64 = 0x1122334455667788;
+            let order_amount: u64 = 0x8877665544332211;
+            let order_remaining_x64: u128 = 0x11223344556677880099aabbccddeeff;
+            let order_count: u32 = 0x12345678;
+            let seconds_per_liquidity_outside_x64: u128 = 0x11223344556677889900aabbccddeeff;
 
             let mut tick_data = [0u8; TickState::LEN];
             let mut offset = 0;
@@ -1360,8 +1680,17 @@ pub mod tick_array_test {
                     .copy_from_slice(&reward_growths_outside_x64[i].to_le_bytes());
                 offset += 16;
             }
-            tick_data[offset..offset + 4 * 13].copy_from_slice(&tick_padding_data);
-            offset += 4 * 13;
+            tick_data[offset..offset + 8].copy_from_slice(&order_phase.to_le_bytes());
+            offset += 8;
+            tick_data[offset..offset + 8].copy_from_slice(&order_amount.to_le_bytes());
+            offset += 8;
+            tick_data[offset..offset + 16].copy_from_slice(&order_remaining_x64.to_le_bytes());
+            offset += 16;
+            tick_data[offset..offset + 4].copy_from_slice(&order_count.to_le_bytes());
+            offset += 4;
+            tick_data[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_outside_x64.to_le_bytes());
+            offset += 16;
             assert_eq!(offset, tick_data.len());
             assert_eq!(tick_data.len(), core::mem::size_of::<TickState>());
 
@@ -1419,8 +1748,20 @@ pub mod tick_array_test {
                     unpack_reward_growths_outside_x64,
                     reward_growths_outside_x64
                 );
-                let unpack_tick_padding = tick_item.padding;
-                assert_eq!(unpack_tick_padding, tick_padding);
+                let unpack_order_phase = tick_item.order_phase;
+                assert_eq!(unpack_order_phase, order_phase);
+                let unpack_order_amount = tick_item.order_amount;
+                assert_eq!(unpack_order_amount, order_amount);
+                let unpack_order_remaining_x64 = tick_item.order_remaining_x64;
+                assert_eq!(unpack_order_remaining_x64, order_remaining_x64);
+                let unpack_order_count = tick_item.order_count;
+                assert_eq!(unpack_order_count, order_count);
+                let unpack_seconds_per_liquidity_outside_x64 =
+                    tick_item.seconds_per_liquidity_outside_x64;
+                assert_eq!(
+                    unpack_seconds_per_liquidity_outside_x64,
+                    seconds_per_liquidity_outside_x64
+                );
             }
             let unpack_initialized_tick_count = unpack_data.initialized_tick_count;
             assert_eq!(unpack_initialized_tick_count, initialized_tick_count);
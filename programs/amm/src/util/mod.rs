@@ -6,3 +6,11 @@ pub use system::*;
 
 pub mod account_load;
 pub use account_load::*;
+
+pub mod swap_hook;
+pub use swap_hook::*;
+
+#[cfg(feature = "cpi")]
+pub mod return_data;
+#[cfg(feature = "cpi")]
+pub use return_data::*;
@@ -0,0 +1,35 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+
+/// Decodes the return data of the last instruction of this program invoked, none if the return
+/// data was set by another program
+pub fn decode_return_data<T: AnchorDeserialize>() -> Option<T> {
+    let (program_id, data) = get_return_data()?;
+    if program_id != crate::id() {
+        return None;
+    }
+    T::try_from_slice(&data).ok()
+}
+
+impl SwapResult {
+    /// Reads the result of a swap_v2 invoked by CPI
+    pub fn from_return_data() -> Option<Self> {
+        decode_return_data()
+    }
+}
+
+impl LiquidityChangeResult {
+    /// Reads the result of an open_position_v2, open_position_single_sided, increase_liquidity_v2
+    /// or decrease_liquidity_v2 invoked by CPI
+    pub fn from_return_data() -> Option<Self> {
+        decode_return_data()
+    }
+}
+
+impl QuoteSwapResult {
+    /// Reads the result of a quote_swap invoked by CPI
+    pub fn from_return_data() -> Option<Self> {
+        decode_return_data()
+    }
+}
@@ -0,0 +1,179 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+
+/// The anchor discriminator of the `before_swap` instruction of the swap hook program
+pub const BEFORE_SWAP_HOOK: [u8; 8] = [227, 59, 240, 68, 164, 9, 29, 254];
+/// The anchor discriminator of the `after_swap` instruction of the swap hook program
+pub const AFTER_SWAP_HOOK: [u8; 8] = [235, 215, 232, 183, 152, 109, 5, 35];
+/// The max number of accounts a swap hook program can be passed
+pub const MAX_SWAP_HOOK_ACCOUNTS: u8 = 8;
+
+/// The payload a swap hook program is called with, following the anchor discriminator of the
+/// hook instruction. Before the swap the after fields and the amounts are zero.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct SwapHookParams {
+    pub zero_for_one: bool,
+    pub is_base_input: bool,
+    pub amount_specified: u64,
+    pub sqrt_price_x64_before: u128,
+    pub tick_current_before: i32,
+    pub sqrt_price_x64_after: u128,
+    pub tick_current_after: i32,
+    /// The amounts of token_0 and token_1 swapped by the pool
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+impl SwapHookParams {
+    pub fn new(
+        pool_state: &PoolState,
+        zero_for_one: bool,
+        is_base_input: bool,
+        amount_specified: u64,
+    ) -> Self {
+        Self {
+            zero_for_one,
+            is_base_input,
+            amount_specified,
+            sqrt_price_x64_before: pool_state.sqrt_price_x64,
+            tick_current_before: pool_state.tick_current,
+            ..Default::default()
+        }
+    }
+
+    /// Records the price after the swap and the amounts swapped
+    pub fn set_after_swap(&mut self, pool_state: &PoolState, amount_0: u64, amount_1: u64) {
+        self.sqrt_price_x64_after = pool_state.sqrt_price_x64;
+        self.tick_current_after = pool_state.tick_current;
+        self.amount_0 = amount_0;
+        self.amount_1 = amount_1;
+    }
+}
+
+/// Splits the remaining accounts of a swap at the swap hook program of the pool. Returns the
+/// accounts of the swap and, if the pool has a swap hook, the hook program followed by the
+/// `swap_hook_account_count` accounts of the hook. Accounts after them are not passed to the hook.
+pub fn split_swap_hook_accounts<'c, 'info>(
+    pool_state: &PoolState,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<(&'c [AccountInfo<'info>], Option<&'c [AccountInfo<'info>]>)> {
+    if pool_state.swap_hook_program == Pubkey::default() {
+        return Ok((remaining_accounts, None));
+    }
+    let hook_index = remaining_accounts
+        .iter()
+        .position(|account_info| account_info.key() == pool_state.swap_hook_program)
+        .ok_or(ErrorCode::MissingSwapHookAccount)?;
+    let (swap_accounts, hook_accounts) = remaining_accounts.split_at(hook_index);
+    let hook_accounts_len = 1 + usize::from(pool_state.swap_hook_account_count);
+    require_gte!(
+        hook_accounts.len(),
+        hook_accounts_len,
+        ErrorCode::NotEnoughSwapHookAccounts
+    );
+    Ok((swap_accounts, Some(&hook_accounts[..hook_accounts_len])))
+}
+
+/// Calls the swap hook program instruction with the `discriminator` with the payer and the pool,
+/// both read only and not signing, followed by the hook accounts. The hook rejects the swap by
+/// failing.
+pub fn invoke_swap_hook<'info>(
+    hook_accounts: &[AccountInfo<'info>],
+    payer: &AccountInfo<'info>,
+    pool_state: &AccountInfo<'info>,
+    discriminator: [u8; 8],
+    params: &SwapHookParams,
+) -> Result<()> {
+    let (hook_program, accounts) = hook_accounts.split_first().unwrap();
+    let mut data = discriminator.to_vec();
+    params.serialize(&mut data)?;
+
+    let mut account_metas = vec![
+        AccountMeta::new_readonly(payer.key(), false),
+        AccountMeta::new_readonly(pool_state.key(), false),
+    ];
+    account_metas.extend(accounts.iter().map(|account_info| {
+        if account_info.is_writable {
+            AccountMeta::new(account_info.key(), account_info.is_signer)
+        } else {
+            AccountMeta::new_readonly(account_info.key(), account_info.is_signer)
+        }
+    }));
+    let mut account_infos = vec![payer.clone(), pool_state.clone()];
+    account_infos.extend_from_slice(accounts);
+    account_infos.push(hook_program.clone());
+    invoke(
+        &Instruction {
+            program_id: hook_program.key(),
+            accounts: account_metas,
+            data,
+        },
+        &account_infos,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod swap_hook_test {
+    use super::*;
+
+    #[test]
+    fn split_swap_hook_accounts_test() {
+        let keys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let owner = Pubkey::new_unique();
+        let mut lamports = [0u64; 4];
+        let mut data = [[0u8; 0]; 4];
+        let account_infos = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(key, false, false, lamports, data, &owner, false, 0)
+            })
+            .collect::<Vec<_>>();
+
+        let mut pool_state = PoolState::default();
+        let (swap_accounts, hook_accounts) =
+            split_swap_hook_accounts(&pool_state, &account_infos).unwrap();
+        assert_eq!(swap_accounts.len(), 4);
+        assert!(hook_accounts.is_none());
+
+        // the hook is only passed its own accounts
+        pool_state.swap_hook_program = keys[1];
+        pool_state.swap_hook_account_count = 1;
+        let (swap_accounts, hook_accounts) =
+            split_swap_hook_accounts(&pool_state, &account_infos).unwrap();
+        assert_eq!(swap_accounts.len(), 1);
+        let hook_accounts = hook_accounts.unwrap();
+        assert_eq!(hook_accounts.len(), 2);
+        assert_eq!(*hook_accounts[0].key, keys[1]);
+        assert_eq!(*hook_accounts[1].key, keys[2]);
+
+        pool_state.swap_hook_account_count = 3;
+        assert!(split_swap_hook_accounts(&pool_state, &account_infos).is_err());
+
+        // the hook program must be passed
+        pool_state.swap_hook_program = Pubkey::new_unique();
+        assert!(split_swap_hook_accounts(&pool_state, &account_infos).is_err());
+    }
+
+    #[test]
+    fn swap_hook_discriminator_test() {
+        use anchor_lang::solana_program::hash::hash;
+        assert_eq!(
+            BEFORE_SWAP_HOOK,
+            hash(b"global:before_swap").to_bytes()[..8]
+        );
+        assert_eq!(AFTER_SWAP_HOOK, hash(b"global:after_swap").to_bytes()[..8]);
+    }
+}
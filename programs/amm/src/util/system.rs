@@ -1,3 +1,4 @@
+use crate::error::ErrorCode;
 use anchor_lang::{prelude::*, system_program};
 
 pub fn create_or_allocate_account<'a>(
@@ -55,6 +56,33 @@ pub fn create_or_allocate_account<'a>(
     Ok(())
 }
 
+/// Closes an account owned by this program, its lamports go to `sol_destination`
+pub fn close_account<'info>(
+    info: &AccountInfo<'info>,
+    sol_destination: &AccountInfo<'info>,
+) -> Result<()> {
+    let dest_starting_lamports = sol_destination.lamports();
+    **sol_destination.lamports.borrow_mut() =
+        dest_starting_lamports.checked_add(info.lamports()).unwrap();
+    **info.lamports.borrow_mut() = 0;
+    info.assign(&system_program::ID);
+    info.resize(0).map_err(Into::into)
+}
+
+/// Fails once the unix timestamp `deadline` of a transaction has passed, no deadline never expires
+pub fn check_deadline(deadline: Option<i64>) -> Result<()> {
+    match deadline {
+        Some(deadline) => check_deadline_at(deadline, Clock::get()?.unix_timestamp),
+        None => Ok(()),
+    }
+}
+
+/// Fails if the unix timestamp `deadline` is earlier than `unix_timestamp`
+pub fn check_deadline_at(deadline: i64, unix_timestamp: i64) -> Result<()> {
+    require_gte!(deadline, unix_timestamp, ErrorCode::TransactionTooOld);
+    Ok(())
+}
+
 #[cfg(not(any(test, feature = "client")))]
 pub fn get_recent_epoch() -> Result<u64> {
     Ok(Clock::get()?.epoch)
@@ -69,3 +97,20 @@ pub fn get_recent_epoch() -> Result<u64> {
         .as_secs()
         / (2 * 24 * 3600))
 }
+
+#[cfg(test)]
+mod system_test {
+    use super::*;
+
+    #[test]
+    fn check_deadline_test() {
+        assert!(check_deadline(None).is_ok());
+        assert!(check_deadline_at(100, 99).is_ok());
+        // the deadline itself is still in time
+        assert!(check_deadline_at(100, 100).is_ok());
+        assert_eq!(
+            check_deadline_at(100, 101).unwrap_err(),
+            ErrorCode::TransactionTooOld.into()
+        );
+    }
+}